@@ -1,13 +1,17 @@
 use crate::{
     error::ContractError,
     storage::{
-        get_config, get_lp_vec, is_initialized, save_config, save_lp_vec,
-        save_lp_vec_with_tuple_as_key, set_initialized, Asset, Config, DataKey, LiquidityPoolInfo,
-        LpPortfolio, PairTupleKey, StakePortfolio, StakedResponse, UserPortfolio,
+        get_admin_change, get_config, get_default_pool_for_pair, get_lp_vec, get_pool_type,
+        get_pools_for_pair, is_initialized, remove_admin_change, save_admin_change, save_config,
+        save_lp_vec, save_lp_vec_with_tuple_as_key, save_pool_type, save_pools_for_pair,
+        set_initialized, Asset, Config, DataKey, LiquidityPoolInfo, LpPortfolio, PairTupleKey,
+        PairType, PoolInfo, StableLiquidityPoolInfo, StakePortfolio, StakedResponse, UserPortfolio,
     },
     utils::{deploy_lp_contract, deploy_multihop_contract},
 };
-use phoenix::utils::{LiquidityPoolInitInfo, StakeInitInfo, TokenInitInfo};
+use phoenix::utils::{
+    AdminChange, LiquidityPoolInitInfo, StableLiquidityPoolInitInfo, StakeInitInfo, TokenInitInfo,
+};
 use phoenix::validate_bps;
 use soroban_sdk::{
     contract, contractimpl, contractmeta, log, panic_with_error, vec, Address, BytesN, Env,
@@ -27,18 +31,23 @@ pub trait FactoryTrait {
         admin: Address,
         multihop_wasm_hash: BytesN<32>,
         lp_wasm_hash: BytesN<32>,
+        stable_wasm_hash: BytesN<32>,
         stake_wasm_hash: BytesN<32>,
         token_wasm_hash: BytesN<32>,
         whitelisted_accounts: Vec<Address>,
         lp_token_decimals: u32,
     );
 
+    // Deploys a new liquidity pool of `pool_type`. `amp` is the amplification coefficient of the
+    // stable pool and is ignored for XYK pools
     fn create_liquidity_pool(
         env: Env,
         sender: Address,
         lp_init_info: LiquidityPoolInitInfo,
         share_token_name: String,
         share_token_symbol: String,
+        pool_type: PairType,
+        amp: Option<u64>,
     ) -> Address;
 
     fn update_whitelisted_accounts(
@@ -48,14 +57,34 @@ pub trait FactoryTrait {
         to_remove: Vec<Address>,
     );
 
+    // Pauses swaps and deposits in every liquidity pool created by this factory
+    fn pause_all_pools(env: Env, sender: Address);
+
+    // Resumes swaps and deposits in every liquidity pool created by this factory
+    fn unpause_all_pools(env: Env, sender: Address);
+
+    // Allows the admin to propose `new_admin` as the next admin. The proposal has to be accepted
+    // by `new_admin` before the ledger timestamp passes `expires_at`
+    fn propose_admin(env: Env, sender: Address, new_admin: Address, expires_at: u64);
+
+    // Allows the proposed admin to accept the admin role
+    fn accept_admin(env: Env, sender: Address);
+
+    // Allows the admin to cancel the pending admin proposal
+    fn cancel_admin_proposal(env: Env, sender: Address);
+
     fn query_pools(env: Env) -> Vec<Address>;
 
     fn query_pool_details(env: Env, pool_address: Address) -> LiquidityPoolInfo;
 
     fn query_all_pools_details(env: Env) -> Vec<LiquidityPoolInfo>;
 
+    // Returns the default pool of the pair, which is the first pool created for it
     fn query_for_pool_by_token_pair(env: Env, token_a: Address, token_b: Address) -> Address;
 
+    // Returns every pool created for the pair, in order of creation
+    fn query_pools_for_pair(env: Env, token_a: Address, token_b: Address) -> Vec<PoolInfo>;
+
     fn get_admin(env: Env) -> Address;
 
     fn get_config(env: Env) -> Config;
@@ -71,6 +100,7 @@ impl FactoryTrait for Factory {
         admin: Address,
         multihop_wasm_hash: BytesN<32>,
         lp_wasm_hash: BytesN<32>,
+        stable_wasm_hash: BytesN<32>,
         stake_wasm_hash: BytesN<32>,
         token_wasm_hash: BytesN<32>,
         whitelisted_accounts: Vec<Address>,
@@ -100,6 +130,7 @@ impl FactoryTrait for Factory {
                 admin: admin.clone(),
                 multihop_address,
                 lp_wasm_hash,
+                stable_wasm_hash,
                 stake_wasm_hash,
                 token_wasm_hash,
                 whitelisted_accounts,
@@ -119,6 +150,8 @@ impl FactoryTrait for Factory {
         lp_init_info: LiquidityPoolInitInfo,
         share_token_name: String,
         share_token_symbol: String,
+        pool_type: PairType,
+        amp: Option<u64>,
     ) -> Address {
         sender.require_auth();
         if !get_config(&env).whitelisted_accounts.contains(sender) {
@@ -136,16 +169,32 @@ impl FactoryTrait for Factory {
         );
 
         let config = get_config(&env);
-        let lp_wasm_hash = config.lp_wasm_hash;
+        let lp_wasm_hash = match pool_type {
+            PairType::Xyk => config.lp_wasm_hash,
+            PairType::Stable => config.stable_wasm_hash,
+        };
         let stake_wasm_hash = config.stake_wasm_hash;
         let token_wasm_hash = config.token_wasm_hash;
 
-        let lp_contract_address = deploy_lp_contract(
-            &env,
-            lp_wasm_hash,
-            &lp_init_info.token_init_info.token_a,
-            &lp_init_info.token_init_info.token_b,
-        );
+        let token_a = &lp_init_info.token_init_info.token_a;
+        let token_b = &lp_init_info.token_init_info.token_b;
+
+        // a pair can have several pools, but only one per pool type and fee tier
+        let mut pair_pools = get_pools_for_pair(&env, token_a, token_b);
+        for pool_address in pair_pools.iter() {
+            if get_pool_type(&env, &pool_address) == pool_type
+                && query_pool_info(&env, &pool_address).total_fee_bps == lp_init_info.swap_fee_bps
+            {
+                log!(
+                    &env,
+                    "Factory: Create Liquidity Pool: A pool with the same type and fee already exists for this pair"
+                );
+                panic_with_error!(&env, ContractError::PoolAlreadyExists);
+            }
+        }
+
+        let lp_contract_address =
+            deploy_lp_contract(&env, lp_wasm_hash, token_a, token_b, pair_pools.len());
 
         validate_bps!(
             lp_init_info.swap_fee_bps,
@@ -156,27 +205,63 @@ impl FactoryTrait for Factory {
 
         let factory_addr = env.current_contract_address();
         let init_fn: Symbol = Symbol::new(&env, "initialize");
-        let init_fn_args: Vec<Val> = (
-            stake_wasm_hash,
-            token_wasm_hash,
-            lp_init_info.clone(),
-            factory_addr,
-            config.lp_token_decimals,
-            share_token_name,
-            share_token_symbol,
-        )
-            .into_val(&env);
+        // the stable pool takes the amplification coefficient right after the wasm hashes
+        let init_fn_args: Vec<Val> = match pool_type {
+            PairType::Xyk => (
+                stake_wasm_hash,
+                token_wasm_hash,
+                lp_init_info.clone(),
+                factory_addr,
+                config.lp_token_decimals,
+                share_token_name,
+                share_token_symbol,
+            )
+                .into_val(&env),
+            PairType::Stable => {
+                let amp = amp.unwrap_or_else(|| {
+                    log!(
+                        &env,
+                        "Factory: Create Liquidity Pool: Amplification coefficient must be set for stable pools"
+                    );
+                    panic_with_error!(&env, ContractError::StableAmpNotSet);
+                });
+                let stable_init_info = StableLiquidityPoolInitInfo {
+                    admin: lp_init_info.admin.clone(),
+                    swap_fee_bps: lp_init_info.swap_fee_bps,
+                    fee_recipient: lp_init_info.fee_recipient.clone(),
+                    max_allowed_slippage_bps: lp_init_info.max_allowed_slippage_bps,
+                    max_allowed_spread_bps: lp_init_info.max_allowed_spread_bps,
+                    tokens: vec![&env, token_a.clone(), token_b.clone()],
+                    stake_init_info: lp_init_info.stake_init_info.clone(),
+                };
+                (
+                    stake_wasm_hash,
+                    token_wasm_hash,
+                    amp,
+                    stable_init_info,
+                    factory_addr,
+                    config.lp_token_decimals,
+                    share_token_name,
+                    share_token_symbol,
+                )
+                    .into_val(&env)
+            }
+        };
 
         env.invoke_contract::<Val>(&lp_contract_address, &init_fn, init_fn_args);
+        save_pool_type(&env, &lp_contract_address, pool_type);
 
         let mut lp_vec = get_lp_vec(&env);
 
         lp_vec.push_back(lp_contract_address.clone());
 
         save_lp_vec(&env, lp_vec);
-        let token_a = &lp_init_info.token_init_info.token_a;
-        let token_b = &lp_init_info.token_init_info.token_b;
-        save_lp_vec_with_tuple_as_key(&env, (token_a, token_b), &lp_contract_address);
+
+        pair_pools.push_back(lp_contract_address.clone());
+        save_pools_for_pair(&env, token_a, token_b, &pair_pools);
+        if get_default_pool_for_pair(&env, token_a, token_b).is_none() {
+            save_lp_vec_with_tuple_as_key(&env, (token_a, token_b), &lp_contract_address);
+        }
 
         env.events()
             .publish(("create", "liquidity_pool"), &lp_contract_address);
@@ -225,28 +310,138 @@ impl FactoryTrait for Factory {
         )
     }
 
+    fn pause_all_pools(env: Env, sender: Address) {
+        sender.require_auth();
+
+        if get_config(&env).admin != sender {
+            log!(&env, "Factory: Pause all pools: You are not authorized!");
+            panic_with_error!(&env, ContractError::NotAuthorized);
+        };
+
+        set_paused_for_all_pools(&env, "pause");
+
+        env.events().publish(("pause_all_pools", "sender"), sender);
+    }
+
+    fn unpause_all_pools(env: Env, sender: Address) {
+        sender.require_auth();
+
+        if get_config(&env).admin != sender {
+            log!(&env, "Factory: Unpause all pools: You are not authorized!");
+            panic_with_error!(&env, ContractError::NotAuthorized);
+        };
+
+        set_paused_for_all_pools(&env, "unpause");
+
+        env.events()
+            .publish(("unpause_all_pools", "sender"), sender);
+    }
+
+    fn propose_admin(env: Env, sender: Address, new_admin: Address, expires_at: u64) {
+        sender.require_auth();
+
+        if sender != get_config(&env).admin {
+            log!(
+                &env,
+                "Factory: Propose admin: Only the admin can propose a new admin"
+            );
+            panic_with_error!(&env, ContractError::NotAuthorized);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            log!(
+                &env,
+                "Factory: Propose admin: Proposal expiration must be in the future"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeExpired);
+        }
+
+        save_admin_change(
+            &env,
+            &AdminChange {
+                new_admin: new_admin.clone(),
+                expires_at,
+            },
+        );
+
+        env.events()
+            .publish(("propose_admin", "new_admin"), new_admin);
+        env.events()
+            .publish(("propose_admin", "expires_at"), expires_at);
+    }
+
+    fn accept_admin(env: Env, sender: Address) {
+        sender.require_auth();
+
+        let admin_change = get_admin_change(&env).unwrap_or_else(|| {
+            log!(&env, "Factory: Accept admin: No admin change in place");
+            panic_with_error!(&env, ContractError::AdminChangeNotFound);
+        });
+        if sender != admin_change.new_admin {
+            log!(
+                &env,
+                "Factory: Accept admin: Only the proposed admin can accept the admin role"
+            );
+            panic_with_error!(&env, ContractError::NotAuthorized);
+        }
+        if env.ledger().timestamp() > admin_change.expires_at {
+            log!(
+                &env,
+                "Factory: Accept admin: Admin change proposal has expired"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeExpired);
+        }
+
+        remove_admin_change(&env);
+        save_config(
+            &env,
+            Config {
+                admin: sender.clone(),
+                ..get_config(&env)
+            },
+        );
+
+        env.events().publish(("accept_admin", "new_admin"), sender);
+    }
+
+    fn cancel_admin_proposal(env: Env, sender: Address) {
+        sender.require_auth();
+
+        if sender != get_config(&env).admin {
+            log!(
+                &env,
+                "Factory: Cancel admin proposal: Only the admin can cancel the proposal"
+            );
+            panic_with_error!(&env, ContractError::NotAuthorized);
+        }
+
+        let admin_change = get_admin_change(&env).unwrap_or_else(|| {
+            log!(
+                &env,
+                "Factory: Cancel admin proposal: No admin change in place"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeNotFound);
+        });
+        remove_admin_change(&env);
+
+        env.events().publish(
+            ("cancel_admin_proposal", "new_admin"),
+            admin_change.new_admin,
+        );
+    }
+
     fn query_pools(env: Env) -> Vec<Address> {
         get_lp_vec(&env)
     }
 
     fn query_pool_details(env: Env, pool_address: Address) -> LiquidityPoolInfo {
-        let pool_response: LiquidityPoolInfo = env.invoke_contract(
-            &pool_address,
-            &Symbol::new(&env, "query_pool_info_for_factory"),
-            Vec::new(&env),
-        );
-        pool_response
+        query_pool_info(&env, &pool_address)
     }
 
     fn query_all_pools_details(env: Env) -> Vec<LiquidityPoolInfo> {
         let all_lp_vec_addresses = get_lp_vec(&env);
         let mut result = Vec::new(&env);
         for address in all_lp_vec_addresses {
-            let pool_response: LiquidityPoolInfo = env.invoke_contract(
-                &address,
-                &Symbol::new(&env, "query_pool_info_for_factory"),
-                Vec::new(&env),
-            );
+            let pool_response = query_pool_info(&env, &address);
 
             result.push_back(pool_response);
         }
@@ -280,6 +475,24 @@ impl FactoryTrait for Factory {
         panic_with_error!(&env, ContractError::LiquidityPoolNotFound);
     }
 
+    fn query_pools_for_pair(env: Env, token_a: Address, token_b: Address) -> Vec<PoolInfo> {
+        let mut pool_addresses = get_pools_for_pair(&env, &token_a, &token_b);
+        if pool_addresses.is_empty() {
+            pool_addresses = get_pools_for_pair(&env, &token_b, &token_a);
+        }
+
+        let mut result = Vec::new(&env);
+        for pool_address in pool_addresses {
+            result.push_back(PoolInfo {
+                pool_type: get_pool_type(&env, &pool_address),
+                total_fee_bps: query_pool_info(&env, &pool_address).total_fee_bps,
+                pool_address,
+            });
+        }
+
+        result
+    }
+
     fn get_admin(env: Env) -> Address {
         get_config(&env).admin
     }
@@ -297,11 +510,7 @@ impl FactoryTrait for Factory {
         let mut stake_portfolio: Vec<StakePortfolio> = Vec::new(&env);
 
         for address in initialized_pools {
-            let response: LiquidityPoolInfo = env.invoke_contract(
-                &address,
-                &Symbol::new(&env, "query_pool_info_for_factory"),
-                Vec::new(&env),
-            );
+            let response = query_pool_info(&env, &address);
 
             // get the lp share token balance for the user
             // if the user has any liquidity tokens in the pool add to the lp_portfolio
@@ -362,6 +571,41 @@ impl FactoryTrait for Factory {
     }
 }
 
+// XYK and stable pools return their own info types, so the response is decoded based on the
+// recorded pool type
+fn query_pool_info(env: &Env, pool_address: &Address) -> LiquidityPoolInfo {
+    let query_fn = Symbol::new(env, "query_pool_info_for_factory");
+    match get_pool_type(env, pool_address) {
+        PairType::Xyk => env.invoke_contract(pool_address, &query_fn, Vec::new(env)),
+        PairType::Stable => {
+            let info: StableLiquidityPoolInfo =
+                env.invoke_contract(pool_address, &query_fn, Vec::new(env));
+            // the factory only deploys stable pools of a token pair, the multihop route search
+            // relies on every pool having exactly two assets
+            if info.pool_response.assets.len() != 2 {
+                log!(
+                    env,
+                    "Factory: Query pool info: Stable pool does not hold exactly two assets"
+                );
+                panic_with_error!(env, ContractError::StablePoolNotAPair);
+            }
+            info.into()
+        }
+    }
+}
+
+// The pools accept the factory as the sender, so every pool can be paused in a single call
+fn set_paused_for_all_pools(env: &Env, function_name: &str) {
+    let factory_addr = env.current_contract_address();
+    for pool_address in get_lp_vec(env) {
+        env.invoke_contract::<Val>(
+            &pool_address,
+            &Symbol::new(env, function_name),
+            vec![env, factory_addr.into_val(env)],
+        );
+    }
+}
+
 fn validate_token_info(
     env: &Env,
     token_init_info: &TokenInitInfo,
@@ -11,4 +11,9 @@ pub enum ContractError {
     TokenABiggerThanTokenB = 5,
     MinStakeInvalid = 6,
     MinRewardInvalid = 7,
+    AdminChangeNotFound = 8,
+    AdminChangeExpired = 9,
+    StableAmpNotSet = 10,
+    PoolAlreadyExists = 11,
+    StablePoolNotAPair = 12,
 }
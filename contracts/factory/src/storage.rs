@@ -1,3 +1,4 @@
+use phoenix::utils::AdminChange;
 use soroban_sdk::{contracttype, Address, BytesN, ConversionError, Env, TryFromVal, Val, Vec};
 
 #[derive(Clone, Copy)]
@@ -6,6 +7,7 @@ pub enum DataKey {
     Config = 1,
     LpVec = 2,
     Initialized = 3,
+    AdminChange = 4,
 }
 
 #[derive(Clone)]
@@ -23,18 +25,50 @@ impl TryFromVal<Env, DataKey> for Val {
     }
 }
 
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum PairType {
+    Xyk = 0,
+    Stable = 1,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct PoolTypeKey {
+    pub(crate) pool_address: Address,
+}
+
+// Only the field names end up in the stored key, so they have to differ from `PairTupleKey`
+#[derive(Clone)]
+#[contracttype]
+pub struct PairPoolsKey {
+    pub(crate) pools_token_a: Address,
+    pub(crate) pools_token_b: Address,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Config {
     pub admin: Address,
     pub multihop_address: Address,
     pub lp_wasm_hash: BytesN<32>,
+    pub stable_wasm_hash: BytesN<32>,
     pub stake_wasm_hash: BytesN<32>,
     pub token_wasm_hash: BytesN<32>,
     pub whitelisted_accounts: Vec<Address>,
     pub lp_token_decimals: u32,
 }
 
+/// A single pool deployed for a token pair
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolInfo {
+    pub pool_address: Address,
+    pub pool_type: PairType,
+    pub total_fee_bps: i64,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct UserPortfolio {
@@ -86,6 +120,42 @@ pub struct LiquidityPoolInfo {
     pub total_fee_bps: i64,
 }
 
+/// The stable pool counterpart of `PoolResponse`, a stable pool can hold more than two assets.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StablePoolResponse {
+    /// The assets in the pool together with asset amounts
+    pub assets: Vec<Asset>,
+    /// The total amount of LP tokens currently issued
+    pub asset_lp_share: Asset,
+    /// The address of the Stake contract for the liquidity pool
+    pub stake_address: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StableLiquidityPoolInfo {
+    pub pool_address: Address,
+    pub pool_response: StablePoolResponse,
+    pub total_fee_bps: i64,
+}
+
+impl From<StableLiquidityPoolInfo> for LiquidityPoolInfo {
+    fn from(info: StableLiquidityPoolInfo) -> Self {
+        // the factory deploys stable pools of a token pair, which hold exactly two assets
+        LiquidityPoolInfo {
+            pool_address: info.pool_address,
+            pool_response: PoolResponse {
+                asset_a: info.pool_response.assets.get(0).unwrap(),
+                asset_b: info.pool_response.assets.get(1).unwrap(),
+                asset_lp_share: info.pool_response.asset_lp_share,
+                stake_address: info.pool_response.stake_address,
+            },
+            total_fee_bps: info.total_fee_bps,
+        }
+    }
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct StakedResponse {
@@ -112,6 +182,20 @@ pub fn get_config(env: &Env) -> Config {
         .expect("Config not set")
 }
 
+pub fn save_admin_change(env: &Env, admin_change: &AdminChange) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::AdminChange, admin_change);
+}
+
+pub fn get_admin_change(env: &Env) -> Option<AdminChange> {
+    env.storage().persistent().get(&DataKey::AdminChange)
+}
+
+pub fn remove_admin_change(env: &Env) {
+    env.storage().persistent().remove(&DataKey::AdminChange);
+}
+
 pub fn get_lp_vec(env: &Env) -> Vec<Address> {
     env.storage()
         .persistent()
@@ -137,6 +221,63 @@ pub fn save_lp_vec_with_tuple_as_key(
     )
 }
 
+pub fn get_default_pool_for_pair(
+    env: &Env,
+    token_a: &Address,
+    token_b: &Address,
+) -> Option<Address> {
+    env.storage().persistent().get(&PairTupleKey {
+        token_a: token_a.clone(),
+        token_b: token_b.clone(),
+    })
+}
+
+pub fn save_pools_for_pair(env: &Env, token_a: &Address, token_b: &Address, pools: &Vec<Address>) {
+    env.storage().persistent().set(
+        &PairPoolsKey {
+            pools_token_a: token_a.clone(),
+            pools_token_b: token_b.clone(),
+        },
+        pools,
+    );
+}
+
+// Pairs created before multiple pools per pair were supported only have their default pool
+pub fn get_pools_for_pair(env: &Env, token_a: &Address, token_b: &Address) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&PairPoolsKey {
+            pools_token_a: token_a.clone(),
+            pools_token_b: token_b.clone(),
+        })
+        .unwrap_or_else(|| {
+            let mut pools = Vec::new(env);
+            if let Some(default_pool) = get_default_pool_for_pair(env, token_a, token_b) {
+                pools.push_back(default_pool);
+            }
+            pools
+        })
+}
+
+pub fn save_pool_type(env: &Env, pool_address: &Address, pool_type: PairType) {
+    env.storage().persistent().set(
+        &PoolTypeKey {
+            pool_address: pool_address.clone(),
+        },
+        &pool_type,
+    );
+}
+
+// Pools created before the stable pool support have no type recorded and are all XYK pools
+pub fn get_pool_type(env: &Env, pool_address: &Address) -> PairType {
+    env.storage()
+        .persistent()
+        .get(&PoolTypeKey {
+            pool_address: pool_address.clone(),
+        })
+        .unwrap_or(PairType::Xyk)
+}
+
 pub fn is_initialized(e: &Env) -> bool {
     e.storage()
         .persistent()
@@ -1,8 +1,8 @@
 use soroban_sdk::{testutils::Address as _, vec, Address, Env};
 
 use self::setup::{
-    deploy_factory_contract, install_lp_contract, install_multihop_wasm, install_stake_wasm,
-    install_token_wasm,
+    deploy_factory_contract, install_lp_contract, install_multihop_wasm,
+    install_stable_lp_contract, install_stake_wasm, install_token_wasm,
 };
 
 mod config;
@@ -21,6 +21,7 @@ fn test_deploy_factory_twice_should_fail() {
     let auth_user = Address::generate(&env);
     let multihop_wasm_hash = install_multihop_wasm(&env);
     let lp_wasm_hash = install_lp_contract(&env);
+    let stable_wasm_hash = install_stable_lp_contract(&env);
     let stake_wasm_hash = install_stake_wasm(&env);
     let token_wasm_hash = install_token_wasm(&env);
 
@@ -30,6 +31,7 @@ fn test_deploy_factory_twice_should_fail() {
         &admin,
         &multihop_wasm_hash,
         &lp_wasm_hash,
+        &stable_wasm_hash,
         &stake_wasm_hash,
         &token_wasm_hash,
         &vec![&env, auth_user.clone()],
@@ -39,6 +41,7 @@ fn test_deploy_factory_twice_should_fail() {
         &admin,
         &multihop_wasm_hash,
         &lp_wasm_hash,
+        &stable_wasm_hash,
         &stake_wasm_hash,
         &token_wasm_hash,
         &vec![&env, auth_user.clone()],
@@ -1,18 +1,22 @@
 use super::setup::{
-    deploy_factory_contract, install_lp_contract, install_multihop_wasm, install_stake_wasm,
-    install_token_wasm, lp_contract,
+    deploy_factory_contract, install_lp_contract, install_multihop_wasm,
+    install_stable_lp_contract, install_stake_wasm, install_token_wasm, lp_contract,
+    stable_lp_contract,
 };
 use crate::contract::{Factory, FactoryClient};
+use crate::storage::PairType;
+use crate::token_contract;
 use phoenix::utils::{LiquidityPoolInitInfo, StakeInitInfo, TokenInitInfo};
 
 use soroban_sdk::{
-    testutils::{arbitrary::std, Address as _},
+    testutils::{arbitrary::std, Address as _, Ledger},
     vec, Address, Env, String,
 };
 
 #[test]
 fn factory_successfully_inits_itself() {
     let env = Env::default();
+    env.budget().reset_unlimited();
     let admin = Address::generate(&env);
 
     let factory = deploy_factory_contract(&env, Some(admin.clone()));
@@ -23,6 +27,7 @@ fn factory_successfully_inits_itself() {
 #[test]
 fn factory_successfully_inits_multihop() {
     let env = Env::default();
+    env.budget().reset_unlimited();
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
@@ -83,6 +88,8 @@ fn factory_successfully_inits_lp() {
         &lp_init_info,
         &String::from_str(&env, "Pool"),
         &String::from_str(&env, "PHO/BTC"),
+        &PairType::Xyk,
+        &None,
     );
     let lp_contract_addr = factory.query_pools().get(0).unwrap();
 
@@ -97,6 +104,8 @@ fn factory_successfully_inits_lp() {
             max_allowed_slippage_bps: 5_000,
             max_allowed_spread_bps: 500,
             max_referral_bps: 5_000,
+            protocol_fee_share_bps: 10_000,
+            paused: false,
             pool_type: lp_contract::PairType::Xyk,
             share_token: share_token_address,
             stake_contract: stake_token_address,
@@ -161,6 +170,8 @@ fn factory_fails_to_init_lp_when_authorized_address_not_present() {
         &lp_init_info,
         &String::from_str(&env, "Pool"),
         &String::from_str(&env, "PHO/BTC"),
+        &PairType::Xyk,
+        &None,
     );
 }
 
@@ -180,6 +191,8 @@ fn factory_fails_to_init_lp_when_no_whitelisted_accounts() {
     let whitelisted_accounts = vec![&env];
 
     let lp_wasm_hash = install_lp_contract(&env);
+
+    let stable_wasm_hash = install_stable_lp_contract(&env);
     let stake_wasm_hash = install_stake_wasm(&env);
     let token_wasm_hash = install_token_wasm(&env);
 
@@ -187,6 +200,7 @@ fn factory_fails_to_init_lp_when_no_whitelisted_accounts() {
         &admin,
         &multihop_wasm_hash,
         &lp_wasm_hash,
+        &stable_wasm_hash,
         &stake_wasm_hash,
         &token_wasm_hash,
         &whitelisted_accounts,
@@ -197,6 +211,7 @@ fn factory_fails_to_init_lp_when_no_whitelisted_accounts() {
 #[test]
 fn successfully_updates_new_list_of_whitelisted_accounts() {
     let env = Env::default();
+    env.budget().reset_unlimited();
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
@@ -226,6 +241,7 @@ fn successfully_updates_new_list_of_whitelisted_accounts() {
 #[test]
 fn doesn_not_change_whitelisted_accounts_when_removing_non_existent() {
     let env = Env::default();
+    env.budget().reset_unlimited();
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
@@ -246,6 +262,7 @@ fn doesn_not_change_whitelisted_accounts_when_removing_non_existent() {
 #[test]
 fn fails_to_update_whitelisted_accounts_when_not_authorized() {
     let env = Env::default();
+    env.budget().reset_unlimited();
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
@@ -265,6 +282,7 @@ fn fails_to_update_whitelisted_accounts_when_not_authorized() {
 #[test]
 fn test_add_vec_with_duplicates_should_be_handled_correctly() {
     let env = Env::default();
+    env.budget().reset_unlimited();
     env.mock_all_auths();
 
     let admin = Address::generate(&env);
@@ -299,3 +317,240 @@ fn test_add_vec_with_duplicates_should_be_handled_correctly() {
     assert!(config.whitelisted_accounts.contains(second_wl_addr));
     assert!(config.whitelisted_accounts.len() == 2);
 }
+
+#[test]
+fn factory_pauses_and_unpauses_all_pools() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+
+    for _ in 0..2 {
+        let mut token1 = Address::generate(&env);
+        let mut token2 = Address::generate(&env);
+        if token2 < token1 {
+            std::mem::swap(&mut token1, &mut token2);
+        }
+
+        let lp_init_info = LiquidityPoolInitInfo {
+            admin: admin.clone(),
+            fee_recipient: Address::generate(&env),
+            max_allowed_slippage_bps: 5_000,
+            max_allowed_spread_bps: 500,
+            swap_fee_bps: 0,
+            max_referral_bps: 5_000,
+            token_init_info: TokenInitInfo {
+                token_a: token1,
+                token_b: token2,
+            },
+            stake_init_info: StakeInitInfo {
+                min_bond: 10i128,
+                min_reward: 5i128,
+                manager: Address::generate(&env),
+                max_complexity: 10u32,
+            },
+        };
+
+        factory.create_liquidity_pool(
+            &admin,
+            &lp_init_info,
+            &String::from_str(&env, "Pool"),
+            &String::from_str(&env, "PHO/BTC"),
+            &PairType::Xyk,
+            &None,
+        );
+    }
+
+    factory.pause_all_pools(&admin);
+    for pool in factory.query_pools() {
+        assert!(lp_contract::Client::new(&env, &pool).query_config().paused);
+    }
+
+    factory.unpause_all_pools(&admin);
+    for pool in factory.query_pools() {
+        assert!(!lp_contract::Client::new(&env, &pool).query_config().paused);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Factory: Pause all pools: You are not authorized!")]
+fn pause_all_pools_by_unauthorized_user_should_fail() {
+    let env = Env::default();
+    env.budget().reset_unlimited();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let factory = deploy_factory_contract(&env, Some(admin));
+
+    factory.pause_all_pools(&Address::generate(&env));
+}
+
+#[test]
+fn propose_and_accept_admin() {
+    let env = Env::default();
+    env.budget().reset_unlimited();
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+
+    factory.propose_admin(&admin, &new_admin, &1_000);
+    assert_eq!(factory.get_admin(), admin);
+
+    factory.accept_admin(&new_admin);
+    assert_eq!(factory.get_admin(), new_admin);
+}
+
+#[test]
+#[should_panic(expected = "Factory: Accept admin: No admin change in place")]
+fn accept_cancelled_admin_proposal_should_fail() {
+    let env = Env::default();
+    env.budget().reset_unlimited();
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+
+    factory.propose_admin(&admin, &new_admin, &1_000);
+    factory.cancel_admin_proposal(&admin);
+
+    factory.accept_admin(&new_admin);
+}
+
+#[test]
+#[should_panic(expected = "Factory: Propose admin: Proposal expiration must be in the future")]
+fn propose_admin_with_expired_proposal_should_fail() {
+    let env = Env::default();
+    env.budget().reset_unlimited();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1_000;
+    });
+
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+
+    factory.propose_admin(&admin, &Address::generate(&env), &1_000);
+}
+
+#[test]
+fn factory_successfully_inits_stable_pool() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let mut token1 =
+        token_contract::Client::new(&env, &env.register_stellar_asset_contract(admin.clone()));
+    let mut token2 =
+        token_contract::Client::new(&env, &env.register_stellar_asset_contract(admin.clone()));
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+
+    let lp_init_info = LiquidityPoolInitInfo {
+        admin: admin.clone(),
+        fee_recipient: Address::generate(&env),
+        max_allowed_slippage_bps: 5_000,
+        max_allowed_spread_bps: 500,
+        swap_fee_bps: 0,
+        max_referral_bps: 5_000,
+        token_init_info: TokenInitInfo {
+            token_a: token1.address.clone(),
+            token_b: token2.address.clone(),
+        },
+        stake_init_info: StakeInitInfo {
+            min_bond: 10i128,
+            min_reward: 5i128,
+            manager: Address::generate(&env),
+            max_complexity: 10u32,
+        },
+    };
+
+    let lp_contract_addr = factory.create_liquidity_pool(
+        &admin,
+        &lp_init_info,
+        &String::from_str(&env, "Pool"),
+        &String::from_str(&env, "PHO/USDC"),
+        &PairType::Stable,
+        &Some(6u64),
+    );
+
+    let stable_pool = stable_lp_contract::Client::new(&env, &lp_contract_addr);
+    let pool_config = stable_pool.query_config();
+    assert_eq!(pool_config.pool_type, stable_lp_contract::PairType::Stable);
+    assert_eq!(
+        pool_config.tokens,
+        vec![&env, token1.address.clone(), token2.address.clone()]
+    );
+
+    let pool_details = factory.query_pool_details(&lp_contract_addr);
+    assert_eq!(pool_details.pool_address, lp_contract_addr);
+    assert_eq!(pool_details.pool_response.asset_a.address, token1.address);
+    assert_eq!(pool_details.pool_response.asset_b.address, token2.address);
+
+    assert_eq!(
+        factory.query_for_pool_by_token_pair(&token1.address, &token2.address),
+        lp_contract_addr
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "Factory: Create Liquidity Pool: Amplification coefficient must be set for stable pools"
+)]
+fn factory_fails_to_init_stable_pool_without_amp() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let mut token1 = Address::generate(&env);
+    let mut token2 = Address::generate(&env);
+    if token2 < token1 {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+
+    let lp_init_info = LiquidityPoolInitInfo {
+        admin: admin.clone(),
+        fee_recipient: Address::generate(&env),
+        max_allowed_slippage_bps: 5_000,
+        max_allowed_spread_bps: 500,
+        swap_fee_bps: 0,
+        max_referral_bps: 5_000,
+        token_init_info: TokenInitInfo {
+            token_a: token1,
+            token_b: token2,
+        },
+        stake_init_info: StakeInitInfo {
+            min_bond: 10i128,
+            min_reward: 5i128,
+            manager: Address::generate(&env),
+            max_complexity: 10u32,
+        },
+    };
+
+    factory.create_liquidity_pool(
+        &admin,
+        &lp_init_info,
+        &String::from_str(&env, "Pool"),
+        &String::from_str(&env, "PHO/USDC"),
+        &PairType::Stable,
+        &None,
+    );
+}
@@ -1,5 +1,7 @@
 use super::setup::{deploy_factory_contract, generate_lp_init_info};
-use crate::storage::{Asset, LpPortfolio, Stake, StakePortfolio, UserPortfolio};
+use crate::storage::{
+    Asset, LpPortfolio, PairType, PoolInfo, Stake, StakePortfolio, UserPortfolio,
+};
 use crate::tests::setup::{lp_contract, stake_contract};
 use crate::token_contract;
 use phoenix::utils::{LiquidityPoolInitInfo, StakeInitInfo, TokenInitInfo};
@@ -10,12 +12,6 @@ use soroban_sdk::{
     Address, Env, String, Symbol, Vec,
 };
 
-#[contracttype]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-#[repr(u32)]
-pub enum PairType {
-    Xyk = 0,
-}
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct LiquidityPoolConfig {
@@ -33,6 +29,8 @@ pub struct LiquidityPoolConfig {
     /// The maximum amount of spread (in bps) that is tolerated during swap
     pub max_allowed_spread_bps: i64,
     pub max_referral_bps: i64,
+    pub protocol_fee_share_bps: i64,
+    pub paused: bool,
 }
 
 #[test]
@@ -136,18 +134,24 @@ fn test_deploy_multiple_liquidity_pools() {
         &first_lp_init_info,
         &String::from_str(&env, "Pool"),
         &String::from_str(&env, "PHO/BTC"),
+        &PairType::Xyk,
+        &None,
     );
     let second_lp_contract_addr = factory.create_liquidity_pool(
         &admin.clone(),
         &second_lp_init_info,
         &String::from_str(&env, "Pool #2"),
         &String::from_str(&env, "PHO/ETH"),
+        &PairType::Xyk,
+        &None,
     );
     let third_lp_contract_addr = factory.create_liquidity_pool(
         &admin.clone(),
         &third_lp_init_info,
         &String::from_str(&env, "Pool #3"),
         &String::from_str(&env, "PHO/XLM"),
+        &PairType::Xyk,
+        &None,
     );
 
     let first_result = factory.query_pool_details(&lp_contract_addr);
@@ -336,18 +340,24 @@ fn test_queries_by_tuple() {
         &first_lp_init_info,
         &String::from_str(&env, "Pool"),
         &String::from_str(&env, "PHO/BTC"),
+        &PairType::Xyk,
+        &None,
     );
     let second_lp_contract_addr = factory.create_liquidity_pool(
         &admin.clone(),
         &second_lp_init_info,
         &String::from_str(&env, "Pool #2"),
         &String::from_str(&env, "PHO/ETH"),
+        &PairType::Xyk,
+        &None,
     );
     let third_lp_contract_addr = factory.create_liquidity_pool(
         &admin.clone(),
         &third_lp_init_info,
         &String::from_str(&env, "Pool #3"),
         &String::from_str(&env, "PHO/XLM"),
+        &PairType::Xyk,
+        &None,
     );
 
     let first_result = factory.query_pool_details(&lp_contract_addr);
@@ -394,12 +404,155 @@ fn test_queries_by_tuple() {
 #[should_panic(expected = "Factory: query_for_pool_by_token_pair failed: No liquidity pool found")]
 fn test_queries_by_tuple_errors() {
     let env = Env::default();
+    env.budget().reset_unlimited();
     let admin = Address::generate(&env);
     let factory = deploy_factory_contract(&env, Some(admin.clone()));
 
     factory.query_for_pool_by_token_pair(&Address::generate(&env), &Address::generate(&env));
 }
 
+#[test]
+fn test_query_pools_for_pair() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let mut token1 =
+        token_contract::Client::new(&env, &env.register_stellar_asset_contract(admin.clone()));
+    let mut token2 =
+        token_contract::Client::new(&env, &env.register_stellar_asset_contract(admin.clone()));
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+
+    let mut lp_init_info = generate_lp_init_info(
+        &token1,
+        &token2,
+        Address::generate(&env),
+        &admin,
+        Address::generate(&env),
+    );
+
+    let first_pool = factory.create_liquidity_pool(
+        &admin,
+        &lp_init_info,
+        &String::from_str(&env, "Pool"),
+        &String::from_str(&env, "PHO/BTC"),
+        &PairType::Xyk,
+        &None,
+    );
+
+    lp_init_info.swap_fee_bps = 30;
+    let second_pool = factory.create_liquidity_pool(
+        &admin,
+        &lp_init_info,
+        &String::from_str(&env, "Pool"),
+        &String::from_str(&env, "PHO/BTC"),
+        &PairType::Xyk,
+        &None,
+    );
+
+    let stable_pool = factory.create_liquidity_pool(
+        &admin,
+        &lp_init_info,
+        &String::from_str(&env, "Pool"),
+        &String::from_str(&env, "PHO/BTC"),
+        &PairType::Stable,
+        &Some(6u64),
+    );
+
+    let expected = vec![
+        &env,
+        PoolInfo {
+            pool_address: first_pool.clone(),
+            pool_type: PairType::Xyk,
+            total_fee_bps: 0,
+        },
+        PoolInfo {
+            pool_address: second_pool,
+            pool_type: PairType::Xyk,
+            total_fee_bps: 30,
+        },
+        PoolInfo {
+            pool_address: stable_pool,
+            pool_type: PairType::Stable,
+            total_fee_bps: 30,
+        },
+    ];
+    assert_eq!(
+        factory.query_pools_for_pair(&token1.address, &token2.address),
+        expected
+    );
+    assert_eq!(
+        factory.query_pools_for_pair(&token2.address, &token1.address),
+        expected
+    );
+
+    // the first pool stays the default pool of the pair
+    assert_eq!(
+        factory.query_for_pool_by_token_pair(&token1.address, &token2.address),
+        first_pool
+    );
+    assert_eq!(factory.query_pools().len(), 3);
+}
+
+#[test]
+#[should_panic(
+    expected = "Factory: Create Liquidity Pool: A pool with the same type and fee already exists for this pair"
+)]
+fn test_create_pool_with_same_type_and_fee_should_fail() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let mut token1 =
+        token_contract::Client::new(&env, &env.register_stellar_asset_contract(admin.clone()));
+    let mut token2 =
+        token_contract::Client::new(&env, &env.register_stellar_asset_contract(admin.clone()));
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+
+    let factory = deploy_factory_contract(&env, Some(admin.clone()));
+
+    let lp_init_info = generate_lp_init_info(
+        &token1,
+        &token2,
+        Address::generate(&env),
+        &admin,
+        Address::generate(&env),
+    );
+
+    for _ in 0..2 {
+        factory.create_liquidity_pool(
+            &admin,
+            &lp_init_info,
+            &String::from_str(&env, "Pool"),
+            &String::from_str(&env, "PHO/BTC"),
+            &PairType::Xyk,
+            &None,
+        );
+    }
+}
+
+#[test]
+fn test_query_pools_for_unknown_pair_is_empty() {
+    let env = Env::default();
+    env.budget().reset_unlimited();
+    let admin = Address::generate(&env);
+    let factory = deploy_factory_contract(&env, Some(admin));
+
+    assert!(factory
+        .query_pools_for_pair(&Address::generate(&env), &Address::generate(&env))
+        .is_empty());
+}
+
 #[test]
 fn test_query_user_portfolio_with_stake() {
     let env = Env::default();
@@ -430,10 +583,10 @@ fn test_query_user_portfolio_with_stake() {
         std::mem::swap(&mut token3, &mut token4);
     }
 
-    token1.mint(&user_1, &10_000i128);
-    token2.mint(&user_1, &10_000i128);
-    token3.mint(&user_2, &20_000i128);
-    token4.mint(&user_2, &20_000i128);
+    token1.mint(&user_1, &100_000i128);
+    token2.mint(&user_1, &100_000i128);
+    token3.mint(&user_2, &200_000i128);
+    token4.mint(&user_2, &200_000i128);
 
     let factory = deploy_factory_contract(&env, Some(admin.clone()));
 
@@ -450,6 +603,8 @@ fn test_query_user_portfolio_with_stake() {
         &first_lp_init_info,
         &String::from_str(&env, "Pool"),
         &String::from_str(&env, "PHO/BTC"),
+        &PairType::Xyk,
+        &None,
     );
 
     let first_lp_client = lp_contract::Client::new(&env, &first_lp_contract_addr);
@@ -463,11 +618,12 @@ fn test_query_user_portfolio_with_stake() {
 
     first_lp_client.provide_liquidity(
         &user_1.clone(),
-        &Some(150),
-        &Some(100i128),
-        &Some(200),
-        &Some(100i128),
+        &Some(15_000),
+        &Some(10_000i128),
+        &Some(20_000),
+        &Some(10_000i128),
         &None::<i64>,
+        &None,
     );
 
     // first user portfolio after providing liquidity
@@ -481,11 +637,11 @@ fn test_query_user_portfolio_with_stake() {
                     assets: (
                         Asset {
                             address: token1.address.clone(),
-                            amount: 150i128,
+                            amount: 14_133i128,
                         },
                         Asset {
                             address: token2.address.clone(),
-                            amount: 200i128
+                            amount: 18_845i128
                         }
                     )
                 }
@@ -494,7 +650,7 @@ fn test_query_user_portfolio_with_stake() {
         }
     );
 
-    first_stake_client.bond(&user_1, &173i128);
+    first_stake_client.bond(&user_1, &16_320i128);
 
     // first user portfolio after staking
     let first_portfolio = factory.query_user_portfolio(&user_1, &true);
@@ -507,11 +663,11 @@ fn test_query_user_portfolio_with_stake() {
                     assets: (
                         Asset {
                             address: token1.address.clone(),
-                            amount: 150i128,
+                            amount: 14_133i128,
                         },
                         Asset {
                             address: token2.address.clone(),
-                            amount: 200i128
+                            amount: 18_845i128
                         }
                     )
                 }
@@ -523,7 +679,7 @@ fn test_query_user_portfolio_with_stake() {
                     stakes: vec![
                         &env,
                         Stake {
-                            stake: 173i128,
+                            stake: 16_320i128,
                             stake_timestamp: 0
                         }
                     ]
@@ -540,6 +696,8 @@ fn test_query_user_portfolio_with_stake() {
         &second_lp_init_info,
         &String::from_str(&env, "Second Pool"),
         &String::from_str(&env, "PHO/ETH"),
+        &PairType::Xyk,
+        &None,
     );
 
     let second_lp_client = lp_contract::Client::new(&env, &second_lp_contract_addr);
@@ -552,11 +710,12 @@ fn test_query_user_portfolio_with_stake() {
 
     second_lp_client.provide_liquidity(
         &user_2.clone(),
-        &Some(200),
-        &Some(100i128),
-        &Some(250),
-        &Some(100i128),
+        &Some(20_000),
+        &Some(10_000i128),
+        &Some(25_000),
+        &Some(10_000i128),
         &None::<i64>,
+        &None,
     );
 
     let second_portfolio = factory.query_user_portfolio(&user_2, &true);
@@ -569,11 +728,11 @@ fn test_query_user_portfolio_with_stake() {
                     assets: (
                         Asset {
                             address: token3.address.clone(),
-                            amount: 200i128,
+                            amount: 19_105i128,
                         },
                         Asset {
                             address: token4.address.clone(),
-                            amount: 250i128
+                            amount: 23_881i128
                         }
                     )
                 }
@@ -582,7 +741,7 @@ fn test_query_user_portfolio_with_stake() {
         }
     );
 
-    second_stake_client.bond(&user_2, &223i128);
+    second_stake_client.bond(&user_2, &21_360i128);
 
     let second_portfolio = factory.query_user_portfolio(&user_2, &true);
     assert_eq!(
@@ -594,11 +753,11 @@ fn test_query_user_portfolio_with_stake() {
                     assets: (
                         Asset {
                             address: token3.address.clone(),
-                            amount: 200i128,
+                            amount: 19_105i128,
                         },
                         Asset {
                             address: token4.address.clone(),
-                            amount: 250i128
+                            amount: 23_881i128
                         }
                     )
                 }
@@ -610,7 +769,7 @@ fn test_query_user_portfolio_with_stake() {
                     stakes: vec![
                         &env,
                         Stake {
-                            stake: 223i128,
+                            stake: 21_360i128,
                             stake_timestamp: 0
                         }
                     ]
@@ -649,15 +808,15 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
         std::mem::swap(&mut token3, &mut token4);
     }
 
-    token1.mint(&user_1, &1_000i128);
-    token1.mint(&user_2, &2_000i128);
-    token2.mint(&user_1, &1_000i128);
-    token2.mint(&user_2, &2_000i128);
+    token1.mint(&user_1, &10_000i128);
+    token1.mint(&user_2, &20_000i128);
+    token2.mint(&user_1, &10_000i128);
+    token2.mint(&user_2, &20_000i128);
 
-    token3.mint(&user_1, &1_000i128);
-    token3.mint(&user_2, &2_000i128);
-    token4.mint(&user_1, &4_000i128);
-    token4.mint(&user_2, &8_000i128);
+    token3.mint(&user_1, &10_000i128);
+    token3.mint(&user_2, &20_000i128);
+    token4.mint(&user_1, &40_000i128);
+    token4.mint(&user_2, &80_000i128);
 
     let factory = deploy_factory_contract(&env, Some(admin.clone()));
 
@@ -675,6 +834,8 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
         &first_lp_init_info,
         &String::from_str(&env, "Pool"),
         &String::from_str(&env, "PHO/BTC"),
+        &PairType::Xyk,
+        &None,
     );
 
     let first_lp_client = lp_contract::Client::new(&env, &first_lp_contract_addr);
@@ -700,6 +861,8 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
         &second_lp_init_info,
         &String::from_str(&env, "Second Pool"),
         &String::from_str(&env, "PHO/ETH"),
+        &PairType::Xyk,
+        &None,
     );
 
     let second_lp_client = lp_contract::Client::new(&env, &second_lp_contract_addr);
@@ -715,20 +878,22 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
     // provides liquidity in 50/50 ratio
     first_lp_client.provide_liquidity(
         &user_1.clone(),
-        &Some(1_000i128),
-        &Some(900i128),
-        &Some(1_000i128),
-        &Some(900i128),
+        &Some(10_000i128),
+        &Some(9_000i128),
+        &Some(10_000i128),
+        &Some(9_000i128),
         &None::<i64>,
+        &None,
     );
 
     first_lp_client.provide_liquidity(
         &user_2.clone(),
-        &Some(2_000i128),
-        &Some(1_900i128),
-        &Some(2_000i128),
-        &Some(1_900i128),
+        &Some(20_000i128),
+        &Some(19_000i128),
+        &Some(20_000i128),
+        &Some(19_000i128),
         &None::<i64>,
+        &None,
     );
 
     // first user portfolio in first pool after providing liquidity
@@ -742,11 +907,11 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     assets: (
                         Asset {
                             address: token1.address.clone(),
-                            amount: 999i128,
+                            amount: 9_000i128,
                         },
                         Asset {
                             address: token2.address.clone(),
-                            amount: 999i128,
+                            amount: 9_000i128,
                         }
                     )
                 }
@@ -755,7 +920,7 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
         }
     );
 
-    first_stake_client.bond(&user_1, &1_000i128);
+    first_stake_client.bond(&user_1, &9_000i128);
 
     // first user portfolio in first pool after staking
     let first_user_first_portfolio = factory.query_user_portfolio(&user_1, &true);
@@ -768,11 +933,11 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     assets: (
                         Asset {
                             address: token1.address.clone(),
-                            amount: 999i128,
+                            amount: 9_000i128,
                         },
                         Asset {
                             address: token2.address.clone(),
-                            amount: 999i128
+                            amount: 9_000i128
                         }
                     )
                 }
@@ -784,7 +949,7 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     stakes: vec![
                         &env,
                         Stake {
-                            stake: 1_000i128,
+                            stake: 9_000i128,
                             stake_timestamp: 0
                         }
                     ]
@@ -804,11 +969,11 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     assets: (
                         Asset {
                             address: token1.address.clone(),
-                            amount: 1_999i128,
+                            amount: 19_999i128,
                         },
                         Asset {
                             address: token2.address.clone(),
-                            amount: 1_999i128,
+                            amount: 19_999i128,
                         }
                     )
                 }
@@ -818,7 +983,7 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
     );
 
     // this time we bond just 50% of the lp share token for 2nd user
-    first_stake_client.bond(&user_2, &1_000i128);
+    first_stake_client.bond(&user_2, &10_000i128);
 
     // second user portfolio in first pool after staking
     let second_user_first_portfolio = factory.query_user_portfolio(&user_2, &true);
@@ -831,11 +996,11 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     assets: (
                         Asset {
                             address: token1.address.clone(),
-                            amount: 1_999i128,
+                            amount: 19_999i128,
                         },
                         Asset {
                             address: token2.address.clone(),
-                            amount: 1_999i128
+                            amount: 19_999i128
                         }
                     )
                 }
@@ -847,7 +1012,7 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     stakes: vec![
                         &env,
                         Stake {
-                            stake: 1_000i128,
+                            stake: 10_000i128,
                             stake_timestamp: 0
                         }
                     ]
@@ -860,20 +1025,22 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
     // provides liquidity in 25/75 ratio
     second_lp_client.provide_liquidity(
         &user_1.clone(),
-        &Some(1_000i128),
-        &Some(900i128),
-        &Some(4_000i128),
-        &Some(3_900i128),
+        &Some(10_000i128),
+        &Some(9_000i128),
+        &Some(40_000i128),
+        &Some(39_000i128),
         &None::<i64>,
+        &None,
     );
 
     second_lp_client.provide_liquidity(
         &user_2.clone(),
-        &Some(2_000i128),
-        &Some(1_900i128),
-        &Some(8_000i128),
-        &Some(7_900i128),
+        &Some(20_000i128),
+        &Some(19_000i128),
+        &Some(80_000i128),
+        &Some(79_000i128),
         &None::<i64>,
+        &None,
     );
 
     // first user portfolio with second pool after providing liquidity
@@ -887,11 +1054,11 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     assets: (
                         Asset {
                             address: token1.address.clone(),
-                            amount: 999i128,
+                            amount: 9_000i128,
                         },
                         Asset {
                             address: token2.address.clone(),
-                            amount: 999i128,
+                            amount: 9_000i128,
                         }
                     )
                 },
@@ -899,11 +1066,11 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     assets: (
                         Asset {
                             address: token3.address.clone(),
-                            amount: 999i128,
+                            amount: 9_499i128,
                         },
                         Asset {
                             address: token4.address.clone(),
-                            amount: 3_999i128,
+                            amount: 37_999i128,
                         }
                     )
                 },
@@ -915,7 +1082,7 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     stakes: vec![
                         &env,
                         Stake {
-                            stake: 1_000i128,
+                            stake: 9_000i128,
                             stake_timestamp: 0
                         }
                     ]
@@ -924,8 +1091,8 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
         }
     );
 
-    // after providing liquidity to 2nd pool user1 has 2_000 lp share tokens
-    second_stake_client.bond(&user_1, &2_000i128);
+    // after providing liquidity to 2nd pool user1 has 19_000 lp share tokens
+    second_stake_client.bond(&user_1, &19_000i128);
 
     // first user portfolio with second pool after staking
     let first_user_first_portfolio = factory.query_user_portfolio(&user_1, &true);
@@ -938,11 +1105,11 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     assets: (
                         Asset {
                             address: token1.address.clone(),
-                            amount: 999i128,
+                            amount: 9_000i128,
                         },
                         Asset {
                             address: token2.address.clone(),
-                            amount: 999i128
+                            amount: 9_000i128
                         }
                     )
                 },
@@ -950,11 +1117,11 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     assets: (
                         Asset {
                             address: token3.address.clone(),
-                            amount: 999i128,
+                            amount: 9_499i128,
                         },
                         Asset {
                             address: token4.address.clone(),
-                            amount: 3_999i128,
+                            amount: 37_999i128,
                         }
                     )
                 },
@@ -966,7 +1133,7 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     stakes: vec![
                         &env,
                         Stake {
-                            stake: 1_000i128,
+                            stake: 9_000i128,
                             stake_timestamp: 0
                         }
                     ]
@@ -976,7 +1143,7 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     stakes: vec![
                         &env,
                         Stake {
-                            stake: 2_000i128,
+                            stake: 19_000i128,
                             stake_timestamp: 0
                         }
                     ]
@@ -996,11 +1163,11 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     assets: (
                         Asset {
                             address: token1.address.clone(),
-                            amount: 1_999i128,
+                            amount: 19_999i128,
                         },
                         Asset {
                             address: token2.address.clone(),
-                            amount: 1_999i128,
+                            amount: 19_999i128,
                         }
                     )
                 },
@@ -1008,11 +1175,11 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     assets: (
                         Asset {
                             address: token3.address.clone(),
-                            amount: 1_999i128,
+                            amount: 19_999i128,
                         },
                         Asset {
                             address: token4.address.clone(),
-                            amount: 7_999i128,
+                            amount: 79_999i128,
                         }
                     )
                 },
@@ -1024,7 +1191,7 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     stakes: vec![
                         &env,
                         Stake {
-                            stake: 1_000i128,
+                            stake: 10_000i128,
                             stake_timestamp: 0
                         }
                     ]
@@ -1034,7 +1201,7 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
     );
 
     // this time we bond just 75% of the lp share token for 2nd user
-    second_stake_client.bond(&user_2, &3_000i128);
+    second_stake_client.bond(&user_2, &30_000i128);
 
     // second user portfolio with second pool after staking
     let second_user_second_portfolio = factory.query_user_portfolio(&user_2, &true);
@@ -1047,11 +1214,11 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     assets: (
                         Asset {
                             address: token1.address.clone(),
-                            amount: 1_999i128,
+                            amount: 19_999i128,
                         },
                         Asset {
                             address: token2.address.clone(),
-                            amount: 1_999i128
+                            amount: 19_999i128
                         }
                     )
                 },
@@ -1059,11 +1226,11 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     assets: (
                         Asset {
                             address: token3.address.clone(),
-                            amount: 1_999i128,
+                            amount: 19_999i128,
                         },
                         Asset {
                             address: token4.address.clone(),
-                            amount: 7_999i128
+                            amount: 79_999i128
                         }
                     )
                 }
@@ -1075,7 +1242,7 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     stakes: vec![
                         &env,
                         Stake {
-                            stake: 1_000i128,
+                            stake: 10_000i128,
                             stake_timestamp: 0
                         }
                     ]
@@ -1085,7 +1252,7 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     stakes: vec![
                         &env,
                         Stake {
-                            stake: 3_000i128,
+                            stake: 30_000i128,
                             stake_timestamp: 0
                         }
                     ]
@@ -1105,11 +1272,11 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     assets: (
                         Asset {
                             address: token1.address.clone(),
-                            amount: 1_999i128,
+                            amount: 19_999i128,
                         },
                         Asset {
                             address: token2.address.clone(),
-                            amount: 1_999i128
+                            amount: 19_999i128
                         }
                     )
                 },
@@ -1117,11 +1284,11 @@ fn test_query_user_portfolio_with_multiple_users_staking_in_multiple_liquidity_p
                     assets: (
                         Asset {
                             address: token3.address.clone(),
-                            amount: 1_999i128,
+                            amount: 19_999i128,
                         },
                         Asset {
                             address: token4.address.clone(),
-                            amount: 7_999i128
+                            amount: 79_999i128
                         }
                     )
                 }
@@ -1152,8 +1319,8 @@ fn test_query_user_portfolio_without_stake() {
         std::mem::swap(&mut token1, &mut token2);
     }
 
-    token1.mint(&user_1, &10_000i128);
-    token2.mint(&user_1, &10_000i128);
+    token1.mint(&user_1, &100_000i128);
+    token2.mint(&user_1, &100_000i128);
     let factory = deploy_factory_contract(&env, Some(admin.clone()));
 
     let first_token_init_info = TokenInitInfo {
@@ -1183,17 +1350,20 @@ fn test_query_user_portfolio_without_stake() {
         &first_lp_init_info,
         &String::from_str(&env, "Pool"),
         &String::from_str(&env, "PHO/BTC"),
+        &PairType::Xyk,
+        &None,
     );
 
     let first_lp_client = lp_contract::Client::new(&env, &lp_contract_addr);
 
     first_lp_client.provide_liquidity(
         &user_1.clone(),
-        &Some(150i128),
-        &Some(100i128),
-        &Some(200i128),
-        &Some(100i128),
+        &Some(15_000i128),
+        &Some(10_000i128),
+        &Some(20_000i128),
+        &Some(10_000i128),
         &None::<i64>,
+        &None,
     );
 
     let result = factory.query_user_portfolio(&user_1, &false);
@@ -1206,11 +1376,11 @@ fn test_query_user_portfolio_without_stake() {
                     assets: (
                         Asset {
                             address: token1.address,
-                            amount: 150i128
+                            amount: 14_133i128
                         },
                         Asset {
                             address: token2.address,
-                            amount: 200i128
+                            amount: 18_845i128
                         }
                     )
                 }
@@ -5,10 +5,15 @@ pub fn deploy_lp_contract(
     lp_wasm_hash: BytesN<32>,
     token_a: &Address,
     token_b: &Address,
+    pool_index: u32,
 ) -> Address {
     let mut salt = Bytes::new(env);
     salt.append(&token_a.to_xdr(env));
     salt.append(&token_b.to_xdr(env));
+    // the first pool of a pair keeps the salt it had before multiple pools per pair existed
+    if pool_index > 0 {
+        salt.append(&pool_index.to_xdr(env));
+    }
     let salt = env.crypto().sha256(&salt);
 
     env.deployer()
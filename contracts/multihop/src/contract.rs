@@ -1,16 +1,28 @@
 use soroban_sdk::{
-    contract, contractimpl, contractmeta, log, panic_with_error, vec, Address, Env, Vec,
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractimpl, contractmeta, log, panic_with_error, symbol_short, vec, Address,
+    BytesN, Env, IntoVal, Map, Symbol, Vec,
 };
 
 use crate::error::ContractError;
-// FIXM: Disable Referral struct
-// use crate::lp_contract::Referral;
+use crate::factory_contract::PairType;
+use crate::pool::LiquidityPool;
 use crate::storage::{
-    get_factory, is_initialized, save_factory, set_initialized, SimulateReverseSwapResponse,
-    SimulateSwapResponse, Swap,
+    get_admin, get_admin_change, get_factory, is_initialized, is_paused, remove_admin_change,
+    save_admin, save_admin_change, save_factory, save_paused, set_initialized, BestRouteResponse,
+    Config, PoolSelector, Referral, SimulateReverseSwapResponse, SimulateSwapResponse,
+    SimulateSwapSplitResponse, Swap,
 };
-use crate::utils::{verify_reverse_swap, verify_swap};
-use crate::{factory_contract, lp_contract, token_contract};
+use crate::utils::{verify_deadline, verify_reverse_swap, verify_split_routes, verify_swap};
+use crate::{factory_contract, token_contract};
+use decimal::Decimal;
+use phoenix::utils::AdminChange;
+
+/// The most swaps a route found by `find_best_route` can have
+const MAX_HOPS: u32 = 4;
+/// The most paths `find_best_route` simulates, which bounds the number of pool simulations of
+/// the search however many pools the factory has
+const MAX_ROUTE_CANDIDATES: u32 = 32;
 
 // Metadata that is added on to the WASM custom section
 contractmeta!(
@@ -24,15 +36,96 @@ pub struct Multihop;
 pub trait MultihopTrait {
     fn initialize(env: Env, admin: Address, factory: Address);
 
+    // Swaps `amount` of `sender` along `operations` and sends the last ask asset to `to`. The
+    // intermediate assets stay in the multihop contract between the swaps. On top of the per swap `ask_asset_min_amount`, the whole
+    // route fails when less than `min_final_amount` is returned, or when the price impact of all
+    // the swaps together is bigger than `max_price_impact_bps`.
+    // Returns the amount of the last ask asset received
+    #[allow(clippy::too_many_arguments)]
     fn swap(
         env: Env,
-        recipient: Address,
-        // FIXM: Disable Referral struct
-        // referral: Option<Referral>,
+        sender: Address,
+        to: Address,
+        referral: Option<Referral>,
         operations: Vec<Swap>,
         max_spread_bps: Option<i64>,
         amount: i128,
-    );
+        min_final_amount: Option<i128>,
+        max_price_impact_bps: Option<i64>,
+        deadline: Option<u64>,
+    ) -> i128;
+
+    // Finds the best route of at most `max_hops` swaps through the pools of the factory and swaps
+    // `amount` of `offer_asset` along it. The swap fails when less than `ask_asset_min_amount` of
+    // `ask_asset` is returned.
+    // Returns the amount of `ask_asset` received
+    #[allow(clippy::too_many_arguments)]
+    fn swap_best_route(
+        env: Env,
+        recipient: Address,
+        offer_asset: Address,
+        ask_asset: Address,
+        amount: i128,
+        max_hops: u32,
+        ask_asset_min_amount: i128,
+        max_spread_bps: Option<i64>,
+        deadline: Option<u64>,
+    ) -> i128;
+
+    // Swaps along `operations` for exactly `ask_amount` of the last ask asset. The operations are
+    // given in the order of `simulate_reverse_swap`, from the last swap back to the first one. The
+    // offer amount is computed backwards over every swap and the swap fails when it is bigger than
+    // `max_offer_amount`.
+    // Returns the amount of the first offer asset paid
+    fn swap_exact_out(
+        env: Env,
+        sender: Address,
+        operations: Vec<Swap>,
+        ask_amount: i128,
+        max_offer_amount: i128,
+        max_spread_bps: Option<i64>,
+        deadline: Option<u64>,
+    ) -> i128;
+
+    // Splits a swap into several `routes`, each a list of `Swap` operations together with the amount
+    // offered to it. Every route has to end in the same ask asset, and the swap fails when the
+    // routes return less than `min_total_out` together.
+    // Returns the total amount of the ask asset received
+    fn swap_split(
+        env: Env,
+        recipient: Address,
+        routes: Vec<(Vec<Swap>, i128)>,
+        min_total_out: i128,
+        max_spread_bps: Option<i64>,
+        deadline: Option<u64>,
+    ) -> i128;
+
+    // Allows the admin to stop swaps through the multihop contract
+    fn pause(env: Env, sender: Address);
+
+    // Allows the admin to resume swaps through the multihop contract
+    fn unpause(env: Env, sender: Address);
+
+    // Allows the admin to propose `new_admin` as the next admin. The proposal has to be accepted
+    // by `new_admin` before the ledger timestamp passes `expires_at`
+    fn propose_admin(env: Env, sender: Address, new_admin: Address, expires_at: u64);
+
+    // Allows the proposed admin to accept the admin role
+    fn accept_admin(env: Env, sender: Address);
+
+    // Allows the admin to cancel the pending admin proposal
+    fn cancel_admin_proposal(env: Env, sender: Address);
+
+    // Allows the admin to point the multihop contract to another factory
+    fn update_config(env: Env, factory: Address);
+
+    // Migration entrypoint
+    fn upgrade(env: Env, new_wasm_hash: BytesN<32>);
+
+    // Returns the admin, the factory and whether swaps are paused
+    fn query_config(env: Env) -> Config;
+
+    fn query_admin(env: Env) -> Address;
 
     fn simulate_swap(env: Env, operations: Vec<Swap>, amount: i128) -> SimulateSwapResponse;
 
@@ -41,6 +134,21 @@ pub trait MultihopTrait {
         operations: Vec<Swap>,
         amount: i128,
     ) -> SimulateReverseSwapResponse;
+
+    // Simulates `swap_split`, returning the total amount of the ask asset together with the
+    // simulation of every route
+    fn simulate_swap_split(env: Env, routes: Vec<(Vec<Swap>, i128)>) -> SimulateSwapSplitResponse;
+
+    // Searches the pools of the factory for the route of at most `max_hops` swaps that returns the
+    // most `ask_asset` for `amount` of `offer_asset`. `max_hops` is capped by `MAX_HOPS` and at
+    // most `MAX_ROUTE_CANDIDATES` paths are simulated
+    fn find_best_route(
+        env: Env,
+        offer_asset: Address,
+        ask_asset: Address,
+        amount: i128,
+        max_hops: u32,
+    ) -> BestRouteResponse;
 }
 
 #[contractimpl]
@@ -57,49 +165,354 @@ impl MultihopTrait for Multihop {
         set_initialized(&env);
 
         save_factory(&env, factory);
+        save_admin(&env, admin.clone());
 
         env.events()
             .publish(("initialize", "Multihop factory with admin: "), admin);
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn swap(
         env: Env,
-        recipient: Address,
-        // FIXM: Disable Referral struct
-        // referral: Option<Referral>,
+        sender: Address,
+        to: Address,
+        referral: Option<Referral>,
         operations: Vec<Swap>,
         max_spread_bps: Option<i64>,
         amount: i128,
-    ) {
+        min_final_amount: Option<i128>,
+        max_price_impact_bps: Option<i64>,
+        deadline: Option<u64>,
+    ) -> i128 {
         if operations.is_empty() {
             log!(&env, "Multihop: Swap: operations is empty!");
             panic_with_error!(&env, ContractError::OperationsEmpty);
         }
         verify_swap(&env, &operations);
+        authorize_swap(&env, &sender, deadline);
+
+        if let Some(max_price_impact_bps) = max_price_impact_bps {
+            if compute_price_impact(&env, &operations, amount) > Decimal::bps(max_price_impact_bps)
+            {
+                log!(
+                    &env,
+                    "Multihop: Swap: Price impact of the route is bigger than the maximum"
+                );
+                panic_with_error!(&env, ContractError::PriceImpactTooHigh);
+            }
+        }
 
-        recipient.require_auth();
+        let ask_amount = do_swap(
+            &env,
+            &sender,
+            &to,
+            &referral,
+            &operations,
+            max_spread_bps,
+            amount,
+            deadline,
+        );
+
+        if let Some(min_final_amount) = min_final_amount {
+            if ask_amount < min_final_amount {
+                log!(
+                    &env,
+                    "Multihop: Swap: Return amount is smaller then the minimum final amount"
+                );
+                panic_with_error!(&env, ContractError::MinimumAmountNotReached);
+            }
+        }
 
-        // first offer amount is an input from the user,
-        // subsequent are the results of the previous swap
-        let mut next_offer_amount: i128 = amount;
+        ask_amount
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn swap_best_route(
+        env: Env,
+        recipient: Address,
+        offer_asset: Address,
+        ask_asset: Address,
+        amount: i128,
+        max_hops: u32,
+        ask_asset_min_amount: i128,
+        max_spread_bps: Option<i64>,
+        deadline: Option<u64>,
+    ) -> i128 {
+        let mut operations =
+            Self::find_best_route(env.clone(), offer_asset, ask_asset, amount, max_hops).operations;
+
+        // the minimum is checked by the pool of the last swap
+        let last_index = operations.len() - 1;
+        let mut last_swap = operations.get(last_index).unwrap();
+        last_swap.ask_asset_min_amount = Some(ask_asset_min_amount);
+        operations.set(last_index, last_swap);
+
+        authorize_swap(&env, &recipient, deadline);
+        do_swap(
+            &env,
+            &recipient,
+            &recipient,
+            &None,
+            &operations,
+            max_spread_bps,
+            amount,
+            deadline,
+        )
+    }
+
+    fn swap_exact_out(
+        env: Env,
+        sender: Address,
+        operations: Vec<Swap>,
+        ask_amount: i128,
+        max_offer_amount: i128,
+        max_spread_bps: Option<i64>,
+        deadline: Option<u64>,
+    ) -> i128 {
+        if operations.is_empty() {
+            log!(&env, "Multihop: Swap exact out: operations is empty!");
+            panic_with_error!(&env, ContractError::OperationsEmpty);
+        }
+        verify_reverse_swap(&env, &operations);
+        authorize_swap(&env, &sender, deadline);
 
         let factory_client = factory_contract::Client::new(&env, &get_factory(&env));
 
-        operations.iter().for_each(|op| {
-            let liquidity_pool_addr: Address = factory_client
-                .query_for_pool_by_token_pair(&op.clone().offer_asset, &op.ask_asset.clone());
+        // walks the path backwards, the amount asked from every pool is the amount offered to the
+        // pool after it. The swaps are collected with their offer and ask amounts in forward order
+        let mut swaps: Vec<(Swap, i128, i128)> = Vec::new(&env);
+        let mut next_ask_amount = ask_amount;
+        for mut op in operations.iter() {
+            let liquidity_pool = LiquidityPool::for_operation(&env, &factory_client, &op);
+            // every offer amount is computed from the reserves before the swaps, which a pool
+            // that is swapped through twice doesn't have anymore on its second swap
+            if swaps
+                .iter()
+                .any(|(swap, _, _)| swap.pool == PoolSelector::Pool(liquidity_pool.address.clone()))
+            {
+                log!(
+                    &env,
+                    "Multihop: Swap exact out: A pool can only be swapped through once"
+                );
+                panic_with_error!(&env, ContractError::PoolRepeatedInRoute);
+            }
+            let offer_amount = liquidity_pool.exact_out_offer_amount(&env, &op, next_ask_amount);
+
+            op.pool = PoolSelector::Pool(liquidity_pool.address);
+            swaps.push_front((op, offer_amount, next_ask_amount));
+            next_ask_amount = offer_amount;
+        }
+        let offer_amount = next_ask_amount;
 
-            let lp_client = lp_contract::Client::new(&env, &liquidity_pool_addr);
-            // FIXM: Disable Referral struct
-            next_offer_amount = lp_client.swap(
-                &recipient,
-                // &referral,
+        if offer_amount > max_offer_amount {
+            log!(
+                &env,
+                "Multihop: Swap exact out: Offer amount is bigger than the maximum offer amount"
+            );
+            panic_with_error!(&env, ContractError::MaxOfferAmountExceeded);
+        }
+
+        let contract = env.current_contract_address();
+        token_contract::Client::new(&env, &swaps.get(0).unwrap().0.offer_asset).transfer(
+            &sender,
+            &contract,
+            &offer_amount,
+        );
+
+        for (op, hop_offer_amount, hop_ask_amount) in swaps.iter() {
+            let liquidity_pool = LiquidityPool::for_operation(&env, &factory_client, &op);
+            authorize_pool_transfer(
+                &env,
                 &op.offer_asset,
-                &next_offer_amount,
-                &op.ask_asset_min_amount,
-                &max_spread_bps,
+                &liquidity_pool.address,
+                hop_offer_amount,
+            );
+            liquidity_pool.swap_exact_out(
+                &env,
+                &contract,
+                &op,
+                hop_ask_amount,
+                hop_offer_amount,
+                max_spread_bps,
+                deadline,
+            );
+        }
+
+        token_contract::Client::new(&env, &operations.get(0).unwrap().ask_asset).transfer(
+            &contract,
+            &sender,
+            &ask_amount,
+        );
+
+        offer_amount
+    }
+
+    fn swap_split(
+        env: Env,
+        recipient: Address,
+        routes: Vec<(Vec<Swap>, i128)>,
+        min_total_out: i128,
+        max_spread_bps: Option<i64>,
+        deadline: Option<u64>,
+    ) -> i128 {
+        verify_split_routes(&env, &routes);
+        authorize_swap(&env, &recipient, deadline);
+
+        let mut total_ask_amount = 0;
+        for (operations, amount) in routes.iter() {
+            total_ask_amount += do_swap(
+                &env,
+                &recipient,
+                &recipient,
+                &None,
+                &operations,
+                max_spread_bps,
+                amount,
+                deadline,
+            );
+        }
+
+        if total_ask_amount < min_total_out {
+            log!(
+                &env,
+                "Multihop: Swap split: Total return amount is smaller then the minimum"
+            );
+            panic_with_error!(&env, ContractError::MinimumAmountNotReached);
+        }
+
+        total_ask_amount
+    }
+
+    fn pause(env: Env, sender: Address) {
+        sender.require_auth();
+        assert_admin(&env, &sender, symbol_short!("pause"));
+
+        save_paused(&env, true);
+
+        env.events().publish(("pause", "sender"), sender);
+    }
+
+    fn unpause(env: Env, sender: Address) {
+        sender.require_auth();
+        assert_admin(&env, &sender, symbol_short!("unpause"));
+
+        save_paused(&env, false);
+
+        env.events().publish(("unpause", "sender"), sender);
+    }
+
+    fn propose_admin(env: Env, sender: Address, new_admin: Address, expires_at: u64) {
+        sender.require_auth();
+
+        if sender != get_admin(&env) {
+            log!(
+                &env,
+                "Multihop: Propose admin: Only the admin can propose a new admin"
+            );
+            panic_with_error!(&env, ContractError::Unauthorized);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            log!(
+                &env,
+                "Multihop: Propose admin: Proposal expiration must be in the future"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeExpired);
+        }
+
+        save_admin_change(
+            &env,
+            &AdminChange {
+                new_admin: new_admin.clone(),
+                expires_at,
+            },
+        );
+
+        env.events()
+            .publish(("propose_admin", "new_admin"), new_admin);
+        env.events()
+            .publish(("propose_admin", "expires_at"), expires_at);
+    }
+
+    fn accept_admin(env: Env, sender: Address) {
+        sender.require_auth();
+
+        let admin_change = get_admin_change(&env).unwrap_or_else(|| {
+            log!(&env, "Multihop: Accept admin: No admin change in place");
+            panic_with_error!(&env, ContractError::AdminChangeNotFound);
+        });
+        if sender != admin_change.new_admin {
+            log!(
+                &env,
+                "Multihop: Accept admin: Only the proposed admin can accept the admin role"
+            );
+            panic_with_error!(&env, ContractError::Unauthorized);
+        }
+        if env.ledger().timestamp() > admin_change.expires_at {
+            log!(
+                &env,
+                "Multihop: Accept admin: Admin change proposal has expired"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeExpired);
+        }
+
+        remove_admin_change(&env);
+        save_admin(&env, sender.clone());
+
+        env.events().publish(("accept_admin", "new_admin"), sender);
+    }
+
+    fn cancel_admin_proposal(env: Env, sender: Address) {
+        sender.require_auth();
+
+        if sender != get_admin(&env) {
+            log!(
+                &env,
+                "Multihop: Cancel admin proposal: Only the admin can cancel the proposal"
             );
+            panic_with_error!(&env, ContractError::Unauthorized);
+        }
+
+        let admin_change = get_admin_change(&env).unwrap_or_else(|| {
+            log!(
+                &env,
+                "Multihop: Cancel admin proposal: No admin change in place"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeNotFound);
         });
+        remove_admin_change(&env);
+
+        env.events().publish(
+            ("cancel_admin_proposal", "new_admin"),
+            admin_change.new_admin,
+        );
+    }
+
+    fn update_config(env: Env, factory: Address) {
+        let admin = get_admin(&env);
+        admin.require_auth();
+
+        save_factory(&env, factory.clone());
+
+        env.events().publish(("update_config", "factory"), factory);
+    }
+
+    fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        let admin = get_admin(&env);
+        admin.require_auth();
+
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    fn query_config(env: Env) -> Config {
+        Config {
+            admin: get_admin(&env),
+            factory: get_factory(&env),
+            paused: is_paused(&env),
+        }
+    }
+
+    fn query_admin(env: Env) -> Address {
+        get_admin(&env)
     }
 
     fn simulate_swap(env: Env, operations: Vec<Swap>, amount: i128) -> SimulateSwapResponse {
@@ -121,23 +534,20 @@ impl MultihopTrait for Multihop {
         let factory_client = factory_contract::Client::new(&env, &get_factory(&env));
 
         operations.iter().for_each(|op| {
-            let liquidity_pool_addr: Address = factory_client
-                .query_for_pool_by_token_pair(&op.clone().offer_asset, &op.ask_asset.clone());
-
-            let lp_client = lp_contract::Client::new(&env, &liquidity_pool_addr);
-            let simulated_swap = lp_client.simulate_swap(&op.offer_asset, &next_offer_amount);
+            let simulated_swap = LiquidityPool::for_operation(&env, &factory_client, &op)
+                .simulate_swap(&env, &op, next_offer_amount);
 
             let token_symbol = token_contract::Client::new(&env, &op.offer_asset).symbol();
 
             simulate_swap_response
                 .commission_amounts
                 .push_back((token_symbol, simulated_swap.commission_amount));
-            simulate_swap_response.ask_amount = simulated_swap.ask_amount;
+            simulate_swap_response.ask_amount = simulated_swap.amount;
             simulate_swap_response
                 .spread_amount
                 .push_back(simulated_swap.spread_amount);
 
-            next_offer_amount = simulated_swap.ask_amount;
+            next_offer_amount = simulated_swap.amount;
         });
 
         simulate_swap_response
@@ -166,27 +576,303 @@ impl MultihopTrait for Multihop {
         let factory_client = factory_contract::Client::new(&env, &get_factory(&env));
 
         operations.iter().for_each(|op| {
-            let liquidity_pool_addr: Address = factory_client
-                .query_for_pool_by_token_pair(&op.clone().offer_asset, &op.ask_asset.clone());
-
-            let lp_client = lp_contract::Client::new(&env, &liquidity_pool_addr);
-            let simulated_reverse_swap =
-                lp_client.simulate_reverse_swap(&op.ask_asset, &next_ask_amount);
+            let simulated_reverse_swap = LiquidityPool::for_operation(&env, &factory_client, &op)
+                .simulate_reverse_swap(&env, &op, next_ask_amount);
 
             let token_symbol = token_contract::Client::new(&env, &op.ask_asset).symbol();
 
             simulate_swap_response
                 .commission_amounts
                 .push_back((token_symbol, simulated_reverse_swap.commission_amount));
-            simulate_swap_response.offer_amount = simulated_reverse_swap.offer_amount;
+            simulate_swap_response.offer_amount = simulated_reverse_swap.amount;
 
             simulate_swap_response
                 .spread_amount
                 .push_back(simulated_reverse_swap.spread_amount);
 
-            next_ask_amount = simulated_reverse_swap.offer_amount;
+            next_ask_amount = simulated_reverse_swap.amount;
         });
 
         simulate_swap_response
     }
+
+    fn simulate_swap_split(env: Env, routes: Vec<(Vec<Swap>, i128)>) -> SimulateSwapSplitResponse {
+        verify_split_routes(&env, &routes);
+
+        let mut simulate_swap_split_response = SimulateSwapSplitResponse {
+            ask_amount: 0,
+            routes: vec![&env],
+        };
+        for (operations, amount) in routes.iter() {
+            let simulated_route = Self::simulate_swap(env.clone(), operations, amount);
+            simulate_swap_split_response.ask_amount += simulated_route.ask_amount;
+            simulate_swap_split_response
+                .routes
+                .push_back(simulated_route);
+        }
+
+        simulate_swap_split_response
+    }
+
+    fn find_best_route(
+        env: Env,
+        offer_asset: Address,
+        ask_asset: Address,
+        amount: i128,
+        max_hops: u32,
+    ) -> BestRouteResponse {
+        if max_hops == 0 || max_hops > MAX_HOPS {
+            log!(
+                &env,
+                "Multihop: Find best route: Maximum number of hops must be between 1 and {}",
+                MAX_HOPS
+            );
+            panic_with_error!(&env, ContractError::InvalidMaxHops);
+        }
+        if offer_asset == ask_asset {
+            log!(
+                &env,
+                "Multihop: Find best route: Offer and ask assets must be different"
+            );
+            panic_with_error!(&env, ContractError::IncorrectAssetSwap);
+        }
+
+        // the pools of every asset, together with the asset they swap it to. The factory only
+        // deploys pools of a token pair, stable pools included, so every pool links two assets
+        let factory_client = factory_contract::Client::new(&env, &get_factory(&env));
+        let mut pool_types: Map<Address, PairType> = Map::new(&env);
+        let mut adjacency: Map<Address, Vec<(Address, PairType, Address)>> = Map::new(&env);
+        for pool_info in factory_client.query_all_pools_details() {
+            let token_a = pool_info.pool_response.asset_a.address;
+            let token_b = pool_info.pool_response.asset_b.address;
+            // the pools of a pair are all typed by a single query
+            if !pool_types.contains_key(pool_info.pool_address.clone()) {
+                for info in factory_client.query_pools_for_pair(&token_a, &token_b) {
+                    pool_types.set(info.pool_address, info.pool_type);
+                }
+            }
+            let pool_type = pool_types
+                .get(pool_info.pool_address.clone())
+                .unwrap_or(PairType::Xyk);
+
+            for (asset, next_asset) in [(&token_a, &token_b), (&token_b, &token_a)] {
+                let mut edges = adjacency
+                    .get(asset.clone())
+                    .unwrap_or_else(|| Vec::new(&env));
+                edges.push_back((
+                    pool_info.pool_address.clone(),
+                    pool_type,
+                    next_asset.clone(),
+                ));
+                adjacency.set(asset.clone(), edges);
+            }
+        }
+
+        let mut route_search = RouteSearch {
+            adjacency,
+            ask_asset,
+            path: Vec::new(&env),
+            best: None,
+            candidates_left: MAX_ROUTE_CANDIDATES,
+        };
+        route_search.search(&env, &offer_asset, amount, max_hops);
+
+        route_search.best.unwrap_or_else(|| {
+            log!(&env, "Multihop: Find best route: No route found");
+            panic_with_error!(&env, ContractError::RouteNotFound);
+        })
+    }
+}
+
+// Checks the deadline and that swaps are not paused, and requires the authorization of `recipient`
+fn authorize_swap(env: &Env, recipient: &Address, deadline: Option<u64>) {
+    verify_deadline(env, deadline);
+
+    if is_paused(env) {
+        log!(env, "Multihop: Swap: Contract is paused");
+        panic_with_error!(env, ContractError::Paused);
+    }
+
+    recipient.require_auth();
+}
+
+// Simulates `operations` and returns the share of the value lost to the spread of all the swaps
+// together, without the commissions
+fn compute_price_impact(env: &Env, operations: &Vec<Swap>, amount: i128) -> Decimal {
+    let factory_client = factory_contract::Client::new(env, &get_factory(env));
+
+    let mut next_offer_amount = amount;
+    let mut retained = Decimal::one();
+    for op in operations.iter() {
+        let simulated_swap = LiquidityPool::for_operation(env, &factory_client, &op).simulate_swap(
+            env,
+            &op,
+            next_offer_amount,
+        );
+
+        let return_amount = simulated_swap.amount + simulated_swap.commission_amount;
+        retained = retained
+            * Decimal::from_ratio(return_amount, return_amount + simulated_swap.spread_amount);
+        next_offer_amount = simulated_swap.amount;
+    }
+
+    Decimal::one() - retained
+}
+
+// Lets the pool pull the offered amount from the multihop contract during the next swap
+fn authorize_pool_transfer(env: &Env, token: &Address, pool: &Address, amount: i128) {
+    env.authorize_as_current_contract(vec![
+        env,
+        InvokerContractAuthEntry::Contract(SubContractInvocation {
+            context: ContractContext {
+                contract: token.clone(),
+                fn_name: Symbol::new(env, "transfer"),
+                args: (env.current_contract_address(), pool.clone(), amount).into_val(env),
+            },
+            sub_invocations: vec![env],
+        }),
+    ]);
+}
+
+// Swaps `amount` of `sender` along `operations`, which have to be verified already, and sends
+// the last ask asset to `to`. The multihop contract swaps in its own name, so the intermediate
+// assets never reach `sender`. Returns the amount of the last ask asset
+#[allow(clippy::too_many_arguments)]
+fn do_swap(
+    env: &Env,
+    sender: &Address,
+    to: &Address,
+    referral: &Option<Referral>,
+    operations: &Vec<Swap>,
+    max_spread_bps: Option<i64>,
+    amount: i128,
+    deadline: Option<u64>,
+) -> i128 {
+    let contract = env.current_contract_address();
+    token_contract::Client::new(env, &operations.get(0).unwrap().offer_asset)
+        .transfer(sender, &contract, &amount);
+
+    // first offer amount is an input from the user,
+    // subsequent are the results of the previous swap
+    let mut next_offer_amount: i128 = amount;
+
+    let factory_client = factory_contract::Client::new(env, &get_factory(env));
+
+    // the referral is paid once, from the last ask asset, instead of taking its fee on every hop
+    let last_index = operations.len() - 1;
+    let no_referral = None;
+    operations.iter().enumerate().for_each(|(index, op)| {
+        let liquidity_pool = LiquidityPool::for_operation(env, &factory_client, &op);
+
+        authorize_pool_transfer(
+            env,
+            &op.offer_asset,
+            &liquidity_pool.address,
+            next_offer_amount,
+        );
+
+        next_offer_amount = liquidity_pool.swap(
+            env,
+            &contract,
+            if index as u32 == last_index {
+                referral
+            } else {
+                &no_referral
+            },
+            &op,
+            next_offer_amount,
+            max_spread_bps,
+            deadline,
+        );
+    });
+
+    token_contract::Client::new(env, &operations.last().unwrap().ask_asset).transfer(
+        &contract,
+        to,
+        &next_offer_amount,
+    );
+
+    next_offer_amount
+}
+
+/// Depth first search for the route that returns the most `ask_asset`. Every path is simulated hop
+/// by hop while it is extended and never visits the same asset twice. At most
+/// `MAX_ROUTE_CANDIDATES` paths are simulated, in the order of the pools in the factory.
+struct RouteSearch {
+    /// The pools of every asset with their type and the asset they swap it to
+    adjacency: Map<Address, Vec<(Address, PairType, Address)>>,
+    ask_asset: Address,
+    /// The swaps of the path that is being extended
+    path: Vec<Swap>,
+    best: Option<BestRouteResponse>,
+    /// The number of paths that can still be simulated
+    candidates_left: u32,
+}
+
+impl RouteSearch {
+    fn search(&mut self, env: &Env, offer_asset: &Address, offer_amount: i128, hops_left: u32) {
+        let edges = match self.adjacency.get(offer_asset.clone()) {
+            Some(edges) => edges,
+            None => return,
+        };
+
+        for (pool, pool_type, next_asset) in edges.iter() {
+            if self.candidates_left == 0 {
+                return;
+            }
+            if self.path.iter().any(|op| op.offer_asset == next_asset) {
+                continue;
+            }
+
+            let op = Swap {
+                offer_asset: offer_asset.clone(),
+                ask_asset: next_asset.clone(),
+                ask_asset_min_amount: None,
+                pool: PoolSelector::Pool(pool.clone()),
+            };
+
+            // pools that can't simulate the swap, like empty pools, are skipped
+            self.candidates_left -= 1;
+            let liquidity_pool = LiquidityPool {
+                address: pool,
+                pool_type,
+            };
+            let ask_amount = match liquidity_pool.try_simulate_swap(env, &op, offer_amount) {
+                Some(simulated_swap) if simulated_swap.amount > 0 => simulated_swap.amount,
+                _ => continue,
+            };
+
+            self.path.push_back(op);
+
+            if next_asset == self.ask_asset {
+                let is_better = match &self.best {
+                    Some(best) => ask_amount > best.ask_amount,
+                    None => true,
+                };
+                if is_better {
+                    self.best = Some(BestRouteResponse {
+                        operations: self.path.clone(),
+                        ask_amount,
+                    });
+                }
+            } else if hops_left > 1 {
+                self.search(env, &next_asset, ask_amount, hops_left - 1);
+            }
+
+            self.path.pop_back();
+        }
+    }
+}
+
+// `operation` names the admin operation in the log, like `pause`
+fn assert_admin(env: &Env, sender: &Address, operation: Symbol) {
+    if *sender != get_admin(env) {
+        log!(
+            env,
+            "Multihop: {}: Only the admin can {} the contract",
+            operation,
+            operation
+        );
+        panic_with_error!(env, ContractError::Unauthorized);
+    }
 }
@@ -7,4 +7,16 @@ pub enum ContractError {
     AlreadyInitialized = 1,
     OperationsEmpty = 2,
     IncorrectAssetSwap = 3,
+    DeadlineExceeded = 4,
+    Unauthorized = 5,
+    Paused = 6,
+    AdminChangeNotFound = 7,
+    AdminChangeExpired = 8,
+    PoolNotFound = 9,
+    RouteNotFound = 10,
+    InvalidMaxHops = 11,
+    MinimumAmountNotReached = 12,
+    PriceImpactTooHigh = 13,
+    MaxOfferAmountExceeded = 14,
+    PoolRepeatedInRoute = 15,
 }
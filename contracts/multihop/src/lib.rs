@@ -1,6 +1,7 @@
 #![no_std]
 mod contract;
 mod error;
+mod pool;
 mod storage;
 mod utils;
 
@@ -14,6 +15,13 @@ pub mod lp_contract {
     );
 }
 
+#[allow(clippy::too_many_arguments)]
+pub mod stable_lp_contract {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32-unknown-unknown/release/phoenix_pool_stable.wasm"
+    );
+}
+
 #[allow(clippy::too_many_arguments)]
 pub mod factory_contract {
     soroban_sdk::contractimport!(
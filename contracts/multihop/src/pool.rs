@@ -0,0 +1,203 @@
+use soroban_sdk::{log, panic_with_error, Address, Env};
+
+use crate::error::ContractError;
+use crate::factory_contract::{self, PairType};
+use crate::lp_contract;
+use crate::stable_lp_contract;
+use crate::storage::{PoolSelector, Referral, Swap};
+
+/// A pool of the factory together with its type, which decides the client used to call it.
+pub struct LiquidityPool {
+    pub address: Address,
+    pub pool_type: PairType,
+}
+
+/// The amounts of a single simulated swap. `amount` is the ask amount of a swap and the offer
+/// amount of a reverse swap.
+pub struct SimulatedSwap {
+    pub amount: i128,
+    pub commission_amount: i128,
+    pub spread_amount: i128,
+}
+
+impl LiquidityPool {
+    // Uses the pool picked for the operation when it belongs to the pair, otherwise the default
+    // pool of the pair
+    pub fn for_operation(env: &Env, factory_client: &factory_contract::Client, op: &Swap) -> Self {
+        let pair_pools = factory_client.query_pools_for_pair(&op.offer_asset, &op.ask_asset);
+        let address = match &op.pool {
+            PoolSelector::Pool(pool) => pool.clone(),
+            PoolSelector::Default => {
+                factory_client.query_for_pool_by_token_pair(&op.offer_asset, &op.ask_asset)
+            }
+        };
+
+        match pair_pools.iter().find(|info| info.pool_address == address) {
+            Some(info) => LiquidityPool {
+                address,
+                pool_type: info.pool_type,
+            },
+            None => {
+                log!(env, "Multihop: Pool does not belong to the token pair");
+                panic_with_error!(env, ContractError::PoolNotFound);
+            }
+        }
+    }
+
+    // Stable pools don't pay referral fees, so `referral` is only passed to XYK pools
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap(
+        &self,
+        env: &Env,
+        sender: &Address,
+        referral: &Option<Referral>,
+        op: &Swap,
+        offer_amount: i128,
+        max_spread_bps: Option<i64>,
+        deadline: Option<u64>,
+    ) -> i128 {
+        match self.pool_type {
+            PairType::Xyk => lp_contract::Client::new(env, &self.address).swap(
+                sender,
+                &referral.clone().map(|referral| lp_contract::Referral {
+                    address: referral.address,
+                    fee: referral.fee,
+                }),
+                &op.offer_asset,
+                &offer_amount,
+                &op.ask_asset_min_amount,
+                &max_spread_bps,
+                &deadline,
+            ),
+            PairType::Stable => stable_lp_contract::Client::new(env, &self.address).swap(
+                sender,
+                &op.offer_asset,
+                &op.ask_asset,
+                &offer_amount,
+                &op.ask_asset_min_amount,
+                &max_spread_bps,
+                &deadline,
+            ),
+        }
+    }
+
+    // The pool takes at most `max_offer_amount` of the offer asset for exactly `ask_amount`
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap_exact_out(
+        &self,
+        env: &Env,
+        sender: &Address,
+        op: &Swap,
+        ask_amount: i128,
+        max_offer_amount: i128,
+        max_spread_bps: Option<i64>,
+        deadline: Option<u64>,
+    ) -> i128 {
+        match self.pool_type {
+            PairType::Xyk => lp_contract::Client::new(env, &self.address).swap_exact_out(
+                sender,
+                &op.ask_asset,
+                &ask_amount,
+                &max_offer_amount,
+                &max_spread_bps,
+                &deadline,
+            ),
+            PairType::Stable => stable_lp_contract::Client::new(env, &self.address).swap_exact_out(
+                sender,
+                &op.offer_asset,
+                &op.ask_asset,
+                &ask_amount,
+                &max_offer_amount,
+                &max_spread_bps,
+                &deadline,
+            ),
+        }
+    }
+
+    pub fn simulate_swap(&self, env: &Env, op: &Swap, offer_amount: i128) -> SimulatedSwap {
+        match self.pool_type {
+            PairType::Xyk => {
+                let simulated_swap = lp_contract::Client::new(env, &self.address)
+                    .simulate_swap(&op.offer_asset, &offer_amount);
+                SimulatedSwap {
+                    amount: simulated_swap.ask_amount,
+                    commission_amount: simulated_swap.commission_amount,
+                    spread_amount: simulated_swap.spread_amount,
+                }
+            }
+            PairType::Stable => {
+                let simulated_swap = stable_lp_contract::Client::new(env, &self.address)
+                    .simulate_swap(&op.offer_asset, &op.ask_asset, &offer_amount);
+                SimulatedSwap {
+                    amount: simulated_swap.ask_amount,
+                    commission_amount: simulated_swap.commission_amount,
+                    spread_amount: simulated_swap.spread_amount,
+                }
+            }
+        }
+    }
+
+    // Returns `None` when the pool can't simulate the swap, like an empty pool
+    pub fn try_simulate_swap(
+        &self,
+        env: &Env,
+        op: &Swap,
+        offer_amount: i128,
+    ) -> Option<SimulatedSwap> {
+        match self.pool_type {
+            PairType::Xyk => match lp_contract::Client::new(env, &self.address)
+                .try_simulate_swap(&op.offer_asset, &offer_amount)
+            {
+                Ok(Ok(simulated_swap)) => Some(SimulatedSwap {
+                    amount: simulated_swap.ask_amount,
+                    commission_amount: simulated_swap.commission_amount,
+                    spread_amount: simulated_swap.spread_amount,
+                }),
+                _ => None,
+            },
+            PairType::Stable => match stable_lp_contract::Client::new(env, &self.address)
+                .try_simulate_swap(&op.offer_asset, &op.ask_asset, &offer_amount)
+            {
+                Ok(Ok(simulated_swap)) => Some(SimulatedSwap {
+                    amount: simulated_swap.ask_amount,
+                    commission_amount: simulated_swap.commission_amount,
+                    spread_amount: simulated_swap.spread_amount,
+                }),
+                _ => None,
+            },
+        }
+    }
+
+    // The offer amount the pool takes in `swap_exact_out` to return exactly `ask_amount`
+    pub fn exact_out_offer_amount(&self, env: &Env, op: &Swap, ask_amount: i128) -> i128 {
+        match self.pool_type {
+            PairType::Xyk => lp_contract::Client::new(env, &self.address)
+                .simulate_swap_exact_out(&op.ask_asset, &ask_amount),
+            PairType::Stable => stable_lp_contract::Client::new(env, &self.address)
+                .simulate_swap_exact_out(&op.offer_asset, &op.ask_asset, &ask_amount),
+        }
+    }
+
+    pub fn simulate_reverse_swap(&self, env: &Env, op: &Swap, ask_amount: i128) -> SimulatedSwap {
+        match self.pool_type {
+            PairType::Xyk => {
+                let simulated_swap = lp_contract::Client::new(env, &self.address)
+                    .simulate_reverse_swap(&op.ask_asset, &ask_amount);
+                SimulatedSwap {
+                    amount: simulated_swap.offer_amount,
+                    commission_amount: simulated_swap.commission_amount,
+                    spread_amount: simulated_swap.spread_amount,
+                }
+            }
+            PairType::Stable => {
+                let simulated_swap = stable_lp_contract::Client::new(env, &self.address)
+                    .simulate_reverse_swap(&op.offer_asset, &op.ask_asset, &ask_amount);
+                SimulatedSwap {
+                    amount: simulated_swap.offer_amount,
+                    commission_amount: simulated_swap.commission_amount,
+                    spread_amount: simulated_swap.spread_amount,
+                }
+            }
+        }
+    }
+}
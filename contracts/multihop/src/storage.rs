@@ -1,3 +1,4 @@
+use phoenix::utils::AdminChange;
 use soroban_sdk::{contracttype, Address, Env, String, Vec};
 
 #[contracttype]
@@ -6,6 +7,29 @@ pub struct Swap {
     pub ask_asset: Address,
     pub offer_asset: Address,
     pub ask_asset_min_amount: Option<i128>,
+    /// Pool to swap through
+    pub pool: PoolSelector,
+}
+
+/// Picks the pool of the pair a swap goes through
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PoolSelector {
+    /// The default pool of the pair, which is the first pool created for it
+    Default,
+    /// A pool of the pair picked by its address
+    Pool(Address),
+}
+
+/// The referral of a swap, which is paid `fee` bps of the amount returned by the last swap of a
+/// route when it goes through an XYK pool
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Referral {
+    /// Address of the referral
+    pub address: Address,
+    /// fee in bps, later parsed to percentage
+    pub fee: i64,
 }
 
 #[derive(Clone)]
@@ -22,6 +46,8 @@ pub enum DataKey {
     FactoryKey,
     Admin,
     Initialized,
+    Paused,
+    AdminChange,
 }
 
 #[contracttype]
@@ -53,6 +79,39 @@ pub fn get_factory(env: &Env) -> Address {
     env.storage().instance().get(&DataKey::FactoryKey).unwrap()
 }
 
+pub fn save_admin(env: &Env, admin: Address) {
+    env.storage().instance().set(&DataKey::Admin, &admin);
+}
+
+pub fn get_admin(env: &Env) -> Address {
+    env.storage().instance().get(&DataKey::Admin).unwrap()
+}
+
+pub fn save_admin_change(env: &Env, admin_change: &AdminChange) {
+    env.storage()
+        .instance()
+        .set(&DataKey::AdminChange, admin_change);
+}
+
+pub fn get_admin_change(env: &Env) -> Option<AdminChange> {
+    env.storage().instance().get(&DataKey::AdminChange)
+}
+
+pub fn remove_admin_change(env: &Env) {
+    env.storage().instance().remove(&DataKey::AdminChange);
+}
+
+pub fn save_paused(env: &Env, paused: bool) {
+    env.storage().instance().set(&DataKey::Paused, &paused);
+}
+
+pub fn is_paused(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::Paused)
+        .unwrap_or(false)
+}
+
 pub fn is_initialized(e: &Env) -> bool {
     e.storage()
         .persistent()
@@ -64,6 +123,16 @@ pub fn set_initialized(e: &Env) {
     e.storage().persistent().set(&DataKey::Initialized, &true);
 }
 
+/// The configuration of the multihop contract.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    pub admin: Address,
+    /// The factory whose pools are used for the swaps
+    pub factory: Address,
+    pub paused: bool,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SimulateSwapResponse {
@@ -81,3 +150,22 @@ pub struct SimulateReverseSwapResponse {
     pub commission_amounts: Vec<(String, i128)>,
     pub spread_amount: Vec<i128>,
 }
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimulateSwapSplitResponse {
+    /// The total amount of the ask asset returned by all the routes
+    pub ask_amount: i128,
+    /// The simulation of every route, in the order of the routes
+    pub routes: Vec<SimulateSwapResponse>,
+}
+
+/// The route with the best output found by `find_best_route`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BestRouteResponse {
+    /// The swaps of the route, each of them through the pool picked for it
+    pub operations: Vec<Swap>,
+    /// The simulated amount of the ask asset returned by the route
+    pub ask_amount: i128,
+}
@@ -1,8 +1,10 @@
 use crate::contract::{Multihop, MultihopClient};
-use crate::tests::setup::deploy_factory_contract;
+use crate::storage::Config;
+use crate::tests::setup::{deploy_factory_contract, install_multihop_wasm};
 use soroban_sdk::{testutils::Address as _, Address, Env};
 
 mod query;
+mod route;
 mod setup;
 mod swap;
 
@@ -20,3 +22,103 @@ fn test_deploy_multihop_twice_should_fail() {
     multihop.initialize(&admin, &factory);
     multihop.initialize(&admin, &factory);
 }
+
+#[test]
+fn propose_and_accept_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+
+    let multihop = MultihopClient::new(&env, &env.register_contract(None, Multihop {}));
+    multihop.initialize(&admin, &Address::generate(&env));
+
+    multihop.propose_admin(&admin, &new_admin, &1_000);
+    multihop.accept_admin(&new_admin);
+
+    assert!(multihop.try_pause(&admin).is_err());
+    multihop.pause(&new_admin);
+}
+
+#[test]
+#[should_panic(
+    expected = "Multihop: Accept admin: Only the proposed admin can accept the admin role"
+)]
+fn accept_admin_by_other_address_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let multihop = MultihopClient::new(&env, &env.register_contract(None, Multihop {}));
+    multihop.initialize(&admin, &Address::generate(&env));
+
+    multihop.propose_admin(&admin, &Address::generate(&env), &1_000);
+    multihop.accept_admin(&admin);
+}
+
+#[test]
+fn update_config_and_query_config() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let new_factory = Address::generate(&env);
+
+    let multihop = MultihopClient::new(&env, &env.register_contract(None, Multihop {}));
+    multihop.initialize(&admin, &factory);
+
+    assert_eq!(multihop.query_admin(), admin);
+    assert_eq!(
+        multihop.query_config(),
+        Config {
+            admin: admin.clone(),
+            factory,
+            paused: false,
+        }
+    );
+
+    multihop.update_config(&new_factory);
+    multihop.pause(&admin);
+
+    assert_eq!(
+        multihop.query_config(),
+        Config {
+            admin,
+            factory: new_factory,
+            paused: true,
+        }
+    );
+}
+
+#[test]
+#[should_panic]
+fn update_config_without_admin_auth_should_fail() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+
+    let multihop = MultihopClient::new(&env, &env.register_contract(None, Multihop {}));
+    multihop.initialize(&admin, &Address::generate(&env));
+
+    multihop.update_config(&Address::generate(&env));
+}
+
+#[test]
+fn upgrade_multihop_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+
+    let multihop = MultihopClient::new(&env, &env.register_contract(None, Multihop {}));
+    multihop.initialize(&admin, &factory);
+
+    let new_wasm_hash = install_multihop_wasm(&env);
+    multihop.upgrade(&new_wasm_hash);
+
+    assert_eq!(multihop.query_admin(), admin);
+}
@@ -1,7 +1,7 @@
-use crate::storage::Swap;
+use crate::storage::{PoolSelector, Swap};
 use crate::tests::setup::{
     create_token_contract_with_metadata, deploy_and_initialize_factory, deploy_and_initialize_lp,
-    deploy_multihop_contract, deploy_token_contract,
+    deploy_and_initialize_stable_lp, deploy_multihop_contract, deploy_token_contract,
 };
 
 use soroban_sdk::{testutils::Address as _, vec, Address, Env, String};
@@ -59,6 +59,7 @@ fn simulate_swap_single_pool_no_fees() {
             offer_asset: token1.address.clone(),
             ask_asset: token2.address.clone(),
             ask_asset_min_amount: None::<i128>,
+            pool: PoolSelector::Default,
         },
     ];
 
@@ -167,16 +168,19 @@ fn simulate_swap_three_equal_pools_no_fees() {
                 offer_asset: token1.address.clone(),
                 ask_asset: token2.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
             Swap {
                 offer_asset: token2.address.clone(),
                 ask_asset: token3.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
             Swap {
                 offer_asset: token3.address.clone(),
                 ask_asset: token4.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
         ],
         &50i128,
@@ -205,16 +209,19 @@ fn simulate_swap_three_equal_pools_no_fees() {
                 offer_asset: token3.address.clone(),
                 ask_asset: token4.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
             Swap {
                 offer_asset: token2.address.clone(),
                 ask_asset: token3.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
             Swap {
                 offer_asset: token1.address.clone(),
                 ask_asset: token2.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
         ],
         &50i128,
@@ -283,6 +290,7 @@ fn simulate_swap_single_pool_with_fees() {
             offer_asset: token1.address.clone(),
             ask_asset: token2.address.clone(),
             ask_asset_min_amount: None::<i128>,
+            pool: PoolSelector::Default,
         },
     ];
 
@@ -392,16 +400,19 @@ fn simulate_swap_three_different_pools_no_fees() {
                 offer_asset: token1.address.clone(),
                 ask_asset: token2.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
             Swap {
                 offer_asset: token2.address.clone(),
                 ask_asset: token3.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
             Swap {
                 offer_asset: token3.address.clone(),
                 ask_asset: token4.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
         ],
         &5_000i128,
@@ -432,16 +443,19 @@ fn simulate_swap_three_different_pools_no_fees() {
                 offer_asset: token3.address.clone(),
                 ask_asset: token4.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
             Swap {
                 offer_asset: token2.address.clone(),
                 ask_asset: token3.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
             Swap {
                 offer_asset: token1.address.clone(),
                 ask_asset: token2.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
         ],
         &4_956i128,
@@ -548,16 +562,19 @@ fn simulate_swap_three_different_pools_with_fees() {
                 offer_asset: token1.address.clone(),
                 ask_asset: token2.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
             Swap {
                 offer_asset: token2.address.clone(),
                 ask_asset: token3.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
             Swap {
                 offer_asset: token3.address.clone(),
                 ask_asset: token4.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
         ],
         &10_000i128,
@@ -610,16 +627,19 @@ fn simulate_swap_three_different_pools_with_fees() {
                 offer_asset: token3.address.clone(),
                 ask_asset: token4.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
             Swap {
                 offer_asset: token2.address.clone(),
                 ask_asset: token3.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
             Swap {
                 offer_asset: token1.address.clone(),
                 ask_asset: token2.address.clone(),
                 ask_asset_min_amount: None::<i128>,
+                pool: PoolSelector::Default,
             },
         ],
         &203_143i128,
@@ -679,3 +699,68 @@ fn query_simulate_reverse_swap_panics_with_no_operations() {
 
     multihop.simulate_reverse_swap(&swap_vec, &50i128);
 }
+
+#[test]
+fn simulate_swap_and_reverse_swap_through_stable_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+
+    let token1 = create_token_contract_with_metadata(
+        &env,
+        &admin,
+        7,
+        String::from_str(&env, "Token 1"),
+        String::from_str(&env, "TKN1"),
+        10_000_000i128,
+    );
+    let token2 = create_token_contract_with_metadata(
+        &env,
+        &admin,
+        7,
+        String::from_str(&env, "Token 2"),
+        String::from_str(&env, "TKN2"),
+        10_000_000i128,
+    );
+
+    let factory_client = deploy_and_initialize_factory(&env, admin.clone());
+    deploy_and_initialize_stable_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token1.address.clone(),
+        1_000_000,
+        token2.address.clone(),
+        1_000_000,
+        Some(100),
+    );
+
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+
+    let operations = vec![
+        &env,
+        Swap {
+            offer_asset: token1.address.clone(),
+            ask_asset: token2.address.clone(),
+            ask_asset_min_amount: None::<i128>,
+            pool: PoolSelector::Default,
+        },
+    ];
+
+    let simulated_swap = multihop.simulate_swap(&operations, &10_000i128);
+    // a balanced stable pool swaps close to 1:1 before the 1% commission
+    assert!(simulated_swap.ask_amount > 9_800 && simulated_swap.ask_amount < 9_900);
+    let (commission_symbol, commission_amount) = simulated_swap.commission_amounts.get(0).unwrap();
+    assert_eq!(commission_symbol, String::from_str(&env, "TKN1"));
+    assert!(commission_amount > 0);
+
+    // the stable pool leaves the commission out of the reverse simulation, so asking for the
+    // amount before the commission needs about the same offer amount, up to rounding
+    let simulated_reverse_swap = multihop.simulate_reverse_swap(
+        &operations,
+        &(simulated_swap.ask_amount + commission_amount),
+    );
+    assert!((simulated_reverse_swap.offer_amount - 10_000i128).abs() <= 10);
+}
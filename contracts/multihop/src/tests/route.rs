@@ -0,0 +1,418 @@
+use crate::storage::{PoolSelector, Swap};
+use crate::tests::setup::{
+    deploy_and_initialize_factory, deploy_and_initialize_lp, deploy_and_mint_tokens,
+    deploy_multihop_contract, factory, token_contract,
+};
+
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+/// Deploys three tokens with a shallow pool between the first and the last token, and deep pools
+/// from both of them to the middle token. Returns the factory, the tokens and the pools.
+fn deploy_triangle<'a>(
+    env: &'a Env,
+    admin: &'a Address,
+) -> (
+    factory::Client<'a>,
+    [token_contract::Client<'a>; 3],
+    [Address; 3],
+) {
+    let token1 = deploy_and_mint_tokens(env, admin, 10_000_000i128);
+    let token2 = deploy_and_mint_tokens(env, admin, 10_000_000i128);
+    let token3 = deploy_and_mint_tokens(env, admin, 10_000_000i128);
+
+    let factory_client = deploy_and_initialize_factory(env, admin.clone());
+
+    let direct_pool = deploy_and_initialize_lp(
+        env,
+        &factory_client,
+        admin.clone(),
+        token1.address.clone(),
+        10_000,
+        token3.address.clone(),
+        10_000,
+        None,
+    );
+    let first_pool = deploy_and_initialize_lp(
+        env,
+        &factory_client,
+        admin.clone(),
+        token1.address.clone(),
+        1_000_000,
+        token2.address.clone(),
+        1_000_000,
+        None,
+    );
+    let second_pool = deploy_and_initialize_lp(
+        env,
+        &factory_client,
+        admin.clone(),
+        token2.address.clone(),
+        1_000_000,
+        token3.address.clone(),
+        1_000_000,
+        None,
+    );
+
+    (
+        factory_client,
+        [token1, token2, token3],
+        [direct_pool, first_pool, second_pool],
+    )
+}
+
+#[test]
+fn find_best_route_prefers_deeper_path() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let (factory_client, [token1, token2, token3], [_, first_pool, second_pool]) =
+        deploy_triangle(&env, &admin);
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+
+    let best_route = multihop.find_best_route(&token1.address, &token3.address, &1_000, &3);
+
+    let expected_operations = vec![
+        &env,
+        Swap {
+            offer_asset: token1.address.clone(),
+            ask_asset: token2.address.clone(),
+            ask_asset_min_amount: None,
+            pool: PoolSelector::Pool(first_pool),
+        },
+        Swap {
+            offer_asset: token2.address.clone(),
+            ask_asset: token3.address.clone(),
+            ask_asset_min_amount: None,
+            pool: PoolSelector::Pool(second_pool),
+        },
+    ];
+    assert_eq!(best_route.operations, expected_operations);
+    assert_eq!(
+        best_route.ask_amount,
+        multihop
+            .simulate_swap(&expected_operations, &1_000)
+            .ask_amount
+    );
+}
+
+#[test]
+fn find_best_route_within_max_hops() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let (factory_client, [token1, _, token3], [direct_pool, _, _]) = deploy_triangle(&env, &admin);
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+
+    // the deeper path takes two hops, so only the direct pool is left
+    let best_route = multihop.find_best_route(&token1.address, &token3.address, &1_000, &1);
+
+    assert_eq!(
+        best_route.operations,
+        vec![
+            &env,
+            Swap {
+                offer_asset: token1.address.clone(),
+                ask_asset: token3.address.clone(),
+                ask_asset_min_amount: None,
+                pool: PoolSelector::Pool(direct_pool),
+            },
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Multihop: Find best route: No route found")]
+fn find_best_route_without_pools_for_asset_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let (factory_client, [token1, _, _], _) = deploy_triangle(&env, &admin);
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+
+    let unknown_token = deploy_and_mint_tokens(&env, &admin, 1_000i128);
+    multihop.find_best_route(&token1.address, &unknown_token.address, &1_000, &3);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #11)")]
+fn find_best_route_with_too_many_hops_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let (factory_client, [token1, _, token3], _) = deploy_triangle(&env, &admin);
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+
+    multihop.find_best_route(&token1.address, &token3.address, &1_000, &5);
+}
+
+#[test]
+fn find_best_route_among_many_pools() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let factory_client = deploy_and_initialize_factory(&env, admin.clone());
+    let tokens: [token_contract::Client; 8] =
+        core::array::from_fn(|_| deploy_and_mint_tokens(&env, &admin, 100_000_000i128));
+
+    // a ring of deep pools with a few shallow pools across it
+    let ring_pools: [Address; 8] = core::array::from_fn(|i| {
+        deploy_and_initialize_lp(
+            &env,
+            &factory_client,
+            admin.clone(),
+            tokens[i].address.clone(),
+            1_000_000,
+            tokens[(i + 1) % tokens.len()].address.clone(),
+            1_000_000,
+            None,
+        )
+    });
+    for (i, j) in [(0, 2), (0, 4), (1, 5), (2, 6), (3, 7)] {
+        deploy_and_initialize_lp(
+            &env,
+            &factory_client,
+            admin.clone(),
+            tokens[i].address.clone(),
+            10_000,
+            tokens[j].address.clone(),
+            10_000,
+            None,
+        );
+    }
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+
+    let best_route = multihop.find_best_route(&tokens[0].address, &tokens[2].address, &1_000, &3);
+
+    assert_eq!(
+        best_route.operations,
+        vec![
+            &env,
+            Swap {
+                offer_asset: tokens[0].address.clone(),
+                ask_asset: tokens[1].address.clone(),
+                ask_asset_min_amount: None,
+                pool: PoolSelector::Pool(ring_pools[0].clone()),
+            },
+            Swap {
+                offer_asset: tokens[1].address.clone(),
+                ask_asset: tokens[2].address.clone(),
+                ask_asset_min_amount: None,
+                pool: PoolSelector::Pool(ring_pools[1].clone()),
+            },
+        ]
+    );
+}
+
+#[test]
+fn swap_best_route() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let (factory_client, [token1, _, token3], _) = deploy_triangle(&env, &admin);
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+
+    let recipient = Address::generate(&env);
+    token1.mint(&recipient, &1_000i128);
+
+    let best_route = multihop.find_best_route(&token1.address, &token3.address, &1_000, &3);
+    let ask_amount = multihop.swap_best_route(
+        &recipient,
+        &token1.address,
+        &token3.address,
+        &1_000,
+        &3,
+        &best_route.ask_amount,
+        &None,
+        &None,
+    );
+
+    assert_eq!(ask_amount, best_route.ask_amount);
+    assert_eq!(token1.balance(&recipient), 0);
+    assert_eq!(token3.balance(&recipient), ask_amount);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #21)")]
+fn swap_best_route_below_minimum_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let (factory_client, [token1, _, token3], _) = deploy_triangle(&env, &admin);
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+
+    let recipient = Address::generate(&env);
+    token1.mint(&recipient, &1_000i128);
+
+    let best_route = multihop.find_best_route(&token1.address, &token3.address, &1_000, &3);
+    multihop.swap_best_route(
+        &recipient,
+        &token1.address,
+        &token3.address,
+        &1_000,
+        &3,
+        &(best_route.ask_amount + 1),
+        &None,
+        &None,
+    );
+}
+
+#[test]
+fn swap_split_matches_simulation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let (factory_client, [token1, token2, token3], _) = deploy_triangle(&env, &admin);
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+
+    let recipient = Address::generate(&env);
+    token1.mint(&recipient, &1_000i128);
+
+    let direct_route = vec![
+        &env,
+        Swap {
+            offer_asset: token1.address.clone(),
+            ask_asset: token3.address.clone(),
+            ask_asset_min_amount: None,
+            pool: PoolSelector::Default,
+        },
+    ];
+    let hop_route = vec![
+        &env,
+        Swap {
+            offer_asset: token1.address.clone(),
+            ask_asset: token2.address.clone(),
+            ask_asset_min_amount: None,
+            pool: PoolSelector::Default,
+        },
+        Swap {
+            offer_asset: token2.address.clone(),
+            ask_asset: token3.address.clone(),
+            ask_asset_min_amount: None,
+            pool: PoolSelector::Default,
+        },
+    ];
+    let routes = vec![&env, (direct_route, 100i128), (hop_route, 900i128)];
+
+    let simulated = multihop.simulate_swap_split(&routes);
+    assert_eq!(simulated.routes.len(), 2);
+    assert_eq!(
+        simulated.ask_amount,
+        simulated.routes.get(0).unwrap().ask_amount + simulated.routes.get(1).unwrap().ask_amount
+    );
+    assert_eq!(simulated.routes.get(1).unwrap().spread_amount.len(), 2);
+
+    let ask_amount = multihop.swap_split(&recipient, &routes, &simulated.ask_amount, &None, &None);
+
+    assert_eq!(ask_amount, simulated.ask_amount);
+    assert_eq!(token1.balance(&recipient), 0);
+    assert_eq!(token3.balance(&recipient), ask_amount);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #12)")]
+fn swap_split_below_min_total_out_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let (factory_client, [token1, token2, token3], _) = deploy_triangle(&env, &admin);
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+
+    let recipient = Address::generate(&env);
+    token1.mint(&recipient, &1_000i128);
+
+    let routes = vec![
+        &env,
+        (
+            vec![
+                &env,
+                Swap {
+                    offer_asset: token1.address.clone(),
+                    ask_asset: token3.address.clone(),
+                    ask_asset_min_amount: None,
+                    pool: PoolSelector::Default,
+                },
+            ],
+            500i128,
+        ),
+        (
+            vec![
+                &env,
+                Swap {
+                    offer_asset: token1.address.clone(),
+                    ask_asset: token2.address.clone(),
+                    ask_asset_min_amount: None,
+                    pool: PoolSelector::Default,
+                },
+                Swap {
+                    offer_asset: token2.address.clone(),
+                    ask_asset: token3.address.clone(),
+                    ask_asset_min_amount: None,
+                    pool: PoolSelector::Default,
+                },
+            ],
+            500i128,
+        ),
+    ];
+
+    multihop.swap_split(&recipient, &routes, &1_000, &None, &None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn swap_split_with_different_ask_assets_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let (factory_client, [token1, token2, token3], _) = deploy_triangle(&env, &admin);
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+
+    let routes = vec![
+        &env,
+        (
+            vec![
+                &env,
+                Swap {
+                    offer_asset: token1.address.clone(),
+                    ask_asset: token3.address.clone(),
+                    ask_asset_min_amount: None,
+                    pool: PoolSelector::Default,
+                },
+            ],
+            500i128,
+        ),
+        (
+            vec![
+                &env,
+                Swap {
+                    offer_asset: token1.address.clone(),
+                    ask_asset: token2.address.clone(),
+                    ask_asset_min_amount: None,
+                    pool: PoolSelector::Default,
+                },
+            ],
+            500i128,
+        ),
+    ];
+
+    multihop.simulate_swap_split(&routes);
+}
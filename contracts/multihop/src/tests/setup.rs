@@ -1,4 +1,5 @@
 use crate::contract::{Multihop, MultihopClient};
+use crate::stable_lp_contract;
 use crate::tests::setup::factory::{LiquidityPoolInitInfo, StakeInitInfo, TokenInitInfo};
 
 use soroban_sdk::{
@@ -46,6 +47,13 @@ pub fn install_lp_contract(env: &Env) -> BytesN<32> {
     env.deployer().upload_contract_wasm(lp_contract::WASM)
 }
 
+pub fn install_stable_lp_contract(env: &Env) -> BytesN<32> {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32-unknown-unknown/release/phoenix_pool_stable.wasm"
+    );
+    env.deployer().upload_contract_wasm(WASM)
+}
+
 pub fn install_token_wasm(env: &Env) -> BytesN<32> {
     soroban_sdk::contractimport!(
         file = "../../target/wasm32-unknown-unknown/release/soroban_token_contract.wasm"
@@ -110,6 +118,8 @@ pub fn deploy_and_initialize_factory(env: &Env, admin: Address) -> factory::Clie
     let whitelisted_accounts = vec![env, admin.clone()];
 
     let lp_wasm_hash = install_lp_contract(env);
+
+    let stable_wasm_hash = install_stable_lp_contract(env);
     let stake_wasm_hash = install_stake_wasm(env);
     let token_wasm_hash = install_token_wasm(env);
 
@@ -117,6 +127,7 @@ pub fn deploy_and_initialize_factory(env: &Env, admin: Address) -> factory::Clie
         &admin.clone(),
         &multihop_wasm_hash,
         &lp_wasm_hash,
+        &stable_wasm_hash,
         &stake_wasm_hash,
         &token_wasm_hash,
         &whitelisted_accounts,
@@ -135,7 +146,7 @@ pub fn deploy_and_initialize_lp(
     mut token_b: Address,
     mut token_b_amount: i128,
     fees: Option<i64>,
-) {
+) -> Address {
     // 2. create liquidity pool from factory
 
     if token_b < token_a {
@@ -170,6 +181,8 @@ pub fn deploy_and_initialize_lp(
         &lp_init_info,
         &String::from_str(env, "Pool"),
         &String::from_str(env, "PHO/XLM"),
+        &factory::PairType::Xyk,
+        &None,
     );
 
     let lp_client = lp_contract::Client::new(env, &lp);
@@ -180,5 +193,59 @@ pub fn deploy_and_initialize_lp(
         &Some(token_b_amount),
         &None,
         &None::<i64>,
+        &None,
     );
+
+    lp
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn deploy_and_initialize_stable_lp(
+    env: &Env,
+    factory: &factory::Client,
+    admin: Address,
+    mut token_a: Address,
+    mut token_a_amount: i128,
+    mut token_b: Address,
+    mut token_b_amount: i128,
+    fees: Option<i64>,
+) -> Address {
+    if token_b < token_a {
+        std::mem::swap(&mut token_a, &mut token_b);
+        std::mem::swap(&mut token_a_amount, &mut token_b_amount);
+    }
+
+    let lp_init_info = LiquidityPoolInitInfo {
+        admin: admin.clone(),
+        fee_recipient: admin.clone(),
+        max_allowed_slippage_bps: 5000,
+        max_allowed_spread_bps: 500,
+        swap_fee_bps: fees.unwrap_or(0i64),
+        max_referral_bps: 5_000,
+        token_init_info: TokenInitInfo { token_a, token_b },
+        stake_init_info: StakeInitInfo {
+            min_bond: 10i128,
+            min_reward: 5i128,
+            manager: Address::generate(env),
+            max_complexity: 10u32,
+        },
+    };
+
+    let lp = factory.create_liquidity_pool(
+        &admin.clone(),
+        &lp_init_info,
+        &String::from_str(env, "Pool"),
+        &String::from_str(env, "PHO/XLM"),
+        &factory::PairType::Stable,
+        &Some(6u64),
+    );
+
+    stable_lp_contract::Client::new(env, &lp).provide_liquidity(
+        &admin,
+        &vec![env, token_a_amount, token_b_amount],
+        &None,
+        &None,
+    );
+
+    lp
 }
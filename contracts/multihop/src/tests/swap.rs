@@ -1,13 +1,15 @@
-// FIXM: Disable Referral struct
-// use crate::lp_contract::Referral;
-use crate::storage::Swap;
+use crate::lp_contract;
+use crate::storage::{PoolSelector, Referral, Swap};
 use crate::tests::setup::{
-    deploy_and_initialize_factory, deploy_and_initialize_lp, deploy_and_mint_tokens,
-    deploy_multihop_contract, deploy_token_contract,
+    deploy_and_initialize_factory, deploy_and_initialize_lp, deploy_and_initialize_stable_lp,
+    deploy_and_mint_tokens, deploy_multihop_contract, deploy_token_contract,
 };
 
 use soroban_sdk::contracterror;
-use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+use soroban_sdk::{
+    testutils::{Address as _, MockAuth, MockAuthInvoke},
+    vec, Address, Env, IntoVal,
+};
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -75,31 +77,40 @@ fn swap_three_equal_pools_no_fees() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
     };
     let swap2 = Swap {
         offer_asset: token2.address.clone(),
         ask_asset: token3.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
     };
     let swap3 = Swap {
         offer_asset: token3.address.clone(),
         ask_asset: token4.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
     };
 
     let operations = vec![&env, swap1, swap2, swap3];
 
-    // FIXM: Disable Referral struct
-    // multihop.swap(&recipient, &None, &operations, &None, &None, &50i128);
-    multihop.swap(&recipient, &operations, &None, &50i128);
+    multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &operations,
+        &None,
+        &50i128,
+        &None,
+        &None,
+        &None,
+    );
 
     // 5. check if it goes according to plan
     assert_eq!(token1.balance(&recipient), 0i128);
     assert_eq!(token4.balance(&recipient), 50i128);
 }
 
-// FIXM: Disable Referral struct
-#[ignore]
 #[test]
 fn swap_three_equal_pools_no_fees_referral_fee() {
     let env = Env::default();
@@ -159,45 +170,48 @@ fn swap_three_equal_pools_no_fees_referral_fee() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
     };
     let swap2 = Swap {
         offer_asset: token2.address.clone(),
         ask_asset: token3.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
     };
     let swap3 = Swap {
         offer_asset: token3.address.clone(),
         ask_asset: token4.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
     };
 
     let operations = vec![&env, swap1, swap2, swap3];
     let referral_addr = Address::generate(&env);
-    // FIXM: Disable Referral struct
-    // let referral = Referral {
-    //     address: referral_addr.clone(),
-    //     fee: 1_000,
-    // };
-
-    // multihop.swap(
-    //     &recipient,
-    //     &Some(referral),
-    //     &operations,
-    //     &None,
-    //     &None,
-    //     &50i128,
-    // );
-    multihop.swap(&recipient, &operations, &None, &50i128);
+    let referral = Referral {
+        address: referral_addr.clone(),
+        fee: 1_000,
+    };
+
+    multihop.swap(
+        &recipient,
+        &recipient,
+        &Some(referral),
+        &operations,
+        &None,
+        &50i128,
+        &None,
+        &None,
+        &None,
+    );
 
     // 5. check if it goes according to plan
     assert_eq!(token1.balance(&recipient), 0i128);
-    assert_eq!(token4.balance(&recipient), 37i128);
-    // referral fee from first swap should be 5 (10% out of 50)
-    assert_eq!(token2.balance(&referral_addr), 5i128);
-    // referral fee from 2nd swap should be 4 (10% out of 45) rounded down
-    assert_eq!(token3.balance(&referral_addr), 4i128);
-    // referral fee from the last swap should also be 4 (10% out of 41) rounded down
-    assert_eq!(token4.balance(&referral_addr), 4i128);
+    assert_eq!(token4.balance(&recipient), 45i128);
+    // the intermediate swaps pay no referral fee
+    assert_eq!(token2.balance(&referral_addr), 0i128);
+    assert_eq!(token3.balance(&referral_addr), 0i128);
+    // the referral fee is taken once from the last swap, 10% out of 50
+    assert_eq!(token4.balance(&referral_addr), 5i128);
 }
 
 #[test]
@@ -236,13 +250,22 @@ fn swap_single_pool_no_fees() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
     };
 
     let operations = vec![&env, swap1];
 
-    // FIXM: Disable Referral struct
-    // multihop.swap(&recipient, /*&None,*/ &operations, &None, &None, &50i128);
-    multihop.swap(&recipient, &operations, &None, &1_000);
+    multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &operations,
+        &None,
+        &1_000,
+        &None,
+        &None,
+        &None,
+    );
 
     // 5. check if it goes according to plan
     assert_eq!(token1.balance(&recipient), 4_000i128); // -1_000 token0
@@ -283,13 +306,22 @@ fn swap_should_fail_when_spread_exceeds_the_limit() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
     };
 
     let operations = vec![&env, swap1];
 
-    // FIXM: Disable Referral struct
-    // multihop.swap(&recipient, &None, &operations, &None, &Some(50), &50);
-    multihop.swap(&recipient, &operations, &Some(50), &50);
+    multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &operations,
+        &Some(50),
+        &50,
+        &None,
+        &None,
+        &None,
+    );
 }
 
 #[test]
@@ -328,13 +360,22 @@ fn swap_single_pool_with_fees() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
     };
 
     let operations = vec![&env, swap1];
 
-    // FIXM: Disable Referral struct
-    // multihop.swap(&recipient, &None, &operations, &None, &None, &300i128);
-    multihop.swap(&recipient, &operations, &None, &300i128);
+    multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &operations,
+        &None,
+        &300i128,
+        &None,
+        &None,
+        &None,
+    );
 
     // 5. check if it goes according to plan
     // 1000 tokens initially
@@ -404,23 +445,34 @@ fn swap_three_different_pools_no_fees() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
     };
     let swap2 = Swap {
         offer_asset: token2.address.clone(),
         ask_asset: token3.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
     };
     let swap3 = Swap {
         offer_asset: token3.address.clone(),
         ask_asset: token4.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
     };
 
     let operations = vec![&env, swap1, swap2, swap3];
 
-    // FIXM: Disable Referral struct
-    // multihop.swap(&recipient, &None, &operations, &None, &None, &5_000i128);
-    multihop.swap(&recipient, &operations, &None, &5_000i128);
+    multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &operations,
+        &None,
+        &5_000i128,
+        &None,
+        &None,
+        &None,
+    );
 
     // 5. check if it goes according to plan
     assert_eq!(token1.balance(&recipient), 0i128);
@@ -489,23 +541,34 @@ fn swap_three_different_pools_with_fees() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
     };
     let swap2 = Swap {
         offer_asset: token2.address.clone(),
         ask_asset: token3.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
     };
     let swap3 = Swap {
         offer_asset: token3.address.clone(),
         ask_asset: token4.address.clone(),
         ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
     };
 
     let operations = vec![&env, swap1, swap2, swap3];
 
-    // FIXM: Disable Referral struct
-    // multihop.swap(&recipient, &None, &operations, &None, &None, &10_000i128);
-    multihop.swap(&recipient, &operations, &None, &10_000i128);
+    multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &operations,
+        &None,
+        &10_000i128,
+        &None,
+        &None,
+        &None,
+    );
 
     // we start swapping 10_000 tokens
 
@@ -548,9 +611,9 @@ fn swap_panics_with_no_operations() {
 
     let swap_vec = vec![&env];
 
-    // FIXM: Disable Referral struct
-    // multihop.swap(&recipient, &None, &swap_vec, &None, &None, &50i128);
-    multihop.swap(&recipient, &swap_vec, &None, &50i128);
+    multihop.swap(
+        &recipient, &recipient, &None, &swap_vec, &None, &50i128, &None, &None, &None,
+    );
 }
 
 #[test]
@@ -607,21 +670,34 @@ fn test_v_phx_vul_013_add_belief_price_for_every_swap() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: Some(1_050),
+        pool: PoolSelector::Default,
     };
     let swap2 = Swap {
         offer_asset: token2.address.clone(),
         ask_asset: token3.address.clone(),
         ask_asset_min_amount: Some(2_100),
+        pool: PoolSelector::Default,
     };
     let swap3 = Swap {
         offer_asset: token3.address.clone(),
         ask_asset: token4.address.clone(),
         ask_asset_min_amount: Some(3_150),
+        pool: PoolSelector::Default,
     };
 
     let operations = vec![&env, swap1, swap2, swap3];
 
-    multihop.swap(&recipient, &operations, &None, &5_000i128);
+    multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &operations,
+        &None,
+        &5_000i128,
+        &None,
+        &None,
+        &None,
+    );
 
     assert_eq!(
         token1.balance(&recipient),
@@ -670,11 +746,22 @@ fn test_swap_with_ask_asset_min_amount() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: Some(1_000),
+        pool: PoolSelector::Default,
     };
 
     let operations = vec![&env, swap1];
 
-    multihop.swap(&recipient, &operations, &None, &1_000);
+    multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &operations,
+        &None,
+        &1_000,
+        &None,
+        &None,
+        &None,
+    );
 
     assert_eq!(token1.balance(&recipient), 4_000i128);
     assert_eq!(token2.balance(&recipient), 1_000i128);
@@ -683,7 +770,850 @@ fn test_swap_with_ask_asset_min_amount() {
         offer_asset: token1.address.clone(),
         ask_asset: token2.address.clone(),
         ask_asset_min_amount: Some(10_000),
+        pool: PoolSelector::Default,
     };
     let operations = vec![&env, greedy_swap];
-    multihop.swap(&recipient, &operations, &None, &1_000);
+    multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &operations,
+        &None,
+        &1_000,
+        &None,
+        &None,
+        &None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Multihop: Swap: Contract is paused")]
+fn swap_when_paused_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    let token1 = deploy_token_contract(&env, &admin);
+    let token2 = deploy_token_contract(&env, &admin);
+    token1.mint(&recipient, &50i128);
+
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory);
+    multihop.pause(&admin);
+
+    let swap = Swap {
+        offer_asset: token1.address.clone(),
+        ask_asset: token2.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
+    };
+
+    multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &vec![&env, swap],
+        &None,
+        &50i128,
+        &None,
+        &None,
+        &None,
+    );
+}
+
+#[test]
+fn swap_after_unpause() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let token1 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+    let token2 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+
+    let factory_client = deploy_and_initialize_factory(&env, admin.clone());
+    deploy_and_initialize_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token1.address.clone(),
+        1_000_000,
+        token2.address.clone(),
+        1_000_000,
+        None,
+    );
+
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+    let recipient = Address::generate(&env);
+    token1.mint(&recipient, &50i128);
+
+    let swap = Swap {
+        offer_asset: token1.address.clone(),
+        ask_asset: token2.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
+    };
+    let operations = vec![&env, swap];
+
+    multihop.pause(&admin);
+    assert!(multihop
+        .try_swap(
+            &recipient,
+            &recipient,
+            &None,
+            &operations,
+            &None,
+            &50i128,
+            &None,
+            &None,
+            &None
+        )
+        .is_err());
+
+    multihop.unpause(&admin);
+    multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &operations,
+        &None,
+        &50i128,
+        &None,
+        &None,
+        &None,
+    );
+
+    assert_eq!(token1.balance(&recipient), 0i128);
+    assert_eq!(token2.balance(&recipient), 50i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn pause_by_unauthorized_user_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+
+    let multihop = deploy_multihop_contract(&env, admin, &factory);
+
+    multihop.pause(&Address::generate(&env));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn unpause_by_unauthorized_user_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory);
+    multihop.pause(&admin);
+
+    multihop.unpause(&Address::generate(&env));
+}
+
+#[test]
+fn swap_through_selected_pool() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let token1 = deploy_and_mint_tokens(&env, &admin, 2_001_000i128);
+    let token2 = deploy_and_mint_tokens(&env, &admin, 2_001_000i128);
+
+    let factory_client = deploy_and_initialize_factory(&env, admin.clone());
+
+    // the pool without fees is created first and becomes the default pool of the pair
+    deploy_and_initialize_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token1.address.clone(),
+        1_000_000,
+        token2.address.clone(),
+        1_000_000,
+        None,
+    );
+    let fee_pool = deploy_and_initialize_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token1.address.clone(),
+        1_000_000,
+        token2.address.clone(),
+        1_000_000,
+        Some(2000),
+    );
+
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+    let recipient = Address::generate(&env);
+    token1.mint(&recipient, &5_000i128);
+
+    let swap = Swap {
+        offer_asset: token1.address.clone(),
+        ask_asset: token2.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Pool(fee_pool),
+    };
+    multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &vec![&env, swap],
+        &None,
+        &300i128,
+        &None,
+        &None,
+        &None,
+    );
+
+    // swap 300 with 2000 bps (20%) fees
+    assert_eq!(token1.balance(&recipient), 4_700i128);
+    assert_eq!(token2.balance(&recipient), 240i128);
+
+    let swap = Swap {
+        offer_asset: token1.address.clone(),
+        ask_asset: token2.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
+    };
+    multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &vec![&env, swap],
+        &None,
+        &1_000i128,
+        &None,
+        &None,
+        &None,
+    );
+
+    // the default pool has no fees
+    assert_eq!(token1.balance(&recipient), 3_700i128);
+    assert_eq!(token2.balance(&recipient), 1_240i128);
+}
+
+#[test]
+#[should_panic(expected = "Multihop: Pool does not belong to the token pair")]
+fn swap_through_pool_of_another_pair_should_fail() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let token1 = deploy_and_mint_tokens(&env, &admin, 1_001_000i128);
+    let token2 = deploy_and_mint_tokens(&env, &admin, 2_000_000i128);
+    let token3 = deploy_and_mint_tokens(&env, &admin, 1_001_000i128);
+
+    let factory_client = deploy_and_initialize_factory(&env, admin.clone());
+
+    deploy_and_initialize_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token1.address.clone(),
+        1_000_000,
+        token2.address.clone(),
+        1_000_000,
+        None,
+    );
+    let other_pool = deploy_and_initialize_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token2.address.clone(),
+        1_000_000,
+        token3.address.clone(),
+        1_000_000,
+        None,
+    );
+
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+    let recipient = Address::generate(&env);
+    token1.mint(&recipient, &1_000i128);
+
+    let swap = Swap {
+        offer_asset: token1.address.clone(),
+        ask_asset: token2.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Pool(other_pool),
+    };
+    multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &vec![&env, swap],
+        &None,
+        &1_000i128,
+        &None,
+        &None,
+        &None,
+    );
+}
+
+#[test]
+fn swap_returns_final_amount_within_route_limits() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let token1 = deploy_and_mint_tokens(&env, &admin, 20_000i128);
+    let token2 = deploy_and_mint_tokens(&env, &admin, 20_000i128);
+
+    let factory_client = deploy_and_initialize_factory(&env, admin.clone());
+    deploy_and_initialize_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token1.address.clone(),
+        10_000,
+        token2.address.clone(),
+        10_000,
+        None,
+    );
+
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+    let recipient = Address::generate(&env);
+    token1.mint(&recipient, &400i128);
+
+    let swap = Swap {
+        offer_asset: token1.address.clone(),
+        ask_asset: token2.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
+    };
+
+    // 10_000 - 10_000 * 10_000 / 10_400 = 385, the spread of 15 is a 3.75% price impact
+    let ask_amount = multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &vec![&env, swap],
+        &None,
+        &400,
+        &Some(385),
+        &Some(400),
+        &None,
+    );
+
+    assert_eq!(ask_amount, 385i128);
+    assert_eq!(token2.balance(&recipient), 385i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #12)")]
+fn swap_below_min_final_amount_should_fail() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let token1 = deploy_and_mint_tokens(&env, &admin, 20_000i128);
+    let token2 = deploy_and_mint_tokens(&env, &admin, 20_000i128);
+
+    let factory_client = deploy_and_initialize_factory(&env, admin.clone());
+    deploy_and_initialize_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token1.address.clone(),
+        10_000,
+        token2.address.clone(),
+        10_000,
+        None,
+    );
+
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+    let recipient = Address::generate(&env);
+    token1.mint(&recipient, &400i128);
+
+    let swap = Swap {
+        offer_asset: token1.address.clone(),
+        ask_asset: token2.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
+    };
+
+    multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &vec![&env, swap],
+        &None,
+        &400,
+        &Some(386),
+        &None,
+        &None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")]
+fn swap_above_max_price_impact_should_fail() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let token1 = deploy_and_mint_tokens(&env, &admin, 20_000i128);
+    let token2 = deploy_and_mint_tokens(&env, &admin, 20_000i128);
+
+    let factory_client = deploy_and_initialize_factory(&env, admin.clone());
+    deploy_and_initialize_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token1.address.clone(),
+        10_000,
+        token2.address.clone(),
+        10_000,
+        None,
+    );
+
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+    let recipient = Address::generate(&env);
+    token1.mint(&recipient, &400i128);
+
+    let swap = Swap {
+        offer_asset: token1.address.clone(),
+        ask_asset: token2.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
+    };
+
+    multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &vec![&env, swap],
+        &None,
+        &400,
+        &None,
+        &Some(300),
+        &None,
+    );
+}
+
+#[test]
+fn swap_pays_out_to_another_address() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let token1 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+    let token2 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+    let token3 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+
+    let factory_client = deploy_and_initialize_factory(&env, admin.clone());
+    deploy_and_initialize_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token1.address.clone(),
+        1_000_000,
+        token2.address.clone(),
+        1_000_000,
+        None,
+    );
+    deploy_and_initialize_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token2.address.clone(),
+        1_000_000,
+        token3.address.clone(),
+        1_000_000,
+        None,
+    );
+
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+    let sender = Address::generate(&env);
+    let to = Address::generate(&env);
+    token1.mint(&sender, &50i128);
+
+    let swap1 = Swap {
+        offer_asset: token1.address.clone(),
+        ask_asset: token2.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
+    };
+    let swap2 = Swap {
+        offer_asset: token2.address.clone(),
+        ask_asset: token3.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
+    };
+
+    let ask_amount = multihop.swap(
+        &sender,
+        &to,
+        &None,
+        &vec![&env, swap1, swap2],
+        &None,
+        &50i128,
+        &None,
+        &None,
+        &None,
+    );
+
+    assert_eq!(ask_amount, 50i128);
+    assert_eq!(token1.balance(&sender), 0i128);
+    assert_eq!(token3.balance(&sender), 0i128);
+    assert_eq!(token3.balance(&to), 50i128);
+    // the intermediate asset never reaches the sender and doesn't stay in the multihop contract
+    assert_eq!(token2.balance(&sender), 0i128);
+    assert_eq!(token2.balance(&multihop.address), 0i128);
+    assert_eq!(token3.balance(&multihop.address), 0i128);
+}
+
+#[test]
+fn swap_through_xyk_and_stable_pools() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let token1 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+    let token2 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+    let token3 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+
+    let factory_client = deploy_and_initialize_factory(&env, admin.clone());
+    deploy_and_initialize_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token1.address.clone(),
+        1_000_000,
+        token2.address.clone(),
+        1_000_000,
+        None,
+    );
+    deploy_and_initialize_stable_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token2.address.clone(),
+        1_000_000,
+        token3.address.clone(),
+        1_000_000,
+        None,
+    );
+
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+    let recipient = Address::generate(&env);
+    token1.mint(&recipient, &1_000i128);
+
+    let swap1 = Swap {
+        offer_asset: token1.address.clone(),
+        ask_asset: token2.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
+    };
+    let swap2 = Swap {
+        offer_asset: token2.address.clone(),
+        ask_asset: token3.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
+    };
+    let operations = vec![&env, swap1, swap2];
+
+    let simulated_swap = multihop.simulate_swap(&operations, &1_000);
+    assert_eq!(simulated_swap.spread_amount.len(), 2);
+
+    let ask_amount = multihop.swap(
+        &recipient,
+        &recipient,
+        &None,
+        &operations,
+        &None,
+        &1_000,
+        &None,
+        &None,
+        &None,
+    );
+
+    assert!(ask_amount > 0);
+    assert_eq!(ask_amount, simulated_swap.ask_amount);
+    assert_eq!(token1.balance(&recipient), 0i128);
+    assert_eq!(token3.balance(&recipient), ask_amount);
+}
+
+#[test]
+fn swap_exact_out_returns_exact_ask_amount() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let token1 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+    let token2 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+    let token3 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+
+    let factory_client = deploy_and_initialize_factory(&env, admin.clone());
+    deploy_and_initialize_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token1.address.clone(),
+        1_000_000,
+        token2.address.clone(),
+        2_000_000,
+        Some(100),
+    );
+    deploy_and_initialize_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token2.address.clone(),
+        1_000_000,
+        token3.address.clone(),
+        3_000_000,
+        Some(300),
+    );
+
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+    let sender = Address::generate(&env);
+    token1.mint(&sender, &10_000i128);
+
+    // the operations are given from the last swap back to the first one
+    let swap1 = Swap {
+        offer_asset: token2.address.clone(),
+        ask_asset: token3.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
+    };
+    let swap2 = Swap {
+        offer_asset: token1.address.clone(),
+        ask_asset: token2.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
+    };
+    let operations = vec![&env, swap1, swap2];
+
+    let offer_amount =
+        multihop.swap_exact_out(&sender, &operations, &5_000i128, &10_000i128, &None, &None);
+
+    assert!(offer_amount > 0 && offer_amount <= 10_000);
+    assert_eq!(token1.balance(&sender), 10_000i128 - offer_amount);
+    assert_eq!(token3.balance(&sender), 5_000i128);
+    assert_eq!(token2.balance(&sender), 0i128);
+    assert_eq!(token1.balance(&multihop.address), 0i128);
+    assert_eq!(token2.balance(&multihop.address), 0i128);
+    assert_eq!(token3.balance(&multihop.address), 0i128);
+}
+
+#[test]
+fn swap_exact_out_with_fees_takes_the_pool_offer_amounts() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let token1 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+    let token2 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+    let token3 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+
+    let factory_client = deploy_and_initialize_factory(&env, admin.clone());
+    let pool1 = deploy_and_initialize_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token1.address.clone(),
+        1_000_000,
+        token2.address.clone(),
+        1_000_000,
+        Some(1_000),
+    );
+    let pool2 = deploy_and_initialize_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token2.address.clone(),
+        1_000_000,
+        token3.address.clone(),
+        1_000_000,
+        Some(1_000),
+    );
+
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+    let sender = Address::generate(&env);
+    token1.mint(&sender, &10_000i128);
+
+    let swap1 = Swap {
+        offer_asset: token2.address.clone(),
+        ask_asset: token3.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
+    };
+    let swap2 = Swap {
+        offer_asset: token1.address.clone(),
+        ask_asset: token2.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
+    };
+    let operations = vec![&env, swap1, swap2];
+
+    // 1_000 / (1 - 10%) = 1_112 of token3 has to leave the second pool, which costs 1_113 of
+    // token2, and 1_237 of token2 has to leave the first pool, which costs 1_238 of token1
+    let pool1_client = lp_contract::Client::new(&env, &pool1);
+    let pool2_client = lp_contract::Client::new(&env, &pool2);
+    assert_eq!(
+        pool2_client.simulate_swap_exact_out(&token3.address, &1_000i128),
+        1_113i128
+    );
+    assert_eq!(
+        pool1_client.simulate_swap_exact_out(&token2.address, &1_113i128),
+        1_238i128
+    );
+
+    // only the sender signs the swap and the transfer of the offer amount to the multihop, the
+    // multihop authorizes the transfers to the pools itself
+    env.mock_auths(&[MockAuth {
+        address: &sender,
+        invoke: &MockAuthInvoke {
+            contract: &multihop.address,
+            fn_name: "swap_exact_out",
+            args: (
+                &sender,
+                operations.clone(),
+                1_000i128,
+                1_238i128,
+                None::<i64>,
+                None::<u64>,
+            )
+                .into_val(&env),
+            sub_invokes: &[MockAuthInvoke {
+                contract: &token1.address,
+                fn_name: "transfer",
+                args: (&sender, &multihop.address, 1_238i128).into_val(&env),
+                sub_invokes: &[],
+            }],
+        },
+    }]);
+
+    let offer_amount =
+        multihop.swap_exact_out(&sender, &operations, &1_000i128, &1_238i128, &None, &None);
+
+    assert_eq!(offer_amount, 1_238i128);
+    assert_eq!(token1.balance(&sender), 10_000i128 - 1_238i128);
+    assert_eq!(token3.balance(&sender), 1_000i128);
+    assert_eq!(token1.balance(&multihop.address), 0i128);
+    assert_eq!(token2.balance(&multihop.address), 0i128);
+    assert_eq!(token3.balance(&multihop.address), 0i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #14)")]
+fn swap_exact_out_above_max_offer_amount_should_fail() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let token1 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+    let token2 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+
+    let factory_client = deploy_and_initialize_factory(&env, admin.clone());
+    deploy_and_initialize_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token1.address.clone(),
+        1_000_000,
+        token2.address.clone(),
+        1_000_000,
+        None,
+    );
+
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+    let sender = Address::generate(&env);
+    token1.mint(&sender, &20_000i128);
+
+    let swap = Swap {
+        offer_asset: token1.address.clone(),
+        ask_asset: token2.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
+    };
+
+    // 10_000 of token2 cost more than 10_000 of token1 because of the spread
+    multihop.swap_exact_out(
+        &sender,
+        &vec![&env, swap],
+        &10_000i128,
+        &10_000i128,
+        &None,
+        &None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #15)")]
+fn swap_exact_out_through_the_same_pool_twice_should_fail() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let token1 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+    let token2 = deploy_and_mint_tokens(&env, &admin, 10_000_000i128);
+
+    let factory_client = deploy_and_initialize_factory(&env, admin.clone());
+    deploy_and_initialize_lp(
+        &env,
+        &factory_client,
+        admin.clone(),
+        token1.address.clone(),
+        1_000_000,
+        token2.address.clone(),
+        1_000_000,
+        None,
+    );
+
+    let multihop = deploy_multihop_contract(&env, admin.clone(), &factory_client.address);
+    let sender = Address::generate(&env);
+    token1.mint(&sender, &10_000i128);
+
+    // token1 -> token2 -> token1 through the only pool of the pair, given from the last swap
+    let swap1 = Swap {
+        offer_asset: token2.address.clone(),
+        ask_asset: token1.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
+    };
+    let swap2 = Swap {
+        offer_asset: token1.address.clone(),
+        ask_asset: token2.address.clone(),
+        ask_asset_min_amount: None::<i128>,
+        pool: PoolSelector::Default,
+    };
+
+    multihop.swap_exact_out(
+        &sender,
+        &vec![&env, swap1, swap2],
+        &1_000i128,
+        &10_000i128,
+        &None,
+        &None,
+    );
 }
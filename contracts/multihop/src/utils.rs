@@ -11,6 +11,31 @@ pub fn verify_swap(env: &Env, operations: &Vec<Swap>) {
     }
 }
 
+// Verifies every route of a split swap like a single swap, and that all of them end in the same
+// ask asset
+pub fn verify_split_routes(env: &Env, routes: &Vec<(Vec<Swap>, i128)>) {
+    if routes.is_empty() || routes.iter().any(|(operations, _)| operations.is_empty()) {
+        log!(
+            &env,
+            "Multihop: Swap split: routes or operations of a route are empty"
+        );
+        panic_with_error!(&env, ContractError::OperationsEmpty);
+    }
+
+    let ask_asset = routes.get(0).unwrap().0.last().unwrap().ask_asset;
+    for (operations, _) in routes.iter() {
+        verify_swap(env, &operations);
+
+        if operations.last().unwrap().ask_asset != ask_asset {
+            log!(
+                &env,
+                "Multihop: Swap split: Every route must end in the same ask asset"
+            );
+            panic_with_error!(&env, ContractError::IncorrectAssetSwap);
+        }
+    }
+}
+
 pub fn verify_reverse_swap(env: &Env, operations: &Vec<Swap>) {
     for (current, next) in operations.iter().zip(operations.iter().skip(1)) {
         if current.offer_asset != next.ask_asset {
@@ -20,12 +45,27 @@ pub fn verify_reverse_swap(env: &Env, operations: &Vec<Swap>) {
     }
 }
 
+pub fn verify_deadline(env: &Env, deadline: Option<u64>) {
+    if let Some(deadline) = deadline {
+        if env.ledger().timestamp() > deadline {
+            log!(&env, "Multihop: Swap: Transaction deadline has passed");
+            panic_with_error!(&env, ContractError::DeadlineExceeded);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{storage::Swap, utils::verify_swap};
+    use crate::{
+        storage::{PoolSelector, Swap},
+        utils::verify_swap,
+    };
 
-    use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        vec, Address, Env,
+    };
 
     #[test]
     fn verify_operations_in_swap_should_work() {
@@ -40,16 +80,19 @@ mod tests {
             offer_asset: token1.clone(),
             ask_asset: token2.clone(),
             ask_asset_min_amount: None::<i128>,
+            pool: PoolSelector::Default,
         };
         let swap2 = Swap {
             offer_asset: token2.clone(),
             ask_asset: token3.clone(),
             ask_asset_min_amount: None::<i128>,
+            pool: PoolSelector::Default,
         };
         let swap3 = Swap {
             offer_asset: token3.clone(),
             ask_asset: token4.clone(),
             ask_asset_min_amount: None::<i128>,
+            pool: PoolSelector::Default,
         };
 
         let operations = vec![&env, swap1, swap2, swap3];
@@ -70,16 +113,19 @@ mod tests {
             offer_asset: token3.clone(),
             ask_asset: token4.clone(),
             ask_asset_min_amount: None::<i128>,
+            pool: PoolSelector::Default,
         };
         let swap2 = Swap {
             offer_asset: token2.clone(),
             ask_asset: token3.clone(),
             ask_asset_min_amount: None::<i128>,
+            pool: PoolSelector::Default,
         };
         let swap3 = Swap {
             offer_asset: token1.clone(),
             ask_asset: token2.clone(),
             ask_asset_min_amount: None::<i128>,
+            pool: PoolSelector::Default,
         };
 
         let operations = vec![&env, swap1, swap2, swap3];
@@ -101,11 +147,13 @@ mod tests {
             offer_asset: token1.clone(),
             ask_asset: token2.clone(),
             ask_asset_min_amount: None::<i128>,
+            pool: PoolSelector::Default,
         };
         let swap2 = Swap {
             offer_asset: token3.clone(),
             ask_asset: token4.clone(),
             ask_asset_min_amount: None::<i128>,
+            pool: PoolSelector::Default,
         };
 
         let operations = vec![&env, swap1, swap2];
@@ -127,15 +175,35 @@ mod tests {
             offer_asset: token1.clone(),
             ask_asset: token2.clone(),
             ask_asset_min_amount: None::<i128>,
+            pool: PoolSelector::Default,
         };
         let swap2 = Swap {
             offer_asset: token3.clone(),
             ask_asset: token4.clone(),
             ask_asset_min_amount: None::<i128>,
+            pool: PoolSelector::Default,
         };
 
         let operations = vec![&env, swap1, swap2];
 
         verify_reverse_swap(&env, &operations);
     }
+
+    #[test]
+    fn verify_deadline_should_work() {
+        let env = Env::default();
+        env.ledger().with_mut(|li| li.timestamp = 100);
+
+        verify_deadline(&env, None);
+        verify_deadline(&env, Some(100));
+    }
+
+    #[test]
+    #[should_panic(expected = "Multihop: Swap: Transaction deadline has passed")]
+    fn verify_deadline_should_fail_when_expired() {
+        let env = Env::default();
+        env.ledger().with_mut(|li| li.timestamp = 100);
+
+        verify_deadline(&env, Some(99));
+    }
 }
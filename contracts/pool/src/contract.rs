@@ -7,21 +7,26 @@ use num_integer::Roots;
 
 use crate::{
     error::ContractError,
+    oracle::{self, update_price_accumulators},
     stake_contract,
     storage::{
         get_config, save_config, utils,
         utils::{is_initialized, set_initialized},
         validate_fee_bps, Asset, ComputeSwap, Config, LiquidityPoolInfo, PairType, PoolResponse,
-        SimulateReverseSwapResponse, SimulateSwapResponse,
+        Referral, SimulateProvideLiquidityResponse, SimulateReverseSwapResponse,
+        SimulateSwapResponse,
     },
     token_contract,
 };
 use decimal::Decimal;
 use phoenix::{
-    utils::{is_approx_ratio, LiquidityPoolInitInfo},
+    utils::{AdminChange, LiquidityPoolInitInfo},
     validate_bps, validate_int_parameters,
 };
 
+// Minimum amount of initial LP shares that is locked in the pool on the first deposit
+const MINIMUM_LIQUIDITY_AMOUNT: i128 = 1000;
+
 // Metadata that is added on to the WASM custom section
 contractmeta!(
     key = "Description",
@@ -49,6 +54,7 @@ pub trait LiquidityPoolTrait {
     // Deposits token_a and token_b. Also mints pool shares for the "to" Identifier. The amount minted
     // is determined based on the difference between the reserves stored by this contract, and
     // the actual balance of token_a and token_b for this contract.
+    #[allow(clippy::too_many_arguments)]
     fn provide_liquidity(
         env: Env,
         depositor: Address,
@@ -57,22 +63,41 @@ pub trait LiquidityPoolTrait {
         desired_b: Option<i128>,
         min_b: Option<i128>,
         custom_slippage_bps: Option<i64>,
+        deadline: Option<u64>,
     );
 
     // `offer_asset` is the asset that the user would like to swap for the other token in the pool.
     // `offer_amount` is the amount being sold, with `max_spread_bps` being a safety to make sure you receive at least that amount.
     // swap will transfer the selling token "to" to this contract, and then the contract will transfer the buying token to `sender`.
+    // `referral` is an optional address and fee (in bps, capped by `max_referral_bps`) that is paid
+    // out of the returned amount in the token being bought.
+    // `deadline` is an optional ledger timestamp after which the swap is rejected.
     // Returns the amount of the token being bought.
+    #[allow(clippy::too_many_arguments)]
     fn swap(
         env: Env,
         sender: Address,
-        // FIXM: Disable Referral struct
-        // referral: Option<Referral>,
+        referral: Option<Referral>,
         offer_asset: Address,
         offer_amount: i128,
         // Minimum amount of the ask token user expects to receive
         ask_asset_min_amount: Option<i128>,
         max_spread_bps: Option<i64>,
+        deadline: Option<u64>,
+    ) -> i128;
+
+    // `ask_asset` is the asset that the user would like to receive from the pool.
+    // `ask_amount` is the exact amount that will be transferred to `sender`, with `max_offer_amount`
+    // being a safety to make sure you don't pay more than that amount of the other token.
+    // Returns the amount of the token being sold.
+    fn swap_exact_out(
+        env: Env,
+        sender: Address,
+        ask_asset: Address,
+        ask_amount: i128,
+        max_offer_amount: i128,
+        max_spread_bps: Option<i64>,
+        deadline: Option<u64>,
     ) -> i128;
 
     // transfers share_amount of pool share tokens to this contract, burns all pools share tokens in this contracts, and sends the
@@ -84,23 +109,77 @@ pub trait LiquidityPoolTrait {
         share_amount: i128,
         min_a: i128,
         min_b: i128,
+        deadline: Option<u64>,
+    ) -> (i128, i128);
+
+    // Provides liquidity like `provide_liquidity` and bonds the minted pool shares in the stake
+    // contract of the pool on behalf of `sender`.
+    // Returns the amount of pool shares staked
+    #[allow(clippy::too_many_arguments)]
+    fn provide_and_stake(
+        env: Env,
+        sender: Address,
+        desired_a: Option<i128>,
+        min_a: Option<i128>,
+        desired_b: Option<i128>,
+        min_b: Option<i128>,
+        custom_slippage_bps: Option<i64>,
+        deadline: Option<u64>,
+    ) -> i128;
+
+    // Unbonds the stake of `stake_amount` pool shares bonded at `stake_timestamp` from the stake
+    // contract of the pool, which also pays out the rewards of `sender`, and withdraws the liquidity
+    // of those shares like `withdraw_liquidity`.
+    // Returns amount of both tokens withdrawn
+    fn unstake_and_withdraw(
+        env: Env,
+        sender: Address,
+        stake_amount: i128,
+        stake_timestamp: u64,
+        min_a: i128,
+        min_b: i128,
+        deadline: Option<u64>,
     ) -> (i128, i128);
 
     // Allows admin address set during initialization to change some parameters of the
     // configuration
     fn update_config(
         env: Env,
-        new_admin: Option<Address>,
         total_fee_bps: Option<i64>,
         fee_recipient: Option<Address>,
         max_allowed_slippage_bps: Option<i64>,
         max_allowed_spread_bps: Option<i64>,
         max_referral_bps: Option<i64>,
+        protocol_fee_share_bps: Option<i64>,
     );
 
     // Migration entrypoint
     fn upgrade(e: Env, new_wasm_hash: BytesN<32>);
 
+    // Sets the stored reserves to the actual token balances of the pool, so tokens sent directly
+    // to the pool are added to the liquidity
+    fn sync(env: Env);
+
+    // Allows admin to transfer the tokens held by the pool on top of the stored reserves to `to`
+    fn skim(env: Env, to: Address);
+
+    // Allows the admin or the factory to stop swaps and deposits. Withdrawals are still allowed
+    // while the pool is paused
+    fn pause(env: Env, sender: Address);
+
+    // Allows the admin or the factory to resume swaps and deposits
+    fn unpause(env: Env, sender: Address);
+
+    // Allows the admin to propose `new_admin` as the next admin. The proposal has to be accepted
+    // by `new_admin` before the ledger timestamp passes `expires_at`
+    fn propose_admin(env: Env, sender: Address, new_admin: Address, expires_at: u64);
+
+    // Allows the proposed admin to accept the admin role
+    fn accept_admin(env: Env, sender: Address);
+
+    // Allows the admin to cancel the pending admin proposal
+    fn cancel_admin_proposal(env: Env, sender: Address);
+
     // QUERIES
 
     // Returns the configuration structure containing the addresses
@@ -127,7 +206,29 @@ pub trait LiquidityPoolTrait {
         ask_amount: i128,
     ) -> SimulateReverseSwapResponse;
 
+    // Returns the amount of the other token `swap_exact_out` takes to return exactly `ask_amount`
+    // of `ask_asset`
+    fn simulate_swap_exact_out(env: Env, ask_asset: Address, ask_amount: i128) -> i128;
+
+    // Simulate providing `desired_a` and `desired_b` of liquidity with the same slippage checks as
+    // `provide_liquidity`. A zero amount simulates a single sided deposit of the other token.
+    // Returns the amounts that would be deposited, the pool shares minted to the depositor and the
+    // slippage of the deposited amounts from the pool ratio
+    fn simulate_provide_liquidity(
+        env: Env,
+        desired_a: i128,
+        desired_b: i128,
+        custom_slippage_bps: Option<i64>,
+    ) -> SimulateProvideLiquidityResponse;
+
     fn query_share(env: Env, amount: i128) -> (Asset, Asset);
+
+    // Returns the time weighted average price of `asset` in terms of the other pool asset, with 18
+    // decimal places, over at least the last `window_seconds`
+    fn query_twap(env: Env, asset: Address, window_seconds: u64) -> i128;
+
+    // Returns the difference between the actual token balances of the pool and the stored reserves
+    fn query_reserve_drift(env: Env) -> (Asset, Asset);
 }
 
 #[contractimpl]
@@ -227,10 +328,14 @@ impl LiquidityPoolTrait for LiquidityPool {
             max_allowed_slippage_bps,
             max_allowed_spread_bps,
             max_referral_bps,
+            // the whole commission goes to the fee recipient until the admin changes it
+            protocol_fee_share_bps: 10_000,
+            paused: false,
         };
 
         save_config(&env, config);
         utils::save_admin(&env, admin);
+        utils::save_factory(&env, factory_addr);
         utils::save_total_shares(&env, 0);
         utils::save_pool_balance_a(&env, 0);
         utils::save_pool_balance_b(&env, 0);
@@ -241,6 +346,7 @@ impl LiquidityPoolTrait for LiquidityPool {
             .publish(("initialize", "XYK LP token_b"), token_b);
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn provide_liquidity(
         env: Env,
         sender: Address,
@@ -249,167 +355,47 @@ impl LiquidityPoolTrait for LiquidityPool {
         desired_b: Option<i128>,
         min_b: Option<i128>,
         custom_slippage_bps: Option<i64>,
+        deadline: Option<u64>,
     ) {
         validate_int_parameters!(desired_a, min_a, desired_b, min_b);
+        assert_deadline(&env, deadline);
+        assert_not_paused(&env);
 
         // sender needs to authorize the deposit
         sender.require_auth();
 
-        let config = get_config(&env);
-        let pool_balance_a = utils::get_pool_balance_a(&env);
-        let pool_balance_b = utils::get_pool_balance_b(&env);
-
-        // Check if custom_slippage_bps is more than max_allowed_slippage
-        if let Some(custom_slippage) = custom_slippage_bps {
-            if custom_slippage > config.max_allowed_slippage_bps {
-                log!(
-                    &env,
-                    "Pool: ProvideLiquidity: Custom slippage tolerance is more than max allowed slippage tolerance"
-                );
-                panic_with_error!(env, ContractError::ProvideLiquiditySlippageToleranceTooHigh);
-            }
-        }
-        // Check if both tokens are provided, one token is provided, or none are provided
-        let amounts = match (desired_a, desired_b) {
-            // Both tokens are provided
-            (Some(a), Some(b)) if a > 0 && b > 0 => {
-                // Calculate deposit amounts
-                utils::get_deposit_amounts(
-                    &env,
-                    a,
-                    min_a,
-                    b,
-                    min_b,
-                    pool_balance_a,
-                    pool_balance_b,
-                    Decimal::bps(custom_slippage_bps.unwrap_or(100)),
-                )
-            }
-            // TODO: https://github.com/Phoenix-Protocol-Group/phoenix-contracts/issues/204
-            // Providing liquidity with a single token is temporarily disabled
-            // // Only token A is provided
-            // (Some(a), None) if a > 0 => {
-            //     let (a_for_swap, b_from_swap) = split_deposit_based_on_pool_ratio(
-            //         &env,
-            //         &config,
-            //         pool_balance_a,
-            //         pool_balance_b,
-            //         a,
-            //         &config.token_a,
-            //     );
-            //     do_swap(
-            //         env.clone(),
-            //         sender.clone(),
-            //         // FIXM: Disable Referral struct
-            //         // None,
-            //         config.clone().token_a,
-            //         a_for_swap,
-            //         // expected amount of ask token from swap
-            //         Some(b_from_swap),
-            //         None,
-            //     );
-            //     // return: rest of Token A amount, simulated result of swap of portion A
-            //     (a - a_for_swap, b_from_swap)
-            // }
-            // // Only token B is provided
-            // (None, Some(b)) if b > 0 => {
-            //     let (b_for_swap, a_from_swap) = split_deposit_based_on_pool_ratio(
-            //         &env,
-            //         &config,
-            //         pool_balance_a,
-            //         pool_balance_b,
-            //         b,
-            //         &config.token_b,
-            //     );
-            //     do_swap(
-            //         env.clone(),
-            //         sender.clone(),
-            //         // FIXM: Disable Referral struct
-            //         // None,
-            //         config.clone().token_b,
-            //         b_for_swap,
-            //         // expected amount of ask token from swap
-            //         Some(a_from_swap),
-            //         None,
-            //     );
-            //     // return: simulated result of swap of portion B, rest of Token B amount
-            //     (a_from_swap, b - b_for_swap)
-            // }
-            // None or invalid amounts are provided
-            _ => {
-                log!(
-                    &env,
-                        "Pool: ProvideLiquidity: Both tokens must be provided and must be bigger then 0!"
-                );
-                panic_with_error!(
-                    env,
-                    ContractError::ProvideLiquidityAtLeastOneTokenMustBeBiggerThenZero
-                );
-            }
-        };
-        let token_a_client = token_contract::Client::new(&env, &config.token_a);
-        let token_b_client = token_contract::Client::new(&env, &config.token_b);
-
-        // Move tokens from client's wallet to the contract
-        token_a_client.transfer(&sender, &env.current_contract_address(), &(amounts.0));
-        token_b_client.transfer(&sender, &env.current_contract_address(), &(amounts.1));
-
-        let pool_balance_a = utils::get_pool_balance_a(&env);
-        let pool_balance_b = utils::get_pool_balance_b(&env);
-
-        // Now calculate how many new pool shares to mint
-        let balance_a = utils::get_balance(&env, &config.token_a);
-        let balance_b = utils::get_balance(&env, &config.token_b);
-        let total_shares = utils::get_total_shares(&env);
-
-        let new_total_shares = if pool_balance_a > 0 && pool_balance_b > 0 {
-            let shares_a = (balance_a * total_shares) / pool_balance_a;
-            let shares_b = (balance_b * total_shares) / pool_balance_b;
-            shares_a.min(shares_b)
-        } else {
-            // In case of empty pool, just produce X*Y shares
-            (balance_a * balance_b).sqrt()
-        };
-
-        utils::mint_shares(
-            &env,
-            &config.share_token,
-            &sender,
-            new_total_shares - total_shares,
+        do_provide_liquidity(
+            env,
+            sender,
+            desired_a,
+            min_a,
+            desired_b,
+            min_b,
+            custom_slippage_bps,
         );
-        utils::save_pool_balance_a(&env, balance_a);
-        utils::save_pool_balance_b(&env, balance_b);
-
-        env.events()
-            .publish(("provide_liquidity", "sender"), sender);
-        env.events()
-            .publish(("provide_liquidity", "token_a"), &config.token_a);
-        env.events()
-            .publish(("provide_liquidity", "token_a-amount"), amounts.0);
-        env.events()
-            .publish(("provide_liquidity", "token_b"), &config.token_b);
-        env.events()
-            .publish(("provide_liquidity", "token_b-amount"), amounts.1);
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn swap(
         env: Env,
         sender: Address,
-        // FIXM: Disable Referral struct
-        // referral: Option<Referral>,
+        referral: Option<Referral>,
         offer_asset: Address,
         offer_amount: i128,
         ask_asset_min_amount: Option<i128>,
         max_spread_bps: Option<i64>,
+        deadline: Option<u64>,
     ) -> i128 {
         validate_int_parameters!(offer_amount);
+        assert_deadline(&env, deadline);
+        assert_not_paused(&env);
 
         sender.require_auth();
 
         do_swap(
             env,
             sender,
-            // referral,
+            referral,
             offer_asset,
             offer_amount,
             ask_asset_min_amount,
@@ -417,98 +403,132 @@ impl LiquidityPoolTrait for LiquidityPool {
         )
     }
 
+    fn swap_exact_out(
+        env: Env,
+        sender: Address,
+        ask_asset: Address,
+        ask_amount: i128,
+        max_offer_amount: i128,
+        max_spread_bps: Option<i64>,
+        deadline: Option<u64>,
+    ) -> i128 {
+        validate_int_parameters!(ask_amount, max_offer_amount);
+        assert_deadline(&env, deadline);
+        assert_not_paused(&env);
+
+        sender.require_auth();
+
+        do_swap_exact_out(
+            env,
+            sender,
+            ask_asset,
+            ask_amount,
+            max_offer_amount,
+            max_spread_bps,
+        )
+    }
+
     fn withdraw_liquidity(
         env: Env,
         sender: Address,
         share_amount: i128,
         min_a: i128,
         min_b: i128,
+        deadline: Option<u64>,
     ) -> (i128, i128) {
         validate_int_parameters!(share_amount, min_a, min_b);
+        assert_deadline(&env, deadline);
 
         sender.require_auth();
 
-        let config = get_config(&env);
+        do_withdraw_liquidity(env, sender, share_amount, min_a, min_b)
+    }
 
-        let share_token_client = token_contract::Client::new(&env, &config.share_token);
-        share_token_client.transfer(&sender, &env.current_contract_address(), &share_amount);
+    #[allow(clippy::too_many_arguments)]
+    fn provide_and_stake(
+        env: Env,
+        sender: Address,
+        desired_a: Option<i128>,
+        min_a: Option<i128>,
+        desired_b: Option<i128>,
+        min_b: Option<i128>,
+        custom_slippage_bps: Option<i64>,
+        deadline: Option<u64>,
+    ) -> i128 {
+        validate_int_parameters!(desired_a, min_a, desired_b, min_b);
+        assert_deadline(&env, deadline);
+        assert_not_paused(&env);
 
-        let pool_balance_a = utils::get_pool_balance_a(&env);
-        let pool_balance_b = utils::get_pool_balance_b(&env);
+        // sender needs to authorize the deposit and the bond
+        sender.require_auth();
 
-        let total_shares = utils::get_total_shares(&env);
+        let shares = do_provide_liquidity(
+            env.clone(),
+            sender.clone(),
+            desired_a,
+            min_a,
+            desired_b,
+            min_b,
+            custom_slippage_bps,
+        );
 
-        if total_shares == 0i128 {
-            log!(&env, "Pool: WithdrawLiquidity: Critical error - Total shares are equal to zero before withdrawal!");
-            panic_with_error!(env, ContractError::TotalSharesEqualZero);
-        }
+        // the stake contract moves the minted shares from the sender and bonds them to the sender
+        let stake_contract = get_config(&env).stake_contract;
+        stake_contract::Client::new(&env, &stake_contract).bond(&sender, &shares);
 
-        let share_ratio = Decimal::from_ratio(share_amount, total_shares);
+        env.events()
+            .publish(("provide_and_stake", "sender"), sender);
+        env.events()
+            .publish(("provide_and_stake", "stake_amount"), shares);
 
-        let return_amount_a = pool_balance_a * share_ratio;
-        let return_amount_b = pool_balance_b * share_ratio;
+        shares
+    }
 
-        if return_amount_a < min_a || return_amount_b < min_b {
-            log!(
-                &env,
-                "Pool: WithdrawLiquidity: Minimum amount of token_a or token_b is not satisfied! min_a: {}, min_b: {}, return_amount_a: {}, return_amount_b: {}",
-                min_a,
-                min_b,
-                return_amount_a,
-                return_amount_b
-            );
-            panic_with_error!(
-                env,
-                ContractError::WithdrawLiquidityMinimumAmountOfAOrBIsNotSatisfied
-            );
-        }
+    fn unstake_and_withdraw(
+        env: Env,
+        sender: Address,
+        stake_amount: i128,
+        stake_timestamp: u64,
+        min_a: i128,
+        min_b: i128,
+        deadline: Option<u64>,
+    ) -> (i128, i128) {
+        validate_int_parameters!(stake_amount, min_a, min_b);
+        assert_deadline(&env, deadline);
 
-        // burn shares
-        utils::burn_shares(&env, &config.share_token, share_amount);
-        // transfer tokens from sender to contract
-        token_contract::Client::new(&env, &config.token_a).transfer(
-            &env.current_contract_address(),
-            &sender,
-            &return_amount_a,
-        );
-        token_contract::Client::new(&env, &config.token_b).transfer(
-            &env.current_contract_address(),
+        // sender needs to authorize the unbond and the withdrawal
+        sender.require_auth();
+
+        // the stake contract pays out the rewards and returns the unbonded shares to the sender
+        let stake_contract = get_config(&env).stake_contract;
+        stake_contract::Client::new(&env, &stake_contract).unbond(
             &sender,
-            &return_amount_b,
+            &stake_amount,
+            &stake_timestamp,
         );
-        // update pool balances
-        utils::save_pool_balance_a(&env, pool_balance_a - return_amount_a);
-        utils::save_pool_balance_b(&env, pool_balance_b - return_amount_b);
 
         env.events()
-            .publish(("withdraw_liquidity", "sender"), sender);
-        env.events()
-            .publish(("withdraw_liquidity", "shares_amount"), share_amount);
+            .publish(("unstake_and_withdraw", "sender"), &sender);
         env.events()
-            .publish(("withdraw_liquidity", "return_amount_a"), return_amount_a);
-        env.events()
-            .publish(("withdraw_liquidity", "return_amount_b"), return_amount_b);
+            .publish(("unstake_and_withdraw", "stake_amount"), stake_amount);
 
-        (return_amount_a, return_amount_b)
+        do_withdraw_liquidity(env, sender, stake_amount, min_a, min_b)
     }
 
     fn update_config(
         env: Env,
-        new_admin: Option<Address>,
         total_fee_bps: Option<i64>,
         fee_recipient: Option<Address>,
         max_allowed_slippage_bps: Option<i64>,
         max_allowed_spread_bps: Option<i64>,
         max_referral_bps: Option<i64>,
+        protocol_fee_share_bps: Option<i64>,
     ) {
         let admin: Address = utils::get_admin(&env);
         admin.require_auth();
 
         let mut config = get_config(&env);
 
-        if let Some(new_admin) = new_admin {
-            utils::save_admin(&env, new_admin);
-        }
         if let Some(total_fee_bps) = total_fee_bps {
             if !(0..=10_000).contains(&total_fee_bps) {
                 log!(&env, "Pool: UpdateConfig: Invalid total_fee_bps");
@@ -528,6 +548,13 @@ impl LiquidityPoolTrait for LiquidityPool {
         if let Some(max_referral_bps) = max_referral_bps {
             config.max_referral_bps = max_referral_bps;
         }
+        if let Some(protocol_fee_share_bps) = protocol_fee_share_bps {
+            if !(0..=10_000).contains(&protocol_fee_share_bps) {
+                log!(&env, "Pool: UpdateConfig: Invalid protocol_fee_share_bps");
+                panic_with_error!(&env, ContractError::InvalidBps);
+            }
+            config.protocol_fee_share_bps = protocol_fee_share_bps;
+        }
 
         save_config(&env, config);
     }
@@ -539,6 +566,165 @@ impl LiquidityPoolTrait for LiquidityPool {
         env.deployer().update_current_contract_wasm(new_wasm_hash);
     }
 
+    fn sync(env: Env) {
+        // syncing an empty pool would leave reserves without any shares to mint against
+        if utils::get_total_shares(&env) == 0 {
+            log!(&env, "Pool: Sync: Pool has no liquidity to sync");
+            panic_with_error!(&env, ContractError::TotalSharesEqualZero);
+        }
+
+        let config = get_config(&env);
+        let pool_balance_a = utils::get_pool_balance_a(&env);
+        let pool_balance_b = utils::get_pool_balance_b(&env);
+        update_price_accumulators(&env, pool_balance_a, pool_balance_b);
+
+        let balance_a = utils::get_balance(&env, &config.token_a);
+        let balance_b = utils::get_balance(&env, &config.token_b);
+        utils::save_pool_balance_a(&env, balance_a);
+        utils::save_pool_balance_b(&env, balance_b);
+
+        env.events().publish(("sync", "reserve_a"), balance_a);
+        env.events().publish(("sync", "reserve_b"), balance_b);
+    }
+
+    fn skim(env: Env, to: Address) {
+        let admin: Address = utils::get_admin(&env);
+        admin.require_auth();
+
+        let config = get_config(&env);
+        let excess_a =
+            (utils::get_balance(&env, &config.token_a) - utils::get_pool_balance_a(&env)).max(0);
+        let excess_b =
+            (utils::get_balance(&env, &config.token_b) - utils::get_pool_balance_b(&env)).max(0);
+
+        if excess_a > 0 {
+            token_contract::Client::new(&env, &config.token_a).transfer(
+                &env.current_contract_address(),
+                &to,
+                &excess_a,
+            );
+        }
+        if excess_b > 0 {
+            token_contract::Client::new(&env, &config.token_b).transfer(
+                &env.current_contract_address(),
+                &to,
+                &excess_b,
+            );
+        }
+
+        env.events().publish(("skim", "to"), to);
+        env.events().publish(("skim", "token_a-amount"), excess_a);
+        env.events().publish(("skim", "token_b-amount"), excess_b);
+    }
+
+    fn pause(env: Env, sender: Address) {
+        sender.require_auth();
+        assert_admin_or_factory(&env, &sender);
+
+        let mut config = get_config(&env);
+        config.paused = true;
+        save_config(&env, config);
+
+        env.events().publish(("pause", "sender"), sender);
+    }
+
+    fn unpause(env: Env, sender: Address) {
+        sender.require_auth();
+        assert_admin_or_factory(&env, &sender);
+
+        let mut config = get_config(&env);
+        config.paused = false;
+        save_config(&env, config);
+
+        env.events().publish(("unpause", "sender"), sender);
+    }
+
+    fn propose_admin(env: Env, sender: Address, new_admin: Address, expires_at: u64) {
+        sender.require_auth();
+
+        if sender != utils::get_admin(&env) {
+            log!(
+                &env,
+                "Pool: Propose admin: Only the admin can propose a new admin"
+            );
+            panic_with_error!(&env, ContractError::Unauthorized);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            log!(
+                &env,
+                "Pool: Propose admin: Proposal expiration must be in the future"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeExpired);
+        }
+
+        utils::save_admin_change(
+            &env,
+            &AdminChange {
+                new_admin: new_admin.clone(),
+                expires_at,
+            },
+        );
+
+        env.events()
+            .publish(("propose_admin", "new_admin"), new_admin);
+        env.events()
+            .publish(("propose_admin", "expires_at"), expires_at);
+    }
+
+    fn accept_admin(env: Env, sender: Address) {
+        sender.require_auth();
+
+        let admin_change = utils::get_admin_change(&env).unwrap_or_else(|| {
+            log!(&env, "Pool: Accept admin: No admin change in place");
+            panic_with_error!(&env, ContractError::AdminChangeNotFound);
+        });
+        if sender != admin_change.new_admin {
+            log!(
+                &env,
+                "Pool: Accept admin: Only the proposed admin can accept the admin role"
+            );
+            panic_with_error!(&env, ContractError::Unauthorized);
+        }
+        if env.ledger().timestamp() > admin_change.expires_at {
+            log!(
+                &env,
+                "Pool: Accept admin: Admin change proposal has expired"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeExpired);
+        }
+
+        utils::remove_admin_change(&env);
+        utils::save_admin(&env, sender.clone());
+
+        env.events().publish(("accept_admin", "new_admin"), sender);
+    }
+
+    fn cancel_admin_proposal(env: Env, sender: Address) {
+        sender.require_auth();
+
+        if sender != utils::get_admin(&env) {
+            log!(
+                &env,
+                "Pool: Cancel admin proposal: Only the admin can cancel the proposal"
+            );
+            panic_with_error!(&env, ContractError::Unauthorized);
+        }
+
+        let admin_change = utils::get_admin_change(&env).unwrap_or_else(|| {
+            log!(
+                &env,
+                "Pool: Cancel admin proposal: No admin change in place"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeNotFound);
+        });
+        utils::remove_admin_change(&env);
+
+        env.events().publish(
+            ("cancel_admin_proposal", "new_admin"),
+            admin_change.new_admin,
+        );
+    }
+
     // Queries
 
     fn query_config(env: Env) -> Config {
@@ -625,9 +811,14 @@ impl LiquidityPoolTrait for LiquidityPool {
             + compute_swap.commission_amount
             + compute_swap.spread_amount;
 
+        let (protocol_fee_amount, lp_fee_amount) =
+            config.split_commission(compute_swap.commission_amount);
+
         SimulateSwapResponse {
             ask_amount: compute_swap.return_amount,
             commission_amount: compute_swap.commission_amount,
+            protocol_fee_amount,
+            lp_fee_amount,
             spread_amount: compute_swap.spread_amount,
             total_return,
         }
@@ -665,49 +856,420 @@ impl LiquidityPoolTrait for LiquidityPool {
         }
     }
 
+    fn simulate_swap_exact_out(env: Env, ask_asset: Address, ask_amount: i128) -> i128 {
+        let config = get_config(&env);
+
+        let pool_balance_a = utils::get_pool_balance_a(&env);
+        let pool_balance_b = utils::get_pool_balance_b(&env);
+        let (pool_balance_sell, pool_balance_buy) = if ask_asset == config.token_b {
+            (pool_balance_a, pool_balance_b)
+        } else if ask_asset == config.token_a {
+            (pool_balance_b, pool_balance_a)
+        } else {
+            log!(&env, "Pool: Token asked to swap not found in Pool");
+            panic_with_error!(env, ContractError::AssetNotInPool);
+        };
+
+        compute_exact_out_offer_amount(
+            &env,
+            pool_balance_sell,
+            pool_balance_buy,
+            ask_amount,
+            config.protocol_fee_rate(),
+        )
+    }
+
+    fn simulate_provide_liquidity(
+        env: Env,
+        desired_a: i128,
+        desired_b: i128,
+        custom_slippage_bps: Option<i64>,
+    ) -> SimulateProvideLiquidityResponse {
+        let config = get_config(&env);
+
+        if let Some(custom_slippage) = custom_slippage_bps {
+            if custom_slippage > config.max_allowed_slippage_bps {
+                log!(
+                    &env,
+                    "Pool: Simulate provide liquidity: Custom slippage tolerance is more than max allowed slippage tolerance"
+                );
+                panic_with_error!(env, ContractError::ProvideLiquiditySlippageToleranceTooHigh);
+            }
+        }
+
+        let pool_balance_a = utils::get_pool_balance_a(&env);
+        let pool_balance_b = utils::get_pool_balance_b(&env);
+
+        // a single sided deposit swaps first, so its amounts are matched to the pool after the swap
+        let (amount_a, amount_b, pool_balance_a, pool_balance_b) =
+            match (desired_a > 0, desired_b > 0) {
+                (true, true) => {
+                    let (amount_a, amount_b) = utils::get_deposit_amounts(
+                        &env,
+                        desired_a,
+                        None,
+                        desired_b,
+                        None,
+                        pool_balance_a,
+                        pool_balance_b,
+                        Decimal::bps(custom_slippage_bps.unwrap_or(100)),
+                    );
+                    (amount_a, amount_b, pool_balance_a, pool_balance_b)
+                }
+                (true, false) => simulate_single_sided_deposit(
+                    &env,
+                    &config,
+                    desired_a,
+                    &config.token_a,
+                    pool_balance_a,
+                    pool_balance_b,
+                ),
+                (false, true) => {
+                    let (amount_b, amount_a, pool_balance_b, pool_balance_a) =
+                        simulate_single_sided_deposit(
+                            &env,
+                            &config,
+                            desired_b,
+                            &config.token_b,
+                            pool_balance_b,
+                            pool_balance_a,
+                        );
+                    (amount_a, amount_b, pool_balance_a, pool_balance_b)
+                }
+                (false, false) => {
+                    log!(
+                    &env,
+                    "Pool: Simulate provide liquidity: At least one token must be bigger then 0!"
+                );
+                    panic_with_error!(
+                        env,
+                        ContractError::ProvideLiquidityAtLeastOneTokenMustBeBiggerThenZero
+                    );
+                }
+            };
+
+        let share_amount = compute_deposit_shares(
+            &env,
+            pool_balance_a,
+            pool_balance_b,
+            pool_balance_a + amount_a,
+            pool_balance_b + amount_b,
+            utils::get_total_shares(&env),
+        );
+
+        let slippage_bps = if desired_a > 0 && desired_b > 0 {
+            deposit_slippage_bps(desired_a, desired_b, pool_balance_a, pool_balance_b)
+        } else {
+            assert_slippage_tolerance(
+                &env,
+                custom_slippage_bps,
+                &[amount_a, amount_b],
+                &[pool_balance_a, pool_balance_b],
+                config.max_allowed_slippage(),
+            );
+            deposit_slippage_bps(amount_a, amount_b, pool_balance_a, pool_balance_b)
+        };
+
+        SimulateProvideLiquidityResponse {
+            asset_a: Asset {
+                address: config.token_a,
+                amount: amount_a,
+            },
+            asset_b: Asset {
+                address: config.token_b,
+                amount: amount_b,
+            },
+            share_amount,
+            slippage_bps,
+        }
+    }
+
     fn query_share(env: Env, amount: i128) -> (Asset, Asset) {
         let pool_info = Self::query_pool_info(env);
         let total_share = pool_info.asset_lp_share.amount;
         let token_a_amount = pool_info.asset_a.amount;
         let token_b_amount = pool_info.asset_b.amount;
 
-        let mut share_ratio = Decimal::zero();
-        if total_share != 0 {
-            share_ratio = Decimal::from_ratio(amount, total_share);
-        }
+        let mut share_ratio = Decimal::zero();
+        if total_share != 0 {
+            share_ratio = Decimal::from_ratio(amount, total_share);
+        }
+
+        let amount_a = token_a_amount * share_ratio;
+        let amount_b = token_b_amount * share_ratio;
+        (
+            Asset {
+                address: pool_info.asset_a.address,
+                amount: amount_a,
+            },
+            Asset {
+                address: pool_info.asset_b.address,
+                amount: amount_b,
+            },
+        )
+    }
+
+    fn query_twap(env: Env, asset: Address, window_seconds: u64) -> i128 {
+        let config = get_config(&env);
+
+        let is_asset_a = if asset == config.token_a {
+            true
+        } else if asset == config.token_b {
+            false
+        } else {
+            log!(&env, "Pool: Query TWAP: Token not found in Pool");
+            panic_with_error!(env, ContractError::AssetNotInPool);
+        };
+
+        oracle::query_twap(
+            &env,
+            utils::get_pool_balance_a(&env),
+            utils::get_pool_balance_b(&env),
+            is_asset_a,
+            window_seconds,
+        )
+    }
+
+    fn query_reserve_drift(env: Env) -> (Asset, Asset) {
+        let config = get_config(&env);
+        let drift_a = utils::get_balance(&env, &config.token_a) - utils::get_pool_balance_a(&env);
+        let drift_b = utils::get_balance(&env, &config.token_b) - utils::get_pool_balance_b(&env);
+
+        (
+            Asset {
+                address: config.token_a,
+                amount: drift_a,
+            },
+            Asset {
+                address: config.token_b,
+                amount: drift_b,
+            },
+        )
+    }
+}
+
+/// Deposits the liquidity of `sender` and mints the pool shares to `sender`. Returns the amount of
+/// shares minted.
+fn do_provide_liquidity(
+    env: Env,
+    sender: Address,
+    desired_a: Option<i128>,
+    min_a: Option<i128>,
+    desired_b: Option<i128>,
+    min_b: Option<i128>,
+    custom_slippage_bps: Option<i64>,
+) -> i128 {
+    let config = get_config(&env);
+    let pool_balance_a = utils::get_pool_balance_a(&env);
+    let pool_balance_b = utils::get_pool_balance_b(&env);
+    update_price_accumulators(&env, pool_balance_a, pool_balance_b);
+
+    // Check if custom_slippage_bps is more than max_allowed_slippage
+    if let Some(custom_slippage) = custom_slippage_bps {
+        if custom_slippage > config.max_allowed_slippage_bps {
+            log!(
+                &env,
+                "Pool: ProvideLiquidity: Custom slippage tolerance is more than max allowed slippage tolerance"
+            );
+            panic_with_error!(env, ContractError::ProvideLiquiditySlippageToleranceTooHigh);
+        }
+    }
+    // Check if both tokens are provided, one token is provided, or none are provided
+    let amounts = match (desired_a, desired_b) {
+        // Both tokens are provided
+        (Some(a), Some(b)) if a > 0 && b > 0 => {
+            // Calculate deposit amounts
+            utils::get_deposit_amounts(
+                &env,
+                a,
+                min_a,
+                b,
+                min_b,
+                pool_balance_a,
+                pool_balance_b,
+                Decimal::bps(custom_slippage_bps.unwrap_or(100)),
+            )
+        }
+        // Only token A is provided
+        (Some(a), None) if a > 0 => provide_single_sided_liquidity(
+            &env,
+            &sender,
+            &config,
+            a,
+            min_a,
+            min_b,
+            custom_slippage_bps,
+            &config.token_a,
+        ),
+        // Only token B is provided
+        (None, Some(b)) if b > 0 => {
+            let (b_deposit, a_from_swap) = provide_single_sided_liquidity(
+                &env,
+                &sender,
+                &config,
+                b,
+                min_b,
+                min_a,
+                custom_slippage_bps,
+                &config.token_b,
+            );
+            (a_from_swap, b_deposit)
+        }
+        // None or invalid amounts are provided
+        _ => {
+            log!(
+                &env,
+                "Pool: ProvideLiquidity: At least one token must be provided and must be bigger then 0!"
+            );
+            panic_with_error!(
+                env,
+                ContractError::ProvideLiquidityAtLeastOneTokenMustBeBiggerThenZero
+            );
+        }
+    };
+    let token_a_client = token_contract::Client::new(&env, &config.token_a);
+    let token_b_client = token_contract::Client::new(&env, &config.token_b);
+
+    // Move tokens from client's wallet to the contract
+    token_a_client.transfer(&sender, &env.current_contract_address(), &(amounts.0));
+    token_b_client.transfer(&sender, &env.current_contract_address(), &(amounts.1));
+
+    let pool_balance_a = utils::get_pool_balance_a(&env);
+    let pool_balance_b = utils::get_pool_balance_b(&env);
+
+    // Now calculate how many new pool shares to mint
+    let balance_a = utils::get_balance(&env, &config.token_a);
+    let balance_b = utils::get_balance(&env, &config.token_b);
+    let shares = compute_deposit_shares(
+        &env,
+        pool_balance_a,
+        pool_balance_b,
+        balance_a,
+        balance_b,
+        utils::get_total_shares(&env),
+    );
+
+    if pool_balance_a == 0 || pool_balance_b == 0 {
+        // The minimum liquidity is minted to the pool itself and can never be withdrawn, so
+        // the first depositor can't inflate the price of a single share
+        utils::mint_shares(
+            &env,
+            &config.share_token,
+            &env.current_contract_address(),
+            MINIMUM_LIQUIDITY_AMOUNT,
+        );
+    }
+    utils::mint_shares(&env, &config.share_token, &sender, shares);
+    utils::save_pool_balance_a(&env, balance_a);
+    utils::save_pool_balance_b(&env, balance_b);
+
+    env.events()
+        .publish(("provide_liquidity", "sender"), sender);
+    env.events()
+        .publish(("provide_liquidity", "token_a"), &config.token_a);
+    env.events()
+        .publish(("provide_liquidity", "token_a-amount"), amounts.0);
+    env.events()
+        .publish(("provide_liquidity", "token_b"), &config.token_b);
+    env.events()
+        .publish(("provide_liquidity", "token_b-amount"), amounts.1);
+
+    shares
+}
+
+/// Burns `share_amount` of the pool shares of `sender` and sends the corresponding amounts of both
+/// tokens to `sender`. Returns the amounts of both tokens withdrawn.
+fn do_withdraw_liquidity(
+    env: Env,
+    sender: Address,
+    share_amount: i128,
+    min_a: i128,
+    min_b: i128,
+) -> (i128, i128) {
+    let config = get_config(&env);
+
+    let share_token_client = token_contract::Client::new(&env, &config.share_token);
+    share_token_client.transfer(&sender, &env.current_contract_address(), &share_amount);
+
+    let pool_balance_a = utils::get_pool_balance_a(&env);
+    let pool_balance_b = utils::get_pool_balance_b(&env);
+    update_price_accumulators(&env, pool_balance_a, pool_balance_b);
+
+    let total_shares = utils::get_total_shares(&env);
+
+    if total_shares == 0i128 {
+        log!(&env, "Pool: WithdrawLiquidity: Critical error - Total shares are equal to zero before withdrawal!");
+        panic_with_error!(env, ContractError::TotalSharesEqualZero);
+    }
+
+    let share_ratio = Decimal::from_ratio(share_amount, total_shares);
+
+    let return_amount_a = pool_balance_a * share_ratio;
+    let return_amount_b = pool_balance_b * share_ratio;
+
+    if return_amount_a < min_a || return_amount_b < min_b {
+        log!(
+            &env,
+            "Pool: WithdrawLiquidity: Minimum amount of token_a or token_b is not satisfied! min_a: {}, min_b: {}, return_amount_a: {}, return_amount_b: {}",
+            min_a,
+            min_b,
+            return_amount_a,
+            return_amount_b
+        );
+        panic_with_error!(
+            env,
+            ContractError::WithdrawLiquidityMinimumAmountOfAOrBIsNotSatisfied
+        );
+    }
+
+    // burn shares
+    utils::burn_shares(&env, &config.share_token, share_amount);
+    // transfer tokens from sender to contract
+    token_contract::Client::new(&env, &config.token_a).transfer(
+        &env.current_contract_address(),
+        &sender,
+        &return_amount_a,
+    );
+    token_contract::Client::new(&env, &config.token_b).transfer(
+        &env.current_contract_address(),
+        &sender,
+        &return_amount_b,
+    );
+    // update pool balances
+    utils::save_pool_balance_a(&env, pool_balance_a - return_amount_a);
+    utils::save_pool_balance_b(&env, pool_balance_b - return_amount_b);
 
-        let amount_a = token_a_amount * share_ratio;
-        let amount_b = token_b_amount * share_ratio;
-        (
-            Asset {
-                address: pool_info.asset_a.address,
-                amount: amount_a,
-            },
-            Asset {
-                address: pool_info.asset_b.address,
-                amount: amount_b,
-            },
-        )
-    }
+    env.events()
+        .publish(("withdraw_liquidity", "sender"), sender);
+    env.events()
+        .publish(("withdraw_liquidity", "shares_amount"), share_amount);
+    env.events()
+        .publish(("withdraw_liquidity", "return_amount_a"), return_amount_a);
+    env.events()
+        .publish(("withdraw_liquidity", "return_amount_b"), return_amount_b);
+
+    (return_amount_a, return_amount_b)
 }
 
 fn do_swap(
     env: Env,
     sender: Address,
-    // FIXM: Disable Referral struct
-    // referral: Option<Referral>,
+    referral: Option<Referral>,
     offer_asset: Address,
     offer_amount: i128,
     ask_asset_min_amount: Option<i128>,
     max_spread: Option<i64>,
 ) -> i128 {
     let config = get_config(&env);
-    // FIXM: Disable Referral struct
-    // if let Some(referral) = &referral {
-    //     if referral.fee > config.max_referral_bps {
-    //         panic!("Pool: Swap: Trying to swap with more than the allowed referral fee");
-    //     }
-    // }
+    if let Some(referral) = &referral {
+        if !(0..=config.max_referral_bps).contains(&referral.fee) {
+            log!(
+                &env,
+                "Pool: Swap: Trying to swap with more than the allowed referral fee"
+            );
+            panic_with_error!(&env, ContractError::ReferralFeeBiggerThanMaxAllowed);
+        }
+    }
 
     if let Some(max_spread) = max_spread {
         if !(0..=config.max_allowed_spread_bps).contains(&max_spread) {
@@ -720,6 +1282,7 @@ fn do_swap(
 
     let pool_balance_a = utils::get_pool_balance_a(&env);
     let pool_balance_b = utils::get_pool_balance_b(&env);
+    update_price_accumulators(&env, pool_balance_a, pool_balance_b);
 
     let (pool_balance_sell, pool_balance_buy) = if offer_asset == config.token_a {
         (pool_balance_a, pool_balance_b)
@@ -730,12 +1293,10 @@ fn do_swap(
         panic_with_error!(env, ContractError::AssetNotInPool);
     };
 
-    // FIXM: Disable Referral struct
-    // let referral_fee_bps = match referral {
-    //     Some(ref referral) => referral.clone().fee,
-    //     None => 0,
-    // };
-    let referral_fee_bps = 0;
+    let referral_fee_bps = match referral {
+        Some(ref referral) => referral.fee,
+        None => 0,
+    };
 
     // 1. We calculate the referral_fee below. If none referral fee will be 0
     let compute_swap: ComputeSwap = compute_swap(
@@ -788,25 +1349,31 @@ fn do_swap(
         &compute_swap.return_amount,
     );
 
-    // send commission to fee recipient
+    // send the protocol part of the commission to fee recipient, the rest stays in the pool
+    let (protocol_fee_amount, lp_fee_amount) =
+        config.split_commission(compute_swap.commission_amount);
     token_contract::Client::new(&env, &buy_token).transfer(
         &env.current_contract_address(),
         &config.fee_recipient,
-        &compute_swap.commission_amount,
+        &protocol_fee_amount,
     );
 
     // 2. If referral is present and return amount is larger than 0 we send referral fee commision
-    //    to fee recipient
-    // FIXM: Disable Referral struct
-    // if let Some(Referral { address, fee }) = referral {
-    //     if fee > 0 {
-    //         token_contract::Client::new(&env, &buy_token).transfer(
-    //             &env.current_contract_address(),
-    //             &address,
-    //             &compute_swap.referral_fee_amount,
-    //         );
-    //     }
-    // }
+    //    to the referral address
+    if let Some(Referral { address, .. }) = referral {
+        if compute_swap.referral_fee_amount > 0 {
+            token_contract::Client::new(&env, &buy_token).transfer(
+                &env.current_contract_address(),
+                &address,
+                &compute_swap.referral_fee_amount,
+            );
+
+            env.events().publish(("referral", "address"), address);
+            env.events().publish(("referral", "token"), &buy_token);
+            env.events()
+                .publish(("referral", "fee_amount"), compute_swap.referral_fee_amount);
+        }
+    }
 
     // user is offering to sell A, so they will receive B
     // A balance is bigger, B balance is smaller
@@ -814,14 +1381,14 @@ fn do_swap(
         (
             pool_balance_a + offer_amount,
             pool_balance_b
-                - compute_swap.commission_amount
+                - protocol_fee_amount
                 - compute_swap.referral_fee_amount
                 - compute_swap.return_amount,
         )
     } else {
         (
             pool_balance_a
-                - compute_swap.commission_amount
+                - protocol_fee_amount
                 - compute_swap.referral_fee_amount
                 - compute_swap.return_amount,
             pool_balance_b + offer_amount,
@@ -838,6 +1405,10 @@ fn do_swap(
         .publish(("swap", "return_amount"), compute_swap.return_amount);
     env.events()
         .publish(("swap", "spread_amount"), compute_swap.spread_amount);
+    env.events()
+        .publish(("swap", "protocol_fee_amount"), protocol_fee_amount);
+    env.events()
+        .publish(("swap", "lp_fee_amount"), lp_fee_amount);
     env.events().publish(
         ("swap", "referral_fee_amount"),
         compute_swap.referral_fee_amount,
@@ -845,18 +1416,330 @@ fn do_swap(
     compute_swap.return_amount
 }
 
+fn do_swap_exact_out(
+    env: Env,
+    sender: Address,
+    ask_asset: Address,
+    ask_amount: i128,
+    max_offer_amount: i128,
+    max_spread: Option<i64>,
+) -> i128 {
+    let config = get_config(&env);
+
+    if let Some(max_spread) = max_spread {
+        if !(0..=config.max_allowed_spread_bps).contains(&max_spread) {
+            log!(&env, "Pool: do_swap_exact_out: max spread is out of bounds");
+            panic_with_error!(&env, ContractError::InvalidBps);
+        }
+    }
+
+    let max_spread = Decimal::bps(max_spread.map_or_else(|| config.max_allowed_spread_bps, |x| x));
+
+    let pool_balance_a = utils::get_pool_balance_a(&env);
+    let pool_balance_b = utils::get_pool_balance_b(&env);
+    update_price_accumulators(&env, pool_balance_a, pool_balance_b);
+
+    let (pool_balance_sell, pool_balance_buy) = if ask_asset == config.token_b {
+        (pool_balance_a, pool_balance_b)
+    } else if ask_asset == config.token_a {
+        (pool_balance_b, pool_balance_a)
+    } else {
+        log!(&env, "Pool: Token asked to swap not found in Pool");
+        panic_with_error!(env, ContractError::AssetNotInPool);
+    };
+
+    let offer_amount = compute_exact_out_offer_amount(
+        &env,
+        pool_balance_sell,
+        pool_balance_buy,
+        ask_amount,
+        config.protocol_fee_rate(),
+    );
+
+    if offer_amount > max_offer_amount {
+        log!(
+            &env,
+            "Pool: do_swap_exact_out: Offer amount ({}) is bigger then the maximum offer amount ({})",
+            offer_amount,
+            max_offer_amount
+        );
+        panic_with_error!(&env, ContractError::SwapOfferAmountBiggerThanMax);
+    }
+
+    // The rounded up offer amount can return slightly more than `ask_amount`, the leftover stays in
+    // the pool, so the sender receives exactly what they asked for.
+    let compute_swap: ComputeSwap = compute_swap(
+        pool_balance_sell,
+        pool_balance_buy,
+        offer_amount,
+        config.protocol_fee_rate(),
+        0i64,
+    );
+
+    assert_max_spread(
+        &env,
+        max_spread,
+        compute_swap.return_amount + compute_swap.commission_amount,
+        compute_swap.spread_amount,
+    );
+
+    let (sell_token, buy_token) = if ask_asset == config.token_b {
+        (config.token_a.clone(), config.token_b.clone())
+    } else {
+        (config.token_b.clone(), config.token_a.clone())
+    };
+
+    // transfer tokens to swap
+    token_contract::Client::new(&env, &sell_token).transfer(
+        &sender,
+        &env.current_contract_address(),
+        &offer_amount,
+    );
+
+    // return exactly the asked amount to the user
+    token_contract::Client::new(&env, &buy_token).transfer(
+        &env.current_contract_address(),
+        &sender,
+        &ask_amount,
+    );
+
+    // send the protocol part of the commission to fee recipient, the rest stays in the pool
+    let (protocol_fee_amount, lp_fee_amount) =
+        config.split_commission(compute_swap.commission_amount);
+    token_contract::Client::new(&env, &buy_token).transfer(
+        &env.current_contract_address(),
+        &config.fee_recipient,
+        &protocol_fee_amount,
+    );
+
+    // user is asking for B, so they will sell A
+    // A balance is bigger, B balance is smaller
+    let (balance_a, balance_b) = if ask_asset == config.token_b {
+        (
+            pool_balance_a + offer_amount,
+            pool_balance_b - protocol_fee_amount - ask_amount,
+        )
+    } else {
+        (
+            pool_balance_a - protocol_fee_amount - ask_amount,
+            pool_balance_b + offer_amount,
+        )
+    };
+    utils::save_pool_balance_a(&env, balance_a);
+    utils::save_pool_balance_b(&env, balance_b);
+
+    env.events().publish(("swap", "sender"), sender);
+    env.events().publish(("swap", "sell_token"), sell_token);
+    env.events().publish(("swap", "offer_amount"), offer_amount);
+    env.events().publish(("swap", "buy_token"), buy_token);
+    env.events().publish(("swap", "return_amount"), ask_amount);
+    env.events()
+        .publish(("swap", "spread_amount"), compute_swap.spread_amount);
+    env.events()
+        .publish(("swap", "protocol_fee_amount"), protocol_fee_amount);
+    env.events()
+        .publish(("swap", "lp_fee_amount"), lp_fee_amount);
+
+    offer_amount
+}
+
+/// Returns the amount of pool shares minted to the depositor when a deposit changes the pool
+/// balances from `pool_balance_a` and `pool_balance_b` to `balance_a` and `balance_b`.
+///
+/// The first deposit mints `sqrt(balance_a * balance_b)` shares, of which
+/// `MINIMUM_LIQUIDITY_AMOUNT` is locked in the pool. Later deposits mint shares in proportion to
+/// the smaller relative increase of the two balances.
+fn compute_deposit_shares(
+    env: &Env,
+    pool_balance_a: i128,
+    pool_balance_b: i128,
+    balance_a: i128,
+    balance_b: i128,
+    total_shares: i128,
+) -> i128 {
+    if pool_balance_a > 0 && pool_balance_b > 0 {
+        let shares_a = (balance_a * total_shares) / pool_balance_a;
+        let shares_b = (balance_b * total_shares) / pool_balance_b;
+        shares_a.min(shares_b) - total_shares
+    } else {
+        // In case of empty pool, just produce X*Y shares
+        let shares = (balance_a * balance_b).sqrt();
+        if shares <= MINIMUM_LIQUIDITY_AMOUNT {
+            log!(env, "Pool: ProvideLiquidity: Liquidity amount is too low");
+            panic_with_error!(env, ContractError::LowLiquidity);
+        }
+        shares - MINIMUM_LIQUIDITY_AMOUNT
+    }
+}
+
+/// Returns how far the price of `amount_a` and `amount_b` is from the pool price in bps, rounded up.
+/// The slippage of a deposit into an empty pool is 0.
+fn deposit_slippage_bps(
+    amount_a: i128,
+    amount_b: i128,
+    pool_balance_a: i128,
+    pool_balance_b: i128,
+) -> i64 {
+    if pool_balance_a <= 0 || pool_balance_b <= 0 {
+        return 0;
+    }
+
+    let price_desired = amount_b * pool_balance_a;
+    let price_pool = amount_a * pool_balance_b;
+    if price_desired.min(price_pool) <= 0 {
+        // one side of the deposit is empty, which no slippage tolerance accepts
+        return 10_000;
+    }
+
+    let slippage =
+        Decimal::from_ratio(price_desired.max(price_pool), price_desired.min(price_pool))
+            - Decimal::one();
+    let one_bps = Decimal::bps(1).atomics();
+    ((slippage.atomics() + one_bps - 1) / one_bps) as i64
+}
+
+/// Simulates `provide_single_sided_liquidity` without moving any tokens. Returns the deposited
+/// amounts and the pool balances after the internal swap, as
+/// `(offer amount, ask amount, offer pool balance, ask pool balance)`.
+fn simulate_single_sided_deposit(
+    env: &Env,
+    config: &Config,
+    deposit: i128,
+    offer_asset: &Address,
+    pool_balance_offer: i128,
+    pool_balance_ask: i128,
+) -> (i128, i128, i128, i128) {
+    let (pool_balance_a, pool_balance_b) = if offer_asset == &config.token_a {
+        (pool_balance_offer, pool_balance_ask)
+    } else {
+        (pool_balance_ask, pool_balance_offer)
+    };
+    let (offer_amount, _) = split_deposit_based_on_pool_ratio(
+        env,
+        config,
+        pool_balance_a,
+        pool_balance_b,
+        deposit,
+        offer_asset,
+    );
+
+    let compute_swap = compute_swap(
+        pool_balance_offer,
+        pool_balance_ask,
+        offer_amount,
+        config.protocol_fee_rate(),
+        0i64,
+    );
+    let (protocol_fee_amount, _) = config.split_commission(compute_swap.commission_amount);
+
+    (
+        deposit - offer_amount,
+        compute_swap.return_amount,
+        pool_balance_offer + offer_amount,
+        pool_balance_ask - compute_swap.return_amount - protocol_fee_amount,
+    )
+}
+
+/// Swaps the part of a single sided deposit for the other pool token and returns the amounts that
+/// will be deposited as `(offer_asset amount, ask_asset amount)`.
+/// * `deposit` - The total amount of `offer_asset` the user wants to deposit into the liquidity pool.
+/// * `min_deposit` - Minimum amount of `offer_asset` that has to be left for the deposit after the swap.
+/// * `min_ask` - Minimum amount of the other token that the internal swap has to return. This is
+///   what protects the depositor from having the internal swap sandwiched, so it is mandatory.
+#[allow(clippy::too_many_arguments)]
+fn provide_single_sided_liquidity(
+    env: &Env,
+    sender: &Address,
+    config: &Config,
+    deposit: i128,
+    min_deposit: Option<i128>,
+    min_ask: Option<i128>,
+    custom_slippage_bps: Option<i64>,
+    offer_asset: &Address,
+) -> (i128, i128) {
+    let min_ask = min_ask.unwrap_or_else(|| {
+        log!(
+            env,
+            "Pool: ProvideLiquidity: Minimum amount of the other token must be set when providing a single token"
+        );
+        panic_with_error!(env, ContractError::ProvideLiquiditySingleSidedMinAmountNotSet);
+    });
+
+    let (offer_amount, _) = split_deposit_based_on_pool_ratio(
+        env,
+        config,
+        utils::get_pool_balance_a(env),
+        utils::get_pool_balance_b(env),
+        deposit,
+        offer_asset,
+    );
+
+    let ask_amount = do_swap(
+        env.clone(),
+        sender.clone(),
+        None,
+        offer_asset.clone(),
+        offer_amount,
+        Some(min_ask),
+        None,
+    );
+    let deposit_amount = deposit - offer_amount;
+
+    if let Some(min_deposit) = min_deposit {
+        if deposit_amount < min_deposit {
+            log!(
+                env,
+                "Pool: ProvideLiquidity: Amount left for deposit after the swap ({}) is below the minimum ({})",
+                deposit_amount,
+                min_deposit
+            );
+            panic_with_error!(
+                env,
+                ContractError::ProvideLiquiditySingleSidedBelowMinAmount
+            );
+        }
+    }
+
+    // the swap has moved the pool, so the deposit has to match the new ratio
+    let (pool_balance_offer, pool_balance_ask) = if offer_asset == &config.token_a {
+        (
+            utils::get_pool_balance_a(env),
+            utils::get_pool_balance_b(env),
+        )
+    } else {
+        (
+            utils::get_pool_balance_b(env),
+            utils::get_pool_balance_a(env),
+        )
+    };
+    assert_slippage_tolerance(
+        env,
+        custom_slippage_bps,
+        &[deposit_amount, ask_amount],
+        &[pool_balance_offer, pool_balance_ask],
+        config.max_allowed_slippage(),
+    );
+
+    (deposit_amount, ask_amount)
+}
+
 /// This function divides the deposit in such a way that when swapping it for the other token,
-/// the resulting amounts of tokens maintain the current pool's ratio.
+/// the resulting amounts of tokens maintain the pool's ratio after the swap.
+///
+/// Swapping `x` out of a deposit `a` into a pool `A/B` with fee `f` (taken from the returned
+/// amount), of which the part `g` stays in the pool for the liquidity providers, leaves the pool
+/// at `(A + x) / ((A + g * x) * B / (A + x))`. Requiring the remaining deposit
+/// `(a - x, (1 - f) * B * x / (A + x))` to have that same ratio gives the quadratic
+/// `(1 - f + g) * x^2 + ((2 - f) * A - g * a) * x - a * A = 0`, whose positive root is computed
+/// here in the cancellation free form `x = 2a / (b + sqrt(b^2 + 4 * (1 - f + g) * a / A))`, with
+/// `b = (2 - f) - g * a / A`.
 /// * `a_pool` - The current amount of Token A in the liquidity pool.
 /// * `b_pool` - The current amount of Token B in the liquidity pool.
 /// * `deposit` - The total amount of tokens that the user wants to deposit into the liquidity pool.
-/// * `sell_a` - A boolean that indicates whether the deposit is in Token A (if true) or in Token B (if false).
+/// * `offer_asset` - The token in which the deposit is made.
 /// # Returns
 /// * A tuple `(final_offer_amount, final_ask_amount)`, where `final_offer_amount` is the amount of deposit tokens
 ///   to be swapped, and `final_ask_amount` is the amount of the other tokens that will be received in return.
-///
-// TODO: https://github.com/Phoenix-Protocol-Group/phoenix-contracts/issues/204
-#[allow(dead_code)]
 fn split_deposit_based_on_pool_ratio(
     env: &Env,
     config: &Config,
@@ -883,57 +1766,26 @@ fn split_deposit_based_on_pool_ratio(
         );
     }
 
-    // Calculate the current ratio in the pool
-    let target_ratio = Decimal::from_ratio(b_pool, a_pool);
-    // Define boundaries for binary search algorithm
-    let mut low = 0;
-    let mut high = deposit;
-
-    // Tolerance is the smallest difference in deposit that we care about
-    let tolerance = 500;
-
-    let mut final_offer_amount = deposit; // amount of deposit tokens to be swapped
-    let mut final_ask_amount = 0; // amount of other tokens to be received
+    let (offer_pool, ask_pool) = if offer_asset == &config.token_a {
+        (a_pool, b_pool)
+    } else {
+        (b_pool, a_pool)
+    };
 
-    while high - low > tolerance {
-        let mid = (low + high) / 2; // Calculate middle point
+    let fee = config.protocol_fee_rate();
+    let lp_fee = fee * (Decimal::one() - Decimal::bps(config.protocol_fee_share_bps));
+    let deposit_ratio = Decimal::from_ratio(deposit, offer_pool);
+    let two = Decimal::from_ratio(2, 1);
+    let b = two - fee - lp_fee * deposit_ratio;
+    let discriminant =
+        b * b + Decimal::from_ratio(4, 1) * (Decimal::one() - fee + lp_fee) * deposit_ratio;
+    // sqrt(x * 10^18) = sqrt(x) * 10^9, so the root keeps 9 out of the 18 decimal places
+    let discriminant_sqrt = Decimal::raw(discriminant.atomics().sqrt() * 1_000_000_000);
 
-        // Simulate swap to get amount of other tokens to be received for `mid` amount of deposit tokens
-        let SimulateSwapResponse {
-            ask_amount,
-            spread_amount: _,
-            commission_amount: _,
-            total_return: _,
-        } = LiquidityPool::simulate_swap(env.clone(), offer_asset.clone(), mid);
-
-        // Update final amounts
-        final_offer_amount = mid;
-        final_ask_amount = ask_amount;
-
-        // Calculate the ratio that would result from swapping `mid` deposit tokens
-        let ratio = if offer_asset == &config.token_a {
-            Decimal::from_ratio(ask_amount, deposit - mid)
-        } else {
-            Decimal::from_ratio(deposit - mid, ask_amount)
-        };
+    let final_offer_amount = deposit * (two / (b + discriminant_sqrt));
+    let final_ask_amount =
+        compute_swap(offer_pool, ask_pool, final_offer_amount, fee, 0i64).return_amount;
 
-        // If the resulting ratio is approximately equal (1%) to the target ratio, break the loop
-        if is_approx_ratio(ratio, target_ratio, Decimal::percent(1)) {
-            break;
-        }
-        // Update boundaries for the next iteration of the binary search
-        if ratio > target_ratio {
-            if offer_asset == &config.token_a {
-                high = mid;
-            } else {
-                low = mid;
-            }
-        } else if offer_asset == &config.token_a {
-            low = mid;
-        } else {
-            high = mid;
-        };
-    }
     (final_offer_amount, final_ask_amount)
 }
 
@@ -946,7 +1798,6 @@ fn split_deposit_based_on_pool_ratio(
 /// # Returns
 /// * An error if the slippage exceeds the tolerance or if the tolerance itself exceeds the maximum allowed,
 ///   otherwise Ok.
-#[allow(dead_code)]
 fn assert_slippage_tolerance(
     env: &Env,
     slippage_tolerance: Option<i64>,
@@ -1006,6 +1857,34 @@ pub fn assert_max_spread(env: &Env, max_spread: Decimal, return_amount: i128, sp
     }
 }
 
+/// Rejects the transaction when the ledger timestamp is past the optional `deadline`.
+pub fn assert_deadline(env: &Env, deadline: Option<u64>) {
+    if let Some(deadline) = deadline {
+        if env.ledger().timestamp() > deadline {
+            log!(env, "Pool: Transaction deadline has passed");
+            panic_with_error!(env, ContractError::DeadlineExceeded);
+        }
+    }
+}
+
+/// Rejects swaps and deposits while the pool is paused.
+pub fn assert_not_paused(env: &Env) {
+    if get_config(env).paused {
+        log!(env, "Pool: Pool is paused");
+        panic_with_error!(env, ContractError::Paused);
+    }
+}
+
+fn assert_admin_or_factory(env: &Env, sender: &Address) {
+    if *sender != utils::get_admin(env) && *sender != utils::get_factory(env) {
+        log!(
+            env,
+            "Pool: Pause: Only the admin or the factory can pause the pool"
+        );
+        panic_with_error!(env, ContractError::Unauthorized);
+    }
+}
+
 /// Computes the result of a swap operation.
 ///
 /// Arguments:
@@ -1088,6 +1967,49 @@ pub fn compute_offer_amount(
     (offer_amount, spread_amount, commission_amount)
 }
 
+/// Returns an amount of offer assets that makes `compute_swap` return at least `ask_amount` of
+/// ask assets after the commission is deducted. Unlike `compute_offer_amount`, the result is
+/// rounded in favour of the pool.
+///
+/// * **offer_pool** total amount of offer assets in the pool.
+/// * **ask_pool** total amount of ask assets in the pool.
+/// * **ask_amount** amount of ask assets the user has to receive.
+/// * **commission_rate** total amount of fees charged for the swap.
+pub fn compute_exact_out_offer_amount(
+    env: &Env,
+    offer_pool: i128,
+    ask_pool: i128,
+    ask_amount: i128,
+    commission_rate: Decimal,
+) -> i128 {
+    let one_minus_commission = (Decimal::one() - commission_rate).atomics();
+    if one_minus_commission <= 0 {
+        log!(
+            env,
+            "Pool: compute_exact_out_offer_amount: Exact output swaps are not possible with 100% fees"
+        );
+        panic_with_error!(env, ContractError::SwapAskAmountBiggerThanReserve);
+    }
+
+    // ask_amount / (1 - commission_rate), rounded up
+    let ask_before_commission =
+        (ask_amount * Decimal::one().atomics() + one_minus_commission - 1) / one_minus_commission;
+    if ask_before_commission >= ask_pool {
+        log!(
+            env,
+            "Pool: compute_exact_out_offer_amount: Not enough liquidity to return the asked amount"
+        );
+        panic_with_error!(env, ContractError::SwapAskAmountBiggerThanReserve);
+    }
+
+    // Calculate the cross product of offer_pool and ask_pool
+    let cp: i128 = offer_pool * ask_pool;
+
+    // `compute_swap` rounds the new ask pool down, so the smallest offer pool for which
+    // `cp / new_offer_pool <= ask_pool - ask_before_commission` holds is this one
+    cp / (ask_pool - ask_before_commission + 1) + 1 - offer_pool
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1238,6 +2160,30 @@ mod tests {
         assert_eq!(result.2, result.0 * Decimal::percent(10));
     }
 
+    #[test]
+    fn test_compute_exact_out_offer_amount() {
+        let env = Env::default();
+        let commission_rate = Decimal::percent(10);
+
+        let offer_amount =
+            compute_exact_out_offer_amount(&env, 1_000_000, 1_000_000, 1_000, commission_rate);
+        assert_eq!(offer_amount, 1_113);
+
+        // 1_112 of the ask asset leave the pool, 111 of which are the commission
+        let result = compute_swap(1_000_000, 1_000_000, offer_amount, commission_rate, 0);
+        assert_eq!(result.return_amount, 1_001);
+        assert_eq!(result.commission_amount, 111);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Pool: compute_exact_out_offer_amount: Exact output swaps are not possible with 100% fees"
+    )]
+    fn test_compute_exact_out_offer_amount_full_commission() {
+        let env = Env::default();
+        compute_exact_out_offer_amount(&env, 1_000_000, 1_000_000, 1_000, Decimal::one());
+    }
+
     #[should_panic(expected = "Pool: Token offered to swap not found in Pool")]
     #[test]
     fn should_panic_when_splitting_non_existent_token() {
@@ -1253,6 +2199,8 @@ mod tests {
             max_allowed_slippage_bps: 100i64,
             max_allowed_spread_bps: 100i64,
             max_referral_bps: 1_000i64,
+            protocol_fee_share_bps: 10_000i64,
+            paused: false,
         };
         split_deposit_based_on_pool_ratio(&env, config, 100, 100, 100, &Address::generate(&env));
     }
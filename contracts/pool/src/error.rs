@@ -29,4 +29,17 @@ pub enum ContractError {
     SlippageInvalid = 20,
 
     SwapMinReceivedBiggerThanReturn = 21,
+    ProvideLiquiditySingleSidedMinAmountNotSet = 22,
+    ReferralFeeBiggerThanMaxAllowed = 23,
+    SwapOfferAmountBiggerThanMax = 24,
+    SwapAskAmountBiggerThanReserve = 25,
+    DeadlineExceeded = 26,
+    TwapWindowInvalid = 27,
+    TwapObservationNotFound = 28,
+    LowLiquidity = 29,
+    Unauthorized = 30,
+    Paused = 31,
+    AdminChangeNotFound = 32,
+    AdminChangeExpired = 33,
+    ProvideLiquiditySingleSidedBelowMinAmount = 34,
 }
@@ -1,6 +1,7 @@
 #![no_std]
 mod contract;
 mod error;
+mod oracle;
 mod storage;
 
 pub mod token_contract {
@@ -0,0 +1,182 @@
+use soroban_sdk::{log, panic_with_error, Env};
+
+use crate::{
+    error::ContractError,
+    storage::{DataKey, Observation},
+};
+use decimal::Decimal;
+
+/// Length of a period in seconds. At most one observation is recorded per period.
+pub const OBSERVATION_PERIOD: u64 = 300;
+/// Number of observations kept in the ring buffer, which covers 24 hours.
+pub const MAX_OBSERVATIONS: u64 = 288;
+
+const DAY_IN_LEDGERS: u32 = 17280;
+/// The observations are kept alive well past the 24 hours covered by the ring buffer, so the last
+/// one can still be read after a long time without swaps.
+const OBSERVATION_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+const OBSERVATION_LIFETIME_THRESHOLD: u32 = OBSERVATION_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+pub fn get_price_cumulative_last(env: &Env) -> Option<Observation> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PriceCumulativeLast)
+}
+
+fn save_price_cumulative_last(env: &Env, observation: &Observation) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::PriceCumulativeLast, observation);
+    env.storage().persistent().extend_ttl(
+        &DataKey::PriceCumulativeLast,
+        OBSERVATION_LIFETIME_THRESHOLD,
+        OBSERVATION_BUMP_AMOUNT,
+    );
+}
+
+fn get_observation(env: &Env, slot: u64) -> Option<Observation> {
+    env.storage()
+        .persistent()
+        .get(&(DataKey::Observations, slot))
+}
+
+fn save_observation(env: &Env, observation: &Observation) {
+    let key = (
+        DataKey::Observations,
+        (observation.timestamp / OBSERVATION_PERIOD) % MAX_OBSERVATIONS,
+    );
+    env.storage().persistent().set(&key, observation);
+    env.storage().persistent().extend_ttl(
+        &key,
+        OBSERVATION_LIFETIME_THRESHOLD,
+        OBSERVATION_BUMP_AMOUNT,
+    );
+}
+
+/// Adds the prices implied by the reserves to the accumulators, weighted by the time passed since
+/// the last update. Has to be called with the reserves from *before* they are changed, so the
+/// price of the current ledger can't be moved by the transaction that reads it.
+pub fn update_price_accumulators(env: &Env, reserve_a: i128, reserve_b: i128) {
+    let now = env.ledger().timestamp();
+
+    let mut last = match get_price_cumulative_last(env) {
+        Some(last) => last,
+        None => {
+            let first = Observation {
+                timestamp: now,
+                price_a_cumulative: 0,
+                price_b_cumulative: 0,
+            };
+            save_price_cumulative_last(env, &first);
+            save_observation(env, &first);
+            return;
+        }
+    };
+
+    if now <= last.timestamp {
+        return;
+    }
+
+    let new_period = now / OBSERVATION_PERIOD != last.timestamp / OBSERVATION_PERIOD;
+    last = accumulate(last, now, reserve_a, reserve_b);
+
+    save_price_cumulative_last(env, &last);
+    if new_period {
+        save_observation(env, &last);
+    }
+}
+
+/// Returns the time weighted average price of asset A (`is_asset_a`) or asset B over at least the
+/// last `window_seconds`, denominated in the other asset with 18 decimal places.
+pub fn query_twap(
+    env: &Env,
+    reserve_a: i128,
+    reserve_b: i128,
+    is_asset_a: bool,
+    window_seconds: u64,
+) -> i128 {
+    if window_seconds == 0 || window_seconds > OBSERVATION_PERIOD * MAX_OBSERVATIONS {
+        log!(
+            env,
+            "Pool: Query TWAP: Window must be bigger than 0 and not longer than the observation buffer"
+        );
+        panic_with_error!(env, ContractError::TwapWindowInvalid);
+    }
+
+    let now = env.ledger().timestamp();
+    let last = match get_price_cumulative_last(env) {
+        Some(last) => last,
+        None => {
+            log!(env, "Pool: Query TWAP: No price observations recorded yet");
+            panic_with_error!(env, ContractError::TwapObservationNotFound);
+        }
+    };
+    let current = accumulate(last.clone(), now, reserve_a, reserve_b);
+
+    let oldest = match now.checked_sub(window_seconds).and_then(|target| {
+        // without any update since the start of the window the price has not changed since the
+        // last one, which may already have been overwritten in the ring buffer
+        if last.timestamp <= target {
+            Some(last)
+        } else {
+            find_observation_before(env, target)
+        }
+    }) {
+        Some(observation) => observation,
+        None => {
+            log!(
+                env,
+                "Pool: Query TWAP: Not enough price observations for the requested window"
+            );
+            panic_with_error!(env, ContractError::TwapObservationNotFound);
+        }
+    };
+
+    let (current_cumulative, oldest_cumulative) = if is_asset_a {
+        (current.price_a_cumulative, oldest.price_a_cumulative)
+    } else {
+        (current.price_b_cumulative, oldest.price_b_cumulative)
+    };
+
+    // The accumulators are allowed to wrap around, their difference is still correct
+    current_cumulative.wrapping_sub(oldest_cumulative) / (now - oldest.timestamp) as i128
+}
+
+fn accumulate(last: Observation, now: u64, reserve_a: i128, reserve_b: i128) -> Observation {
+    let elapsed = (now - last.timestamp) as i128;
+    if elapsed == 0 || reserve_a == 0 || reserve_b == 0 {
+        return Observation {
+            timestamp: now,
+            ..last
+        };
+    }
+
+    let price_a = Decimal::from_ratio(reserve_b, reserve_a).atomics();
+    let price_b = Decimal::from_ratio(reserve_a, reserve_b).atomics();
+
+    Observation {
+        timestamp: now,
+        price_a_cumulative: last
+            .price_a_cumulative
+            .wrapping_add(price_a.wrapping_mul(elapsed)),
+        price_b_cumulative: last
+            .price_b_cumulative
+            .wrapping_add(price_b.wrapping_mul(elapsed)),
+    }
+}
+
+/// Walks the ring buffer back from the period of `target` and returns the most recent
+/// observation that is not newer than `target`.
+fn find_observation_before(env: &Env, target: u64) -> Option<Observation> {
+    let target_period = target / OBSERVATION_PERIOD;
+
+    (0..MAX_OBSERVATIONS.min(target_period + 1))
+        .map(|periods_back| target_period - periods_back)
+        .find_map(|period| {
+            get_observation(env, period % MAX_OBSERVATIONS).filter(|observation| {
+                // slots are reused, so the observation has to be from the same period
+                observation.timestamp / OBSERVATION_PERIOD == period
+                    && observation.timestamp <= target
+            })
+        })
+}
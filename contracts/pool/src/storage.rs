@@ -14,6 +14,10 @@ pub enum DataKey {
     ReserveB = 2,
     Admin = 3,
     Initialized = 4,
+    PriceCumulativeLast = 5,
+    Observations = 6,
+    Factory = 7,
+    AdminChange = 8,
 }
 
 impl TryFromVal<Env, DataKey> for Val {
@@ -49,6 +53,11 @@ pub struct Config {
     pub max_allowed_spread_bps: i64,
     /// The maximum allowed percentage (in bps) for referral fee
     pub max_referral_bps: i64,
+    /// The share (in bps) of the commission that is sent to the fee recipient.
+    /// The rest of the commission stays in the pool for the liquidity providers
+    pub protocol_fee_share_bps: i64,
+    /// While paused, swaps and deposits are rejected. Withdrawals are still allowed
+    pub paused: bool,
 }
 const CONFIG: Symbol = symbol_short!("CONFIG");
 
@@ -77,6 +86,13 @@ impl Config {
     pub fn max_allowed_slippage(&self) -> Decimal {
         Decimal::bps(self.max_allowed_slippage_bps)
     }
+
+    /// Splits the commission into the protocol part, sent to the fee recipient, and the
+    /// liquidity providers part, which stays in the pool.
+    pub fn split_commission(&self, commission_amount: i128) -> (i128, i128) {
+        let protocol_fee_amount = commission_amount * Decimal::bps(self.protocol_fee_share_bps);
+        (protocol_fee_amount, commission_amount - protocol_fee_amount)
+    }
 }
 
 pub fn get_config(env: &Env) -> Config {
@@ -120,6 +136,20 @@ pub struct Referral {
     pub fee: i64,
 }
 
+/// Cumulative prices of both pool assets at a given point in time. Each price is the amount of
+/// the other asset paid for one unit, with 18 decimal places, summed up for every second it was
+/// in effect.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Observation {
+    /// Ledger timestamp of the observation
+    pub timestamp: u64,
+    /// Cumulative price of asset A denominated in asset B
+    pub price_a_cumulative: i128,
+    /// Cumulative price of asset B denominated in asset A
+    pub price_b_cumulative: i128,
+}
+
 /// This struct is used to return a query result with the total amount of LP tokens and assets in a specific pool.
 #[contracttype]
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -147,6 +177,8 @@ pub struct LiquidityPoolInfo {
 pub struct SimulateSwapResponse {
     pub ask_amount: i128,
     pub commission_amount: i128,
+    pub protocol_fee_amount: i128,
+    pub lp_fee_amount: i128,
     pub spread_amount: i128,
     pub total_return: i128,
 }
@@ -159,8 +191,24 @@ pub struct SimulateReverseSwapResponse {
     pub spread_amount: i128,
 }
 
+/// The result of a simulated deposit of liquidity.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimulateProvideLiquidityResponse {
+    /// The asset A together with the amount that would be deposited
+    pub asset_a: Asset,
+    /// The asset B together with the amount that would be deposited
+    pub asset_b: Asset,
+    /// The amount of LP tokens minted to the depositor
+    pub share_amount: i128,
+    /// The difference between the price of the desired amounts and the pool price, in bps rounded
+    /// up. The deposit is accepted with a `custom_slippage_bps` of at least this value
+    pub slippage_bps: i64,
+}
+
 pub mod utils {
     use super::*;
+    use phoenix::utils::AdminChange;
 
     pub fn deploy_token_contract(
         e: &Env,
@@ -190,6 +238,20 @@ pub mod utils {
         e.storage().persistent().set(&DataKey::Admin, &address)
     }
 
+    pub fn save_admin_change(e: &Env, admin_change: &AdminChange) {
+        e.storage()
+            .persistent()
+            .set(&DataKey::AdminChange, admin_change)
+    }
+
+    pub fn remove_admin_change(e: &Env) {
+        e.storage().persistent().remove(&DataKey::AdminChange)
+    }
+
+    pub fn save_factory(e: &Env, address: Address) {
+        e.storage().persistent().set(&DataKey::Factory, &address)
+    }
+
     pub fn save_total_shares(e: &Env, amount: i128) {
         e.storage().persistent().set(&DataKey::TotalShares, &amount)
     }
@@ -223,6 +285,14 @@ pub mod utils {
         e.storage().persistent().get(&DataKey::Admin).unwrap()
     }
 
+    pub fn get_admin_change(e: &Env) -> Option<AdminChange> {
+        e.storage().persistent().get(&DataKey::AdminChange)
+    }
+
+    pub fn get_factory(e: &Env) -> Address {
+        e.storage().persistent().get(&DataKey::Factory).unwrap()
+    }
+
     pub fn get_total_shares(e: &Env) -> i128 {
         e.storage().persistent().get(&DataKey::TotalShares).unwrap()
     }
@@ -640,6 +710,8 @@ mod tests {
             fee_recipient: Address::generate(&env),
             max_allowed_spread_bps: 10_i64,
             max_referral_bps: 10i64,
+            protocol_fee_share_bps: 10_000i64,
+            paused: false,
         };
 
         let result = config.max_allowed_slippage();
@@ -1,5 +1,6 @@
 mod config;
 mod liquidity;
+mod oracle;
 mod setup;
 mod stake_deployment;
 mod swap;
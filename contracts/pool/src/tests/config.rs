@@ -1,6 +1,9 @@
 extern crate std;
 use phoenix::utils::{LiquidityPoolInitInfo, StakeInitInfo, TokenInitInfo};
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env, String,
+};
 
 use super::setup::{
     deploy_liquidity_pool_contract, deploy_token_contract, install_new_lp_wasm, install_stake_wasm,
@@ -112,17 +115,19 @@ fn update_config() {
             max_allowed_slippage_bps: 500,
             max_allowed_spread_bps: 200,
             max_referral_bps: 5_000,
+            protocol_fee_share_bps: 10_000,
+            paused: false,
         }
     );
 
     // update fees and recipient
     pool.update_config(
-        &None,
         &Some(500i64), // 5% fees
         &Some(admin2.clone()),
         &None,
         &None,
         &Some(1_000i64),
+        &None,
     );
     assert_eq!(
         pool.query_config(),
@@ -137,11 +142,13 @@ fn update_config() {
             max_allowed_slippage_bps: 500,
             max_allowed_spread_bps: 200,
             max_referral_bps: 1_000,
+            protocol_fee_share_bps: 10_000,
+            paused: false,
         }
     );
 
     // update slippage and spread
-    pool.update_config(&None, &None, &None, &None, &Some(5_000i64), &Some(500));
+    pool.update_config(&None, &None, &None, &Some(5_000i64), &Some(500), &None);
     assert_eq!(
         pool.query_config(),
         Config {
@@ -155,6 +162,8 @@ fn update_config() {
             max_allowed_slippage_bps: 500,
             max_allowed_spread_bps: 5_000,
             max_referral_bps: 500,
+            protocol_fee_share_bps: 10_000,
+            paused: false,
         }
     );
 }
@@ -191,12 +200,12 @@ fn update_config_unauthorized() {
     );
 
     pool.update_config(
-        &None,
         &Some(500i64), // 5% fees
         &Some(admin2.clone()),
         &None,
         &None,
         &None,
+        &None,
     );
 }
 
@@ -232,14 +241,16 @@ fn update_config_update_admin() {
         stake_owner,
     );
 
-    // update admin to new admin
-    pool.update_config(&Some(admin2.clone()), &None, &None, &None, &None, &None);
+    // the admin is only changed through a proposal accepted by the new admin
+    pool.propose_admin(&admin1, &admin2, &1_000);
+    pool.accept_admin(&admin2);
 
     let share_token_address = pool.query_share_token_address();
     let stake_token_address = pool.query_stake_contract_address();
 
-    // now update succeeds
-    pool.update_config(&Some(admin2.clone()), &None, &None, &None, &None, &None);
+    // now update is authorized by the new admin
+    pool.update_config(&None, &None, &None, &Some(3_000i64), &None, &None);
+    assert_eq!(env.auths()[0].0, admin2);
     assert_eq!(
         pool.query_config(),
         Config {
@@ -251,8 +262,10 @@ fn update_config_update_admin() {
             total_fee_bps: 0,
             fee_recipient: user1,
             max_allowed_slippage_bps: 500,
-            max_allowed_spread_bps: 200,
+            max_allowed_spread_bps: 3_000,
             max_referral_bps: 5_000,
+            protocol_fee_share_bps: 10_000,
+            paused: false,
         }
     );
 }
@@ -291,12 +304,54 @@ fn update_config_too_high_fees() {
 
     // update fees and recipient
     pool.update_config(
-        &None,
         &Some(10_100i64), // 101% fees
         &Some(admin2.clone()),
         &None,
         &None,
         &None,
+        &None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Pool: UpdateConfig: Invalid protocol_fee_share_bps")]
+fn update_config_too_high_protocol_fee_share() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let mut admin1 = Address::generate(&env);
+    let mut admin2 = Address::generate(&env);
+
+    let mut token1 = deploy_token_contract(&env, &admin1);
+    let mut token2 = deploy_token_contract(&env, &admin2);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+        std::mem::swap(&mut admin1, &mut admin2);
+    }
+    let user1 = Address::generate(&env);
+    let stake_manager = Address::generate(&env);
+    let stake_owner = Address::generate(&env);
+
+    let swap_fees = 0i64;
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        Some(admin1.clone()),
+        (&token1.address, &token2.address),
+        swap_fees,
+        user1,
+        500,
+        200,
+        stake_manager,
+        stake_owner,
+    );
+
+    pool.update_config(
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &Some(10_001i64), // more than the whole commission
     );
 }
 
@@ -358,3 +413,304 @@ fn update_liquidity_pool_works() {
     );
     assert_eq!(result.total_fee_bps, 0);
 }
+
+#[test]
+fn pause_blocks_swaps_but_allows_withdrawals() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let mut token1 = deploy_token_contract(&env, &admin);
+    let mut token2 = deploy_token_contract(&env, &admin);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+    let user1 = Address::generate(&env);
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        Some(admin.clone()),
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    token1.mint(&user1, &20_000);
+    token2.mint(&user1, &10_000);
+    pool.provide_liquidity(
+        &user1,
+        &Some(10_000),
+        &None,
+        &Some(10_000),
+        &None,
+        &None,
+        &None,
+    );
+
+    pool.pause(&admin);
+    assert!(pool.query_config().paused);
+
+    // swaps and deposits are rejected while paused
+    assert!(pool
+        .try_swap(&user1, &None, &token1.address, &1_000, &None, &None, &None)
+        .is_err());
+    assert!(pool
+        .try_provide_liquidity(
+            &user1,
+            &Some(1_000),
+            &None,
+            &Some(1_000),
+            &None,
+            &None,
+            &None,
+        )
+        .is_err());
+
+    // withdrawals are still possible
+    pool.withdraw_liquidity(&user1, &5_000, &5_000, &5_000, &None);
+    assert_eq!(token1.balance(&user1), 15_000);
+    assert_eq!(token2.balance(&user1), 5_000);
+
+    pool.unpause(&admin);
+    assert!(!pool.query_config().paused);
+
+    pool.swap(&user1, &None, &token1.address, &100, &None, &None, &None);
+    assert_eq!(token1.balance(&user1), 14_900);
+}
+
+#[test]
+#[should_panic(expected = "Pool: Pool is paused")]
+fn swap_when_paused_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let mut token1 = deploy_token_contract(&env, &admin);
+    let mut token2 = deploy_token_contract(&env, &admin);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+    let user1 = Address::generate(&env);
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        Some(admin.clone()),
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    token1.mint(&user1, &11_000);
+    token2.mint(&user1, &10_000);
+    pool.provide_liquidity(
+        &user1,
+        &Some(10_000),
+        &None,
+        &Some(10_000),
+        &None,
+        &None,
+        &None,
+    );
+
+    pool.pause(&admin);
+    pool.swap(&user1, &None, &token1.address, &1_000, &None, &None, &None);
+}
+
+#[test]
+fn factory_can_pause_the_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let factory = Address::generate(&env);
+    let mut token1 = deploy_token_contract(&env, &admin);
+    let mut token2 = deploy_token_contract(&env, &admin);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        Some(admin),
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        factory.clone(),
+    );
+
+    pool.pause(&factory);
+    assert!(pool.query_config().paused);
+    pool.unpause(&factory);
+    assert!(!pool.query_config().paused);
+}
+
+#[test]
+#[should_panic(expected = "Pool: Pause: Only the admin or the factory can pause the pool")]
+fn pause_by_unauthorized_user_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let mut token1 = deploy_token_contract(&env, &admin);
+    let mut token2 = deploy_token_contract(&env, &admin);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        Some(admin),
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    pool.pause(&Address::generate(&env));
+}
+
+#[test]
+fn propose_and_accept_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let mut token1 = deploy_token_contract(&env, &admin);
+    let mut token2 = deploy_token_contract(&env, &admin);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        Some(admin.clone()),
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    pool.propose_admin(&admin, &new_admin, &1_000);
+    // the admin role is handed over only after it is accepted
+    pool.pause(&admin);
+
+    pool.accept_admin(&new_admin);
+
+    assert!(pool.try_unpause(&admin).is_err());
+    pool.unpause(&new_admin);
+    assert!(!pool.query_config().paused);
+}
+
+#[test]
+#[should_panic(expected = "Pool: Accept admin: Only the proposed admin can accept the admin role")]
+fn accept_admin_by_other_address_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let mut token1 = deploy_token_contract(&env, &admin);
+    let mut token2 = deploy_token_contract(&env, &admin);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        Some(admin.clone()),
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    pool.propose_admin(&admin, &Address::generate(&env), &1_000);
+    pool.accept_admin(&Address::generate(&env));
+}
+
+#[test]
+#[should_panic(expected = "Pool: Accept admin: Admin change proposal has expired")]
+fn accept_expired_admin_proposal_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let mut token1 = deploy_token_contract(&env, &admin);
+    let mut token2 = deploy_token_contract(&env, &admin);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        Some(admin.clone()),
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    pool.propose_admin(&admin, &new_admin, &1_000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1_001;
+    });
+
+    pool.accept_admin(&new_admin);
+}
+
+#[test]
+#[should_panic(expected = "Pool: Accept admin: No admin change in place")]
+fn accept_cancelled_admin_proposal_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let mut token1 = deploy_token_contract(&env, &admin);
+    let mut token2 = deploy_token_contract(&env, &admin);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        Some(admin.clone()),
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    pool.propose_admin(&admin, &new_admin, &1_000);
+    pool.cancel_admin_proposal(&admin);
+
+    pool.accept_admin(&new_admin);
+}
@@ -4,13 +4,14 @@ use pretty_assertions::assert_eq;
 
 use soroban_sdk::{
     symbol_short,
-    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Ledger},
     Address, Env, IntoVal, Symbol,
 };
+use test_case::test_case;
 
 use super::setup::{deploy_liquidity_pool_contract, deploy_token_contract};
 use crate::{
-    storage::{Asset, PoolResponse},
+    storage::{Asset, PoolResponse, SimulateProvideLiquidityResponse},
     token_contract,
 };
 
@@ -49,18 +50,19 @@ fn provide_liqudity() {
     let share_token_address = pool.query_share_token_address();
     let token_share = token_contract::Client::new(&env, &share_token_address);
 
-    token1.mint(&user1, &1000);
-    assert_eq!(token1.balance(&user1), 1000);
+    token1.mint(&user1, &100_000);
+    assert_eq!(token1.balance(&user1), 100_000);
 
-    token2.mint(&user1, &1000);
-    assert_eq!(token2.balance(&user1), 1000);
+    token2.mint(&user1, &100_000);
+    assert_eq!(token2.balance(&user1), 100_000);
 
     pool.provide_liquidity(
         &user1,
-        &Some(100),
-        &Some(100),
-        &Some(100),
-        &Some(100),
+        &Some(10_000),
+        &Some(10_000),
+        &Some(10_000),
+        &Some(10_000),
+        &None,
         &None,
     );
 
@@ -74,11 +76,12 @@ fn provide_liqudity() {
                     Symbol::new(&env, "provide_liquidity"),
                     (
                         &user1,
-                        Some(100i128),
-                        Some(100i128),
-                        Some(100i128),
-                        Some(100i128),
-                        None::<i64>
+                        Some(10_000i128),
+                        Some(10_000i128),
+                        Some(10_000i128),
+                        Some(10_000i128),
+                        None::<i64>,
+                        None::<u64>
                     )
                         .into_val(&env),
                 )),
@@ -87,7 +90,7 @@ fn provide_liqudity() {
                         function: AuthorizedFunction::Contract((
                             token1.address.clone(),
                             symbol_short!("transfer"),
-                            (&user1, &pool.address, 100_i128).into_val(&env)
+                            (&user1, &pool.address, 10_000_i128).into_val(&env)
                         )),
                         sub_invocations: std::vec![],
                     },
@@ -95,7 +98,7 @@ fn provide_liqudity() {
                         function: AuthorizedFunction::Contract((
                             token2.address.clone(),
                             symbol_short!("transfer"),
-                            (&user1, &pool.address, 100_i128).into_val(&env)
+                            (&user1, &pool.address, 10_000_i128).into_val(&env)
                         )),
                         sub_invocations: std::vec![],
                     },
@@ -104,12 +107,13 @@ fn provide_liqudity() {
         ),]
     );
 
-    assert_eq!(token_share.balance(&user1), 100);
-    assert_eq!(token_share.balance(&pool.address), 0);
-    assert_eq!(token1.balance(&user1), 900);
-    assert_eq!(token1.balance(&pool.address), 100);
-    assert_eq!(token2.balance(&user1), 900);
-    assert_eq!(token2.balance(&pool.address), 100);
+    // the minimum liquidity is locked in the pool on the first deposit
+    assert_eq!(token_share.balance(&user1), 9_000);
+    assert_eq!(token_share.balance(&pool.address), 1_000);
+    assert_eq!(token1.balance(&user1), 90_000);
+    assert_eq!(token1.balance(&pool.address), 10_000);
+    assert_eq!(token2.balance(&user1), 90_000);
+    assert_eq!(token2.balance(&pool.address), 10_000);
 
     let result = pool.query_pool_info();
     assert_eq!(
@@ -117,15 +121,15 @@ fn provide_liqudity() {
         PoolResponse {
             asset_a: Asset {
                 address: token1.address,
-                amount: 100i128
+                amount: 10_000i128
             },
             asset_b: Asset {
                 address: token2.address,
-                amount: 100i128
+                amount: 10_000i128
             },
             asset_lp_share: Asset {
                 address: share_token_address,
-                amount: 100i128
+                amount: 10_000i128
             },
             stake_address: result.clone().stake_address,
         }
@@ -167,26 +171,28 @@ fn withdraw_liquidity() {
     let share_token_address = pool.query_share_token_address();
     let token_share = token_contract::Client::new(&env, &share_token_address);
 
-    token1.mint(&user1, &100);
-    token2.mint(&user1, &100);
+    token1.mint(&user1, &10_000);
+    token2.mint(&user1, &10_000);
     pool.provide_liquidity(
         &user1,
-        &Some(100),
-        &Some(100),
-        &Some(100),
-        &Some(100),
+        &Some(10_000),
+        &Some(10_000),
+        &Some(10_000),
+        &Some(10_000),
+        &None,
         &None,
     );
 
     assert_eq!(token1.balance(&user1), 0);
-    assert_eq!(token1.balance(&pool.address), 100);
+    assert_eq!(token1.balance(&pool.address), 10_000);
     assert_eq!(token2.balance(&user1), 0);
-    assert_eq!(token2.balance(&pool.address), 100);
+    assert_eq!(token2.balance(&pool.address), 10_000);
 
-    let share_amount = 50;
-    let min_a = 50;
-    let min_b = 50;
-    pool.withdraw_liquidity(&user1, &share_amount, &min_a, &min_b);
+    // 1_000 shares are locked in the pool, so the user has 9_000 shares
+    let share_amount = 4_500;
+    let min_a = 4_500;
+    let min_b = 4_500;
+    pool.withdraw_liquidity(&user1, &share_amount, &min_a, &min_b, &None);
     // assert_eq!(
     //     env.auths(),
     //     [
@@ -205,12 +211,12 @@ fn withdraw_liquidity() {
     //     ]
     // );
 
-    assert_eq!(token_share.balance(&user1), 50);
-    assert_eq!(token_share.balance(&pool.address), 0); // sanity check
-    assert_eq!(token1.balance(&user1), 50);
-    assert_eq!(token1.balance(&pool.address), 50);
-    assert_eq!(token2.balance(&user1), 50);
-    assert_eq!(token2.balance(&pool.address), 50);
+    assert_eq!(token_share.balance(&user1), 4_500);
+    assert_eq!(token_share.balance(&pool.address), 1_000); // sanity check
+    assert_eq!(token1.balance(&user1), 4_500);
+    assert_eq!(token1.balance(&pool.address), 5_500);
+    assert_eq!(token2.balance(&user1), 4_500);
+    assert_eq!(token2.balance(&pool.address), 5_500);
 
     let result = pool.query_pool_info();
     assert_eq!(
@@ -218,366 +224,383 @@ fn withdraw_liquidity() {
         PoolResponse {
             asset_a: Asset {
                 address: token1.address.clone(),
-                amount: 50i128,
+                amount: 5_500i128,
             },
             asset_b: Asset {
                 address: token2.address.clone(),
-                amount: 50i128,
+                amount: 5_500i128,
             },
             asset_lp_share: Asset {
                 address: share_token_address,
-                amount: 50i128,
+                amount: 5_500i128,
             },
             stake_address: result.clone().stake_address,
         }
     );
 
-    // clear the pool
-    pool.withdraw_liquidity(&user1, &share_amount, &min_a, &min_b);
+    // withdraw the rest, only the locked minimum liquidity stays in the pool. The returned amounts
+    // are rounded down in favour of the pool.
+    pool.withdraw_liquidity(&user1, &share_amount, &4_499, &4_499, &None);
     assert_eq!(token_share.balance(&user1), 0);
-    assert_eq!(token_share.balance(&pool.address), 0); // sanity check
-    assert_eq!(token1.balance(&user1), 100);
-    assert_eq!(token1.balance(&pool.address), 0);
-    assert_eq!(token2.balance(&user1), 100);
-    assert_eq!(token2.balance(&pool.address), 0);
+    assert_eq!(token_share.balance(&pool.address), 1_000); // sanity check
+    assert_eq!(token1.balance(&user1), 8_999);
+    assert_eq!(token1.balance(&pool.address), 1_001);
+    assert_eq!(token2.balance(&user1), 8_999);
+    assert_eq!(token2.balance(&pool.address), 1_001);
+}
+
+fn deploy_pool_with_liquidity<'a>(
+    env: &Env,
+    swap_fees: i64,
+    fee_recipient: &Address,
+    user: &Address,
+    amount_a: i128,
+    amount_b: i128,
+) -> (
+    token_contract::Client<'a>,
+    token_contract::Client<'a>,
+    crate::contract::LiquidityPoolClient<'a>,
+) {
+    let mut admin1 = Address::generate(env);
+    let mut admin2 = Address::generate(env);
+
+    let mut token1 = deploy_token_contract(env, &admin1);
+    let mut token2 = deploy_token_contract(env, &admin2);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+        std::mem::swap(&mut admin1, &mut admin2);
+    }
+
+    let pool = deploy_liquidity_pool_contract(
+        env,
+        None,
+        (&token1.address, &token2.address),
+        swap_fees,
+        fee_recipient.clone(),
+        None,
+        None,
+        Address::generate(env),
+        Address::generate(env),
+    );
+
+    token1.mint(user, &amount_a);
+    token2.mint(user, &amount_b);
+    pool.provide_liquidity(
+        user,
+        &Some(amount_a),
+        &Some(amount_a),
+        &Some(amount_b),
+        &Some(amount_b),
+        &None,
+        &None,
+    );
+
+    (token1, token2, pool)
+}
+
+#[test]
+#[should_panic = "Pool: split_deposit_based_on_pool_ratio: Both pools and deposit must be a positive!"]
+fn provide_liqudity_single_asset_on_empty_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let mut admin1 = Address::generate(&env);
+    let mut admin2 = Address::generate(&env);
+
+    let mut token1 = deploy_token_contract(&env, &admin1);
+    let mut token2 = deploy_token_contract(&env, &admin2);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+        std::mem::swap(&mut admin1, &mut admin2);
+    }
+    let user1 = Address::generate(&env);
+    let stake_manager = Address::generate(&env);
+    let stake_owner = Address::generate(&env);
+
+    let swap_fees = 0i64;
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        None,
+        (&token1.address, &token2.address),
+        swap_fees,
+        None,
+        None,
+        None,
+        stake_manager,
+        stake_owner,
+    );
+
+    token1.mint(&user1, &1_000_000);
+
+    // providing liquidity with single asset is not allowed on an empty pool
+    pool.provide_liquidity(
+        &user1,
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &None,
+        &Some(1),
+        &None,
+        &None,
+    );
+}
+
+#[test]
+fn provide_liqudity_single_asset_equal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user1 = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let (token1, token2, pool) =
+        deploy_pool_with_liquidity(&env, 0i64, &fee_recipient, &user1, 10_000_000, 10_000_000);
+    let token_share = token_contract::Client::new(&env, &pool.query_share_token_address());
+    // 1_000 shares are locked in the pool on the first deposit
+    assert_eq!(token_share.balance(&user1), 9_999_000);
+
+    token1.mint(&user1, &100_000);
+
+    // Providing 100k of token1 to 1:1 pool will perform swap which will create imbalance
+    pool.provide_liquidity(
+        &user1,
+        &Some(100_000),
+        &Some(50_000),
+        &None,
+        &Some(49_000),
+        &None,
+        &None,
+    );
+    // before swap : A(10_000_000), B(10_000_000)
+    // x = 2a / (2 + sqrt(4 + 4 * a / A)) = 49_875 of token A is swapped for 49_628 of token B
+    // after swap : A(10_049_875), B(9_950_372)
+    // 50_125 A and 49_628 B are then deposited in the new pool ratio
+    // after providing liquidity
+    // A(10_100_000), B(10_000_000)
+    assert_eq!(token1.balance(&pool.address), 10_100_000);
+    // because of lack of fees, the swap took from pool B the exact amount
+    // that was provided to the pool in the next step
+    assert_eq!(token2.balance(&pool.address), 10_000_000);
+    assert_eq!(token1.balance(&user1), 0);
+    assert_eq!(token2.balance(&user1), 0);
+    assert_eq!(token_share.balance(&user1), 10_048_875);
+}
+
+#[test]
+fn provide_liqudity_single_asset_equal_with_fees() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user1 = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let swap_fees = 1_000i64; // 10% bps
+    let initial_pool_liquidity = 10_000_000;
+    let (token1, token2, pool) = deploy_pool_with_liquidity(
+        &env,
+        swap_fees,
+        &fee_recipient,
+        &user1,
+        initial_pool_liquidity,
+        initial_pool_liquidity,
+    );
+    let token_share = token_contract::Client::new(&env, &pool.query_share_token_address());
+
+    let token_a_amount = 100_000;
+    token1.mint(&user1, &token_a_amount);
+    // Providing 100k of token1 to 1:1 pool will perform swap which will create imbalance
+    pool.provide_liquidity(
+        &user1,
+        &Some(token_a_amount),
+        &Some(45_000),
+        &None,
+        &Some(45_000),
+        &None,
+        &None,
+    );
+    // before swap : A(10_000_000), B(10_000_000)
+    // with a 10% fee the split has to swap more than half of the deposit to keep the ratio:
+    // x = 2a / (1.9 + sqrt(1.9^2 + 3.6 * a / A)) = 52_501 of token A
+    // the swap returns 52_227 of token B, 5_222 of which are the fee, so the user gets 47_005
+    // after swap : A(10_052_501), B(9_947_773)
+    // 47_499 A and 47_005 B are then deposited in the new pool ratio
+    // after providing liquidity
+    // A(10_100_000), B(9_994_778)
+    let fees = 5_222;
+    assert_eq!(
+        token1.balance(&pool.address),
+        initial_pool_liquidity + token_a_amount
+    );
+    assert_eq!(token2.balance(&pool.address), initial_pool_liquidity - fees);
+    assert_eq!(token2.balance(&fee_recipient), fees);
+    assert_eq!(token1.balance(&user1), 0);
+    assert_eq!(token2.balance(&user1), 0);
+    assert_eq!(token_share.balance(&user1), 10_046_250);
+}
+
+#[test]
+fn provide_liqudity_single_asset_one_third() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user1 = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    // providing liquidity in 1:3 ratio
+    let (token1, token2, pool) =
+        deploy_pool_with_liquidity(&env, 0i64, &fee_recipient, &user1, 10_000_000, 30_000_000);
+    assert_eq!(token1.balance(&pool.address), 10_000_000);
+    assert_eq!(token2.balance(&pool.address), 30_000_000);
+
+    token2.mint(&user1, &100_000);
+    // Providing 100k of token2 to 1:3 pool will perform swap which will create imbalance
+    let slippage_tolerance_bps = 300; // 3%
+    pool.provide_liquidity(
+        &user1,
+        &None,
+        &Some(16_000),
+        &Some(100_000),
+        &None,
+        &Some(slippage_tolerance_bps),
+        &None,
+    );
+    // before swap : A(10_000_000), B(30_000_000)
+    // swap 49_958 B for 16_625 A
+    // after swap : A(9_983_375), B(30_049_958)
+    // after providing liquidity
+    // A(10_000_000), B(30_100_000)
+
+    assert_eq!(token1.balance(&pool.address), 10_000_000);
+    assert_eq!(token2.balance(&pool.address), 30_100_000);
+    assert_eq!(token1.balance(&user1), 0);
+    assert_eq!(token2.balance(&user1), 0);
 }
 
-// TODO: https://github.com/Phoenix-Protocol-Group/phoenix-contracts/issues/204
-// #[test]
-// #[should_panic = "Pool: split_deposit_based_on_pool_ratio: Both pools and deposit must be a positive!"]
-// fn provide_liqudity_single_asset_on_empty_pool() {
-//     let env = Env::default();
-//     env.mock_all_auths();
-//
-//     let mut admin1 = Address::generate(&env);
-//     let mut admin2 = Address::generate(&env);
-//
-//     let mut token1 = deploy_token_contract(&env, &admin1);
-//     let mut token2 = deploy_token_contract(&env, &admin2);
-//     if token2.address < token1.address {
-//         std::mem::swap(&mut token1, &mut token2);
-//         std::mem::swap(&mut admin1, &mut admin2);
-//     }
-//     let user1 = Address::generate(&env);
-//     let stake_manager = Address::generate(&env);
-//     let stake_owner = Address::generate(&env);
-//
-//     let swap_fees = 0i64;
-//     let pool = deploy_liquidity_pool_contract(
-//         &env,
-//         None,
-//         (&token1.address, &token2.address),
-//         swap_fees,
-//         None,
-//         None,
-//         None,
-//         stake_manager,
-//         stake_owner,
-//     );
-//
-//     token1.mint(&user1, &1_000_000);
-//
-//     // providing liquidity with single asset is not allowed on an empty pool
-//     pool.provide_liquidity(
-//         &user1,
-//         &Some(1_000_000),
-//         &Some(1_000_000),
-//         &None,
-//         &None,
-//         &None,
-//     );
-// }
-//
-// #[test]
-// fn provide_liqudity_single_asset_equal() {
-//     let env = Env::default();
-//     env.mock_all_auths();
-//     env.budget().reset_unlimited();
-//
-//     let mut admin1 = Address::generate(&env);
-//     let mut admin2 = Address::generate(&env);
-//
-//     let mut token1 = deploy_token_contract(&env, &admin1);
-//     let mut token2 = deploy_token_contract(&env, &admin2);
-//     if token2.address < token1.address {
-//         std::mem::swap(&mut token1, &mut token2);
-//         std::mem::swap(&mut admin1, &mut admin2);
-//     }
-//     let user1 = Address::generate(&env);
-//     let stake_manager = Address::generate(&env);
-//     let stake_owner = Address::generate(&env);
-//
-//     let swap_fees = 0i64;
-//     let pool = deploy_liquidity_pool_contract(
-//         &env,
-//         None,
-//         (&token1.address, &token2.address),
-//         swap_fees,
-//         None,
-//         None,
-//         None,
-//         stake_manager,
-//         stake_owner,
-//     );
-//
-//     token1.mint(&user1, &10_000_000);
-//     token2.mint(&user1, &10_000_000);
-//
-//     // providing liquidity with equal assets
-//     pool.provide_liquidity(
-//         &user1,
-//         &Some(10_000_000),
-//         &Some(10_000_000),
-//         &Some(10_000_000),
-//         &Some(10_000_000),
-//         &None,
-//     );
-//     assert_eq!(token1.balance(&pool.address), 10_000_000);
-//     assert_eq!(token2.balance(&pool.address), 10_000_000);
-//
-//     token1.mint(&user1, &100_000);
-//
-//     // Providing 100k of token1 to 1:1 pool will perform swap which will create imbalance
-//     pool.provide_liquidity(
-//         &user1,
-//         &Some(100_000),
-//         &Some(50_000),
-//         &None,
-//         &Some(49_000),
-//         &None,
-//     );
-//     // before swap : A(10_000_000), B(10_000_000)
-//     // since pool is equal divides 50/50 sum for swap
-//     // swap 50k A for B = 49752
-//     // after swap : A(10_050_000), B(9_950_248)
-//     // after providing liquidity
-//     // A(1_100_000), B(1_000_000)
-//
-//     assert_eq!(token1.balance(&pool.address), 10_100_000);
-//     // because of lack of fees, first swap took from pool b exact amount
-//     // that was provided to the pool in the next step
-//     assert_eq!(token2.balance(&pool.address), 10_000_000);
-//     assert_eq!(token1.balance(&user1), 0);
-//     assert_eq!(token2.balance(&user1), 0);
-// }
-//
-// #[test]
-// fn provide_liqudity_single_asset_equal_with_fees() {
-//     let env = Env::default();
-//     env.mock_all_auths();
-//     env.budget().reset_unlimited();
-//
-//     let mut admin1 = Address::generate(&env);
-//     let mut admin2 = Address::generate(&env);
-//
-//     let mut token1 = deploy_token_contract(&env, &admin1);
-//     let mut token2 = deploy_token_contract(&env, &admin2);
-//     if token2.address < token1.address {
-//         std::mem::swap(&mut token1, &mut token2);
-//         std::mem::swap(&mut admin1, &mut admin2);
-//     }
-//     let user1 = Address::generate(&env);
-//     let stake_manager = Address::generate(&env);
-//     let stake_owner = Address::generate(&env);
-//
-//     let swap_fees = 1_000i64; // 10% bps
-//     let pool = deploy_liquidity_pool_contract(
-//         &env,
-//         None,
-//         (&token1.address, &token2.address),
-//         swap_fees,
-//         None,
-//         None,
-//         None,
-//         stake_manager,
-//         stake_owner,
-//     );
-//
-//     let initial_pool_liquidity = 10_000_000;
-//     token1.mint(&user1, &initial_pool_liquidity);
-//     token2.mint(&user1, &initial_pool_liquidity);
-//
-//     // providing liquidity with equal assets
-//     pool.provide_liquidity(
-//         &user1,
-//         &Some(initial_pool_liquidity),
-//         &Some(initial_pool_liquidity),
-//         &Some(initial_pool_liquidity),
-//         &Some(initial_pool_liquidity),
-//         &None,
-//     );
-//     assert_eq!(token1.balance(&pool.address), initial_pool_liquidity);
-//     assert_eq!(token2.balance(&pool.address), initial_pool_liquidity);
-//
-//     let token_a_amount = 100_000;
-//     token1.mint(&user1, &token_a_amount);
-//     // Providing 100k of token1 to 1:1 pool will perform swap which will create imbalance
-//     pool.provide_liquidity(
-//         &user1,
-//         &Some(token_a_amount),
-//         &Some(50_000),
-//         &None,
-//         &Some(49_000),
-//         &None,
-//     );
-//     // before swap : A(10_000_000), B(10_000_000)
-//     // algorithm splits 100k in such way, so that after swapping (with 10% fee)
-//     // it will provide liquidity maintining 1:1 ratio
-//     // split is 47_266 token A and 47213 token B (52_734 of token A was swapped to B)
-//     // after swap : A(10_052_734), B(9_947_542)
-//     // after providing liquidity
-//     // A(1_100_000), B(9_994_755)
-//
-//     // return_amount: i128 = ask_pool - (cp / (offer_pool + offer_amount))
-//     let return_amount = 52_458; // that's how many tokens B would be received from 52_734 tokens A
-//     let fees = Decimal::percent(10);
-//     assert_eq!(
-//         token1.balance(&pool.address),
-//         initial_pool_liquidity + token_a_amount
-//     );
-//     assert_eq!(
-//         token2.balance(&pool.address),
-//         initial_pool_liquidity - return_amount * fees
-//     );
-//     assert_eq!(token1.balance(&user1), 0);
-//     assert_eq!(token2.balance(&user1), 0);
-// }
-//
-// #[test]
-// fn provide_liqudity_single_asset_one_third() {
-//     let env = Env::default();
-//     env.mock_all_auths();
-//     env.budget().reset_unlimited();
-//
-//     let mut admin1 = Address::generate(&env);
-//     let mut admin2 = Address::generate(&env);
-//
-//     let mut token1 = deploy_token_contract(&env, &admin1);
-//     let mut token2 = deploy_token_contract(&env, &admin2);
-//     if token2.address < token1.address {
-//         std::mem::swap(&mut token1, &mut token2);
-//         std::mem::swap(&mut admin1, &mut admin2);
-//     }
-//     let user1 = Address::generate(&env);
-//     let stake_manager = Address::generate(&env);
-//     let stake_owner = Address::generate(&env);
-//
-//     let swap_fees = 0i64;
-//     let pool = deploy_liquidity_pool_contract(
-//         &env,
-//         None,
-//         (&token1.address, &token2.address),
-//         swap_fees,
-//         None,
-//         None,
-//         None,
-//         stake_manager,
-//         stake_owner,
-//     );
-//
-//     token1.mint(&user1, &10_000_000);
-//     token2.mint(&user1, &30_000_000);
-//
-//     // providing liquidity in 1:3 ratio
-//     pool.provide_liquidity(
-//         &user1,
-//         &Some(10_000_000),
-//         &Some(10_000_000),
-//         &Some(30_000_000),
-//         &Some(30_000_000),
-//         &None,
-//     );
-//     assert_eq!(token1.balance(&pool.address), 10_000_000);
-//     assert_eq!(token2.balance(&pool.address), 30_000_000);
-//
-//     token2.mint(&user1, &100_000);
-//     // Providing 100k of token2 to 1:3 pool will perform swap which will create imbalance
-//     let slippage_tolerance_bps = 300; // 3%
-//     pool.provide_liquidity(
-//         &user1,
-//         &None,
-//         &None,
-//         &Some(100_000),
-//         &None,
-//         &Some(slippage_tolerance_bps),
-//     );
-//     // before swap : A(10_000_000), B(30_000_000)
-//     // since pool is 1/3 divides 75k/25k sum for swap
-//     // swap 25k B for A = 8327
-//     // after swap : A(9_991_673), B(30_025_000)
-//     // after providing liquidity
-//     // A(10_000_000), B(30_100_000)
-//
-//     assert_eq!(token1.balance(&pool.address), 10_000_000);
-//     assert_eq!(token2.balance(&pool.address), 30_100_000);
-// }
-//
-// #[test]
-// fn provide_liqudity_single_asset_one_third_with_fees() {
-//     let env = Env::default();
-//     env.mock_all_auths();
-//     env.budget().reset_unlimited();
-//
-//     let mut admin1 = Address::generate(&env);
-//     let mut admin2 = Address::generate(&env);
-//
-//     let mut token1 = deploy_token_contract(&env, &admin1);
-//     let mut token2 = deploy_token_contract(&env, &admin2);
-//     if token2.address < token1.address {
-//         std::mem::swap(&mut token1, &mut token2);
-//         std::mem::swap(&mut admin1, &mut admin2);
-//     }
-//     let user1 = Address::generate(&env);
-//     let stake_manager = Address::generate(&env);
-//     let stake_owner = Address::generate(&env);
-//
-//     let swap_fees = 1_000i64; // 10% bps
-//     let pool = deploy_liquidity_pool_contract(
-//         &env,
-//         None,
-//         (&token1.address, &token2.address),
-//         swap_fees,
-//         None,
-//         None,
-//         None,
-//         stake_manager,
-//         stake_owner,
-//     );
-//
-//     token1.mint(&user1, &10_000_000);
-//     token2.mint(&user1, &30_000_000);
-//
-//     // providing liquidity in 1:3 ratio
-//     pool.provide_liquidity(
-//         &user1,
-//         &Some(10_000_000),
-//         &Some(10_000_000),
-//         &Some(30_000_000),
-//         &Some(30_000_000),
-//         &None,
-//     );
-//     assert_eq!(token1.balance(&pool.address), 10_000_000);
-//     assert_eq!(token2.balance(&pool.address), 30_000_000);
-//
-//     token2.mint(&user1, &100_000);
-//     // providing liquidity with a single asset - token2
-//     pool.provide_liquidity(&user1, &None, &None, &Some(100_000), &None, &None);
-//     // before swap : A(10_000_000), B(30_000_000)
-//     // since pool is 1/3 algorithm will split it around 15794/52734
-//     // swap 47_226k B for A = 17_548 (-10% fee = 15_793)
-//     // after swap : A(9_982_452), B(30_052_734)
-//     // after providing liquidity
-//     // A(10_000_000), B(30_100_000)
-//
-//     // return_amount: i128 = ask_pool - (cp / (offer_pool + offer_amount))
-//     let return_amount = 17_548;
-//     let fees = Decimal::percent(10);
-//     assert_eq!(
-//         token1.balance(&pool.address),
-//         10_000_000 - return_amount * fees
-//     );
-//     assert_eq!(token2.balance(&pool.address), 30_100_000);
-// }
+#[test]
+fn provide_liqudity_single_asset_one_third_with_fees() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user1 = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let swap_fees = 1_000i64; // 10% bps
+                              // providing liquidity in 1:3 ratio
+    let (token1, token2, pool) = deploy_pool_with_liquidity(
+        &env,
+        swap_fees,
+        &fee_recipient,
+        &user1,
+        10_000_000,
+        30_000_000,
+    );
+
+    token2.mint(&user1, &100_000);
+    // providing liquidity with a single asset - token2
+    pool.provide_liquidity(
+        &user1,
+        &None,
+        &Some(15_000),
+        &Some(100_000),
+        &None,
+        &None,
+        &None,
+    );
+    // before swap : A(10_000_000), B(30_000_000)
+    // swap 52_587 B for 17_499 A (-10% fee = 15_750)
+    // after swap : A(9_982_501), B(30_052_587)
+    // after providing liquidity
+    // A(9_998_251), B(30_100_000)
+
+    let fees = 1_749;
+    assert_eq!(token1.balance(&pool.address), 10_000_000 - fees);
+    assert_eq!(token2.balance(&pool.address), 30_100_000);
+    assert_eq!(token1.balance(&fee_recipient), fees);
+    assert_eq!(token1.balance(&user1), 0);
+    assert_eq!(token2.balance(&user1), 0);
+}
+
+#[test]
+#[should_panic(
+    expected = "Pool: ProvideLiquidity: Minimum amount of the other token must be set when providing a single token"
+)]
+fn provide_liqudity_single_asset_without_min_amount_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user1 = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let (token1, _, pool) =
+        deploy_pool_with_liquidity(&env, 0i64, &fee_recipient, &user1, 10_000_000, 10_000_000);
+
+    token1.mint(&user1, &100_000);
+    pool.provide_liquidity(&user1, &Some(100_000), &None, &None, &None, &None, &None);
+}
+
+#[test]
+#[should_panic(expected = "Pool: do_swap: Return amount is smaller then expected minimum amount")]
+fn provide_liqudity_single_asset_sandwiched_swap_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user1 = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let (token1, _, pool) =
+        deploy_pool_with_liquidity(&env, 0i64, &fee_recipient, &user1, 10_000_000, 10_000_000);
+
+    // the user quotes the deposit on the untouched 1:1 pool: 49_875 A for 49_628 B
+    token1.mint(&user1, &100_000);
+
+    // the attacker front-runs the deposit and pushes the price of token B up
+    token1.mint(&attacker, &500_000);
+    pool.swap(
+        &attacker,
+        &None,
+        &token1.address,
+        &500_000,
+        &None,
+        &None,
+        &None,
+    );
+
+    // the internal swap would now return only 45_030 of token B
+    pool.provide_liquidity(
+        &user1,
+        &Some(100_000),
+        &None,
+        &None,
+        &Some(49_000),
+        &None,
+        &None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #34)")]
+fn provide_liqudity_single_asset_below_min_deposit_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user1 = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let (token1, _, pool) =
+        deploy_pool_with_liquidity(&env, 0i64, &fee_recipient, &user1, 10_000_000, 10_000_000);
+
+    // 49_875 of the 100_000 A are swapped, which leaves 50_125 A for the deposit
+    token1.mint(&user1, &100_000);
+    pool.provide_liquidity(
+        &user1,
+        &Some(100_000),
+        &Some(50_200),
+        &None,
+        &Some(49_000),
+        &None,
+        &None,
+    );
+}
 
 #[test]
 #[should_panic(expected = "The value 10001 is out of range. Must be between 0 and 10000 bps.")]
@@ -614,7 +637,7 @@ fn provide_liqudity_too_high_fees() {
 
 #[test]
 #[should_panic(
-    expected = "Pool: ProvideLiquidity: Both tokens must be provided and must be bigger then 0!"
+    expected = "Pool: ProvideLiquidity: At least one token must be provided and must be bigger then 0!"
 )]
 fn swap_with_no_amounts() {
     let env = Env::default();
@@ -649,7 +672,7 @@ fn swap_with_no_amounts() {
     token1.mint(&user1, &1_001_000);
     token2.mint(&user1, &1_001_000);
     // providing all amounts as None
-    pool.provide_liquidity(&user1, &None, &None, &None, &None, &None);
+    pool.provide_liquidity(&user1, &None, &None, &None, &None, &None, &None);
 }
 
 #[test]
@@ -686,21 +709,22 @@ fn withdraw_liqudity_below_min() {
         stake_owner,
     );
 
-    token1.mint(&user1, &100);
-    token2.mint(&user1, &100);
+    token1.mint(&user1, &10_000);
+    token2.mint(&user1, &10_000);
     // providing liquidity in a 1:1 ratio
     pool.provide_liquidity(
         &user1,
-        &Some(100),
-        &Some(100),
-        &Some(100),
-        &Some(100),
+        &Some(10_000),
+        &Some(10_000),
+        &Some(10_000),
+        &Some(10_000),
+        &None,
         &None,
     );
 
-    let share_amount = 50;
+    let share_amount = 5_000;
     // Expecting min_a and/or min_b as huge bigger then available
-    pool.withdraw_liquidity(&user1, &share_amount, &3000, &3000);
+    pool.withdraw_liquidity(&user1, &share_amount, &30_000, &30_000, &None);
 }
 
 #[test]
@@ -739,27 +763,45 @@ fn query_share_valid_liquidity() {
     let share_token_address = pool.query_share_token_address();
     let token_share = token_contract::Client::new(&env, &share_token_address.clone());
 
-    token1.mint(&user1, &150);
-    token1.mint(&user2, &250);
-    token1.mint(&user3, &350);
+    token1.mint(&user1, &15_000);
+    token1.mint(&user2, &25_000);
+    token1.mint(&user3, &35_000);
 
-    token2.mint(&user1, &200);
-    token2.mint(&user2, &300);
-    token2.mint(&user3, &400);
+    token2.mint(&user1, &20_000);
+    token2.mint(&user2, &30_000);
+    token2.mint(&user3, &40_000);
 
     // all users provide liquidity in a 3:4 ratio
-    pool.provide_liquidity(&user1, &Some(150), &Some(10), &Some(200), &Some(10), &None);
-    pool.provide_liquidity(&user2, &Some(150), &Some(50), &Some(200), &Some(50), &None);
+    pool.provide_liquidity(
+        &user1,
+        &Some(15_000),
+        &Some(1_000),
+        &Some(20_000),
+        &Some(1_000),
+        &None,
+        &None,
+    );
+    pool.provide_liquidity(
+        &user2,
+        &Some(15_000),
+        &Some(5_000),
+        &Some(20_000),
+        &Some(5_000),
+        &None,
+        &None,
+    );
     pool.provide_liquidity(
         &user3,
-        &Some(150),
-        &Some(100),
-        &Some(200),
-        &Some(100),
+        &Some(15_000),
+        &Some(10_000),
+        &Some(20_000),
+        &Some(10_000),
+        &None,
         &None,
     );
 
     // user1 assertions
+    // as the first depositor, 1_000 of user1 shares are locked in the pool
     let lp_share_balance_user1 = token_share.balance(&user1);
     let query_share_result_user1 = pool.query_share(&lp_share_balance_user1);
     assert_eq!(
@@ -767,11 +809,11 @@ fn query_share_valid_liquidity() {
         (
             Asset {
                 address: token1.address.clone(),
-                amount: 149
+                amount: 14_133
             },
             Asset {
                 address: token2.address.clone(),
-                amount: 199
+                amount: 18_845
             }
         )
     );
@@ -782,36 +824,42 @@ fn query_share_valid_liquidity() {
         PoolResponse {
             asset_a: Asset {
                 address: token1.address.clone(),
-                amount: 450
+                amount: 45_000
             },
             asset_b: Asset {
                 address: token2.address.clone(),
-                amount: 600
+                amount: 60_000
             },
             asset_lp_share: Asset {
                 address: share_token_address.clone(),
-                amount: 519
+                amount: 51_960
             },
             stake_address: pool_info_before_withdrawal.clone().stake_address,
         }
     );
 
-    pool.withdraw_liquidity(&user1, &lp_share_balance_user1, &100i128, &100i128);
+    pool.withdraw_liquidity(
+        &user1,
+        &lp_share_balance_user1,
+        &10_000i128,
+        &10_000i128,
+        &None,
+    );
     let pool_info_after_withdrawal = pool.query_pool_info();
     assert_eq!(
         pool_info_after_withdrawal,
         PoolResponse {
             asset_a: Asset {
                 address: token1.address.clone(),
-                amount: 301
+                amount: 30_867
             },
             asset_b: Asset {
                 address: token2.address.clone(),
-                amount: 401
+                amount: 41_155
             },
             asset_lp_share: Asset {
                 address: share_token_address.clone(),
-                amount: 346
+                amount: 35_640
             },
             stake_address: pool_info_after_withdrawal.clone().stake_address,
         }
@@ -843,31 +891,37 @@ fn query_share_valid_liquidity() {
         (
             Asset {
                 address: token1.address.clone(),
-                amount: 150
+                amount: 15_000
             },
             Asset {
                 address: token2.address.clone(),
-                amount: 200
+                amount: 20_000
             }
         )
     );
 
-    pool.withdraw_liquidity(&user2, &lp_share_balance_user2, &150i128, &200i128);
+    pool.withdraw_liquidity(
+        &user2,
+        &lp_share_balance_user2,
+        &15_000i128,
+        &20_000i128,
+        &None,
+    );
     let pool_info_after_withdrawal = pool.query_pool_info();
     assert_eq!(
         pool_info_after_withdrawal,
         PoolResponse {
             asset_a: Asset {
                 address: token1.address.clone(),
-                amount: 151
+                amount: 15_867
             },
             asset_b: Asset {
                 address: token2.address.clone(),
-                amount: 201
+                amount: 21_155
             },
             asset_lp_share: Asset {
                 address: share_token_address.clone(),
-                amount: 173
+                amount: 18_320
             },
             stake_address: pool_info_after_withdrawal.clone().stake_address,
         }
@@ -899,39 +953,39 @@ fn query_share_valid_liquidity() {
         (
             Asset {
                 address: token1.address.clone(),
-                amount: 151
+                amount: 15_000
             },
             Asset {
                 address: token2.address.clone(),
-                amount: 201
+                amount: 20_000
             }
         )
     );
 
-    // user3 has 173 shares, we are withdrawing 73
-    pool.withdraw_liquidity(&user3, &73, &1i128, &1i128);
+    // user3 has 17_320 shares, we are withdrawing 7_320
+    pool.withdraw_liquidity(&user3, &7_320, &100i128, &100i128, &None);
     let pool_info_after_withdrawal = pool.query_pool_info();
     assert_eq!(
         pool_info_after_withdrawal,
         PoolResponse {
             asset_a: Asset {
                 address: token1.address.clone(),
-                amount: 88
+                amount: 9_528
             },
             asset_b: Asset {
                 address: token2.address.clone(),
-                amount: 117
+                amount: 12_703
             },
             asset_lp_share: Asset {
                 address: share_token_address.clone(),
-                amount: 100
+                amount: 11_000
             },
             stake_address: pool_info_after_withdrawal.clone().stake_address,
         }
     );
 
     let lp_share_balance_after_withdraw_user3: i128 = token_share.balance(&user3);
-    assert_eq!(lp_share_balance_after_withdraw_user3, 100);
+    assert_eq!(lp_share_balance_after_withdraw_user3, 10_000);
 
     let query_share_result_user3 = pool.query_share(&lp_share_balance_after_withdraw_user3);
     assert_eq!(
@@ -939,11 +993,11 @@ fn query_share_valid_liquidity() {
         (
             Asset {
                 address: token1.address.clone(),
-                amount: 88
+                amount: 8_661
             },
             Asset {
                 address: token2.address.clone(),
-                amount: 117
+                amount: 11_548
             }
         )
     );
@@ -1038,6 +1092,7 @@ fn provide_liquidity_slippage_tolerance_too_high() {
         &Some(100),
         &Some(100),
         &Some(10001),
+        &None,
     );
 }
 
@@ -1094,3 +1149,579 @@ fn test_query_info_for_factory_works() {
     );
     assert_eq!(result.total_fee_bps, 0);
 }
+
+#[test]
+#[should_panic(expected = "Pool: Transaction deadline has passed")]
+fn provide_liquidity_should_fail_after_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user1 = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let (token1, token2, pool) =
+        deploy_pool_with_liquidity(&env, 0i64, &fee_recipient, &user1, 10_000, 10_000);
+
+    token1.mint(&user1, &100);
+    token2.mint(&user1, &100);
+
+    env.ledger().with_mut(|li| li.timestamp = 100);
+
+    pool.provide_liquidity(
+        &user1,
+        &Some(100),
+        &None,
+        &Some(100),
+        &None,
+        &None,
+        &Some(99),
+    );
+}
+
+#[test]
+fn withdraw_liquidity_with_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user1 = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let (token1, token2, pool) =
+        deploy_pool_with_liquidity(&env, 0i64, &fee_recipient, &user1, 10_000, 10_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 100);
+
+    // the deadline is still valid at its exact timestamp
+    pool.withdraw_liquidity(&user1, &5_000, &5_000, &5_000, &Some(100));
+    assert_eq!(token1.balance(&user1), 5_000);
+    assert_eq!(token2.balance(&user1), 5_000);
+}
+
+#[test]
+#[should_panic(expected = "Pool: Transaction deadline has passed")]
+fn withdraw_liquidity_should_fail_after_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user1 = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let (_, _, pool) =
+        deploy_pool_with_liquidity(&env, 0i64, &fee_recipient, &user1, 10_000, 10_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 100);
+
+    pool.withdraw_liquidity(&user1, &5_000, &5_000, &5_000, &Some(99));
+}
+
+#[test_case(1, 1 ; "when the attacker deposits dust")]
+#[test_case(1_000, 1_000 ; "when the shares are equal to the minimum liquidity")]
+#[should_panic(expected = "Pool: ProvideLiquidity: Liquidity amount is too low")]
+fn provide_liquidity_first_deposit_below_minimum_liquidity_should_fail(
+    amount_a: i128,
+    amount_b: i128,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let attacker = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+
+    // a first deposit of a single unit of each token would give the attacker the only share of
+    // the pool, making a share expensive enough to round the next deposits down to zero
+    deploy_pool_with_liquidity(&env, 0i64, &fee_recipient, &attacker, amount_a, amount_b);
+}
+
+#[test]
+fn provide_liquidity_donation_attack_should_not_steal_next_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let attacker = Address::generate(&env);
+    let victim = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+
+    // the smallest possible first deposit leaves the attacker with a single share, the other
+    // 1_000 shares are locked in the pool
+    let (token1, token2, pool) =
+        deploy_pool_with_liquidity(&env, 0i64, &fee_recipient, &attacker, 1_001, 1_001);
+    let token_share = token_contract::Client::new(&env, &pool.query_share_token_address());
+    assert_eq!(token_share.balance(&attacker), 1);
+    assert_eq!(token_share.balance(&pool.address), 1_000);
+
+    // the attacker donates tokens straight to the pool to inflate the value of a single share
+    token1.mint(&attacker, &1_000_000);
+    token2.mint(&attacker, &1_000_000);
+    token1.transfer(&attacker, &pool.address, &1_000_000);
+    token2.transfer(&attacker, &pool.address, &1_000_000);
+
+    token1.mint(&victim, &500_000);
+    token2.mint(&victim, &500_000);
+    pool.provide_liquidity(
+        &victim,
+        &Some(500_000),
+        &Some(500_000),
+        &Some(500_000),
+        &Some(500_000),
+        &None,
+        &None,
+    );
+
+    // the victim still gets its shares and can withdraw at least what it has deposited
+    assert_eq!(token_share.balance(&victim), 1_500_000);
+    pool.withdraw_liquidity(&victim, &1_500_000, &500_000, &500_000, &None);
+    assert!(token1.balance(&victim) >= 500_000);
+    assert!(token2.balance(&victim) >= 500_000);
+
+    // while the attacker can't get the donation back
+    pool.withdraw_liquidity(&attacker, &1, &1, &1, &None);
+    assert_eq!(token1.balance(&attacker), 1);
+    assert_eq!(token2.balance(&attacker), 1);
+}
+
+#[test]
+fn sync_adds_donated_tokens_to_reserves() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user1 = Address::generate(&env);
+    let donor = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let (token1, token2, pool) =
+        deploy_pool_with_liquidity(&env, 0i64, &fee_recipient, &user1, 10_000, 10_000);
+
+    token1.mint(&donor, &1_000);
+    token2.mint(&donor, &1_000);
+    token1.transfer(&donor, &pool.address, &1_000);
+    token2.transfer(&donor, &pool.address, &1_000);
+
+    assert_eq!(
+        pool.query_reserve_drift(),
+        (
+            Asset {
+                address: token1.address.clone(),
+                amount: 1_000
+            },
+            Asset {
+                address: token2.address.clone(),
+                amount: 1_000
+            }
+        )
+    );
+
+    pool.sync();
+
+    let result = pool.query_pool_info();
+    assert_eq!(result.asset_a.amount, 11_000);
+    assert_eq!(result.asset_b.amount, 11_000);
+    assert_eq!(pool.query_reserve_drift().0.amount, 0);
+    assert_eq!(pool.query_reserve_drift().1.amount, 0);
+
+    // the donation now belongs to the liquidity providers
+    pool.withdraw_liquidity(&user1, &9_000, &9_900, &9_900, &None);
+    assert_eq!(token1.balance(&user1), 9_900);
+    assert_eq!(token2.balance(&user1), 9_900);
+}
+
+#[test]
+#[should_panic(expected = "Pool: Sync: Pool has no liquidity to sync")]
+fn sync_empty_pool_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let mut token1 = deploy_token_contract(&env, &Address::generate(&env));
+    let mut token2 = deploy_token_contract(&env, &Address::generate(&env));
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        None,
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    token1.mint(&pool.address, &1_000);
+    token2.mint(&pool.address, &1_000);
+
+    pool.sync();
+}
+
+#[test]
+fn skim_sends_excess_tokens_to_recipient() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let mut token1 = deploy_token_contract(&env, &Address::generate(&env));
+    let mut token2 = deploy_token_contract(&env, &Address::generate(&env));
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        Some(admin.clone()),
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    token1.mint(&user1, &10_000);
+    token2.mint(&user1, &10_000);
+    pool.provide_liquidity(
+        &user1,
+        &Some(10_000),
+        &Some(10_000),
+        &Some(10_000),
+        &Some(10_000),
+        &None,
+        &None,
+    );
+
+    // tokens sent by mistake to the pool
+    token1.mint(&user1, &500);
+    token1.transfer(&user1, &pool.address, &500);
+
+    pool.skim(&recipient);
+    assert_eq!(
+        env.auths(),
+        [(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    pool.address.clone(),
+                    Symbol::new(&env, "skim"),
+                    (&recipient,).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        ),]
+    );
+
+    assert_eq!(token1.balance(&recipient), 500);
+    assert_eq!(token2.balance(&recipient), 0);
+    assert_eq!(token1.balance(&pool.address), 10_000);
+    assert_eq!(token2.balance(&pool.address), 10_000);
+
+    let result = pool.query_pool_info();
+    assert_eq!(result.asset_a.amount, 10_000);
+    assert_eq!(result.asset_b.amount, 10_000);
+    assert_eq!(pool.query_reserve_drift().0.amount, 0);
+}
+
+#[test]
+fn provide_liquidity_donation_attack_with_sync_should_not_be_profitable() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let attacker = Address::generate(&env);
+    let victim = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+
+    let (token1, token2, pool) =
+        deploy_pool_with_liquidity(&env, 0i64, &fee_recipient, &attacker, 1_001, 1_001);
+    let token_share = token_contract::Client::new(&env, &pool.query_share_token_address());
+    assert_eq!(token_share.balance(&attacker), 1);
+
+    // the attacker donates tokens and syncs them into the reserves, so a single share is now
+    // worth ~1_000 tokens
+    token1.mint(&attacker, &1_000_000);
+    token2.mint(&attacker, &1_000_000);
+    token1.transfer(&attacker, &pool.address, &1_000_000);
+    token2.transfer(&attacker, &pool.address, &1_000_000);
+    pool.sync();
+
+    token1.mint(&victim, &500_000);
+    token2.mint(&victim, &500_000);
+    pool.provide_liquidity(
+        &victim,
+        &Some(500_000),
+        &Some(500_000),
+        &Some(500_000),
+        &Some(500_000),
+        &None,
+        &None,
+    );
+
+    // without the locked liquidity the victim would get 0 shares and the attacker would take
+    // the whole deposit, now the victim loses only the rounding of a single share
+    assert_eq!(token_share.balance(&victim), 499);
+    pool.withdraw_liquidity(&victim, &499, &499_000, &499_000, &None);
+    assert_eq!(token1.balance(&victim), 499_332);
+    assert_eq!(token2.balance(&victim), 499_332);
+
+    // while most of the donation stays with the locked shares
+    pool.withdraw_liquidity(&attacker, &1, &1, &1, &None);
+    assert_eq!(token1.balance(&attacker), 1_000);
+    assert_eq!(token2.balance(&attacker), 1_000);
+}
+
+#[test]
+fn simulate_provide_liquidity_on_empty_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let mut token1 = deploy_token_contract(&env, &Address::generate(&env));
+    let mut token2 = deploy_token_contract(&env, &Address::generate(&env));
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+    let user = Address::generate(&env);
+
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        None,
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    let simulated = pool.simulate_provide_liquidity(&10_000, &40_000, &None);
+    // the minimum liquidity is locked in the pool on the first deposit
+    assert_eq!(
+        simulated,
+        SimulateProvideLiquidityResponse {
+            asset_a: Asset {
+                address: token1.address.clone(),
+                amount: 10_000,
+            },
+            asset_b: Asset {
+                address: token2.address.clone(),
+                amount: 40_000,
+            },
+            share_amount: 19_000,
+            slippage_bps: 0,
+        }
+    );
+
+    token1.mint(&user, &10_000);
+    token2.mint(&user, &40_000);
+    pool.provide_liquidity(
+        &user,
+        &Some(10_000),
+        &None,
+        &Some(40_000),
+        &None,
+        &None,
+        &None,
+    );
+
+    let token_share = token_contract::Client::new(&env, &pool.query_share_token_address());
+    assert_eq!(token_share.balance(&user), simulated.share_amount);
+}
+
+#[test]
+fn simulate_provide_liquidity_matches_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let (token1, token2, pool) = deploy_pool_with_liquidity(
+        &env,
+        0i64,
+        &Address::generate(&env),
+        &user1,
+        1_000_000,
+        1_000_000,
+    );
+
+    // the desired amounts are 2% off the pool ratio, so only 10_000 of token B is deposited
+    let simulated = pool.simulate_provide_liquidity(&10_000, &10_200, &Some(200));
+    assert_eq!(
+        simulated,
+        SimulateProvideLiquidityResponse {
+            asset_a: Asset {
+                address: token1.address.clone(),
+                amount: 10_000,
+            },
+            asset_b: Asset {
+                address: token2.address.clone(),
+                amount: 10_000,
+            },
+            share_amount: 10_000,
+            slippage_bps: 200,
+        }
+    );
+
+    token1.mint(&user2, &10_000);
+    token2.mint(&user2, &10_200);
+    pool.provide_liquidity(
+        &user2,
+        &Some(10_000),
+        &None,
+        &Some(10_200),
+        &None,
+        &Some(simulated.slippage_bps),
+        &None,
+    );
+
+    let token_share = token_contract::Client::new(&env, &pool.query_share_token_address());
+    assert_eq!(token_share.balance(&user2), simulated.share_amount);
+    assert_eq!(token1.balance(&user2), 0);
+    assert_eq!(token2.balance(&user2), 200);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn provide_liquidity_below_simulated_slippage_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let (token1, token2, pool) = deploy_pool_with_liquidity(
+        &env,
+        0i64,
+        &Address::generate(&env),
+        &user1,
+        1_000_000,
+        1_000_000,
+    );
+
+    let simulated = pool.simulate_provide_liquidity(&10_000, &10_200, &Some(200));
+
+    token1.mint(&user2, &10_000);
+    token2.mint(&user2, &10_200);
+    pool.provide_liquidity(
+        &user2,
+        &Some(10_000),
+        &None,
+        &Some(10_200),
+        &None,
+        &Some(simulated.slippage_bps - 1),
+        &None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn simulate_provide_liquidity_with_default_slippage_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let (_, _, pool) = deploy_pool_with_liquidity(
+        &env,
+        0i64,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        1_000_000,
+        1_000_000,
+    );
+
+    // like `provide_liquidity`, the default slippage tolerance is 1%
+    pool.simulate_provide_liquidity(&10_000, &10_200, &None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn simulate_provide_liquidity_without_tokens_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let (_, _, pool) = deploy_pool_with_liquidity(
+        &env,
+        0i64,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        1_000_000,
+        1_000_000,
+    );
+
+    pool.simulate_provide_liquidity(&0, &0, &None);
+}
+
+#[test]
+fn simulate_single_sided_provide_liquidity_matches_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let (token1, token2, pool) = deploy_pool_with_liquidity(
+        &env,
+        0i64,
+        &Address::generate(&env),
+        &user1,
+        1_000_000,
+        1_000_000,
+    );
+
+    // only token B is deposited, part of it is swapped for token A first
+    let simulated = pool.simulate_provide_liquidity(&0, &10_000, &None);
+    assert!(simulated.asset_a.amount > 0);
+    assert!(simulated.asset_b.amount > 0 && simulated.asset_b.amount < 10_000);
+    assert!(simulated.share_amount > 0);
+
+    token2.mint(&user2, &10_000);
+    pool.provide_liquidity(
+        &user2,
+        &None,
+        &Some(simulated.asset_a.amount),
+        &Some(10_000),
+        &None,
+        &None,
+        &None,
+    );
+
+    let token_share = token_contract::Client::new(&env, &pool.query_share_token_address());
+    assert_eq!(token_share.balance(&user2), simulated.share_amount);
+    assert_eq!(token1.balance(&user2), 0);
+    assert_eq!(token2.balance(&user2), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn simulate_single_sided_provide_liquidity_on_empty_pool_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let mut token1 = deploy_token_contract(&env, &Address::generate(&env));
+    let mut token2 = deploy_token_contract(&env, &Address::generate(&env));
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        None,
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    pool.simulate_provide_liquidity(&10_000, &0, &None);
+}
@@ -0,0 +1,188 @@
+extern crate std;
+use pretty_assertions::assert_eq;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+use super::setup::{deploy_liquidity_pool_contract, deploy_token_contract};
+use crate::{contract::LiquidityPoolClient, token_contract};
+
+fn deploy_pool_at<'a>(
+    env: &Env,
+    timestamp: u64,
+    user: &Address,
+) -> (
+    token_contract::Client<'a>,
+    token_contract::Client<'a>,
+    LiquidityPoolClient<'a>,
+) {
+    let mut token1 = deploy_token_contract(env, &Address::generate(env));
+    let mut token2 = deploy_token_contract(env, &Address::generate(env));
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+
+    let pool = deploy_liquidity_pool_contract(
+        env,
+        None,
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(env),
+        Address::generate(env),
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = timestamp);
+    token1.mint(user, &1_000_000);
+    token2.mint(user, &1_000_000);
+    pool.provide_liquidity(
+        user,
+        &Some(1_000_000),
+        &None,
+        &Some(1_000_000),
+        &None,
+        &None,
+        &None,
+    );
+
+    (token1, token2, pool)
+}
+
+#[test]
+fn query_twap_after_swap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (token1, token2, pool) = deploy_pool_at(&env, 1_000, &user);
+
+    // price was 1:1 for 600 seconds
+    env.ledger().with_mut(|li| li.timestamp = 1_600);
+    assert_eq!(
+        pool.query_twap(&token1.address, &600),
+        1_000_000_000_000_000_000
+    );
+
+    // after the swap the reserves are A(1_100_000), B(909_090)
+    token1.mint(&user, &100_000);
+    pool.swap(&user, &None, &token1.address, &100_000, &None, &None, &None);
+
+    env.ledger().with_mut(|li| li.timestamp = 2_200);
+    // 909_090 / 1_100_000
+    assert_eq!(
+        pool.query_twap(&token1.address, &600),
+        826_445_454_545_454_545
+    );
+    // (1 * 600 + 0.826445454545454545 * 600) / 1_200
+    assert_eq!(
+        pool.query_twap(&token1.address, &1_200),
+        913_222_727_272_727_272
+    );
+    // (1 * 600 + 1.210001210001210001 * 600) / 1_200
+    assert_eq!(
+        pool.query_twap(&token2.address, &1_200),
+        1_105_000_605_000_605_000
+    );
+
+    // there is no observation between 1_000 and 1_300, so the oldest one before the window is used
+    assert_eq!(
+        pool.query_twap(&token1.address, &900),
+        913_222_727_272_727_272
+    );
+}
+
+#[test]
+fn query_twap_is_not_moved_by_swaps_in_the_same_ledger() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (token1, _, pool) = deploy_pool_at(&env, 1_000, &user);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_600);
+    token1.mint(&user, &100_000);
+    pool.swap(&user, &None, &token1.address, &100_000, &None, &None, &None);
+
+    // the price moved, but it has not been in effect for any time yet
+    assert_eq!(
+        pool.query_twap(&token1.address, &600),
+        1_000_000_000_000_000_000
+    );
+}
+
+#[test]
+fn query_twap_after_more_than_a_day_without_swaps() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (token1, _, pool) = deploy_pool_at(&env, 1_000, &user);
+
+    // after the swap the reserves are A(1_100_000), B(909_090)
+    env.ledger().with_mut(|li| li.timestamp = 1_600);
+    token1.mint(&user, &100_000);
+    pool.swap(&user, &None, &token1.address, &100_000, &None, &None, &None);
+
+    // the ring buffer has wrapped around since, but the price has not changed
+    env.ledger()
+        .with_mut(|li| li.timestamp = 1_600 + 2 * 86_400);
+    assert_eq!(
+        pool.query_twap(&token1.address, &600),
+        826_445_454_545_454_545
+    );
+    assert_eq!(
+        pool.query_twap(&token1.address, &86_400),
+        826_445_454_545_454_545
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "Pool: Query TWAP: Not enough price observations for the requested window"
+)]
+fn query_twap_should_fail_when_window_is_older_than_observations() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (token1, _, pool) = deploy_pool_at(&env, 1_000, &user);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_600);
+    pool.query_twap(&token1.address, &601);
+}
+
+#[test]
+#[should_panic(
+    expected = "Pool: Query TWAP: Window must be bigger than 0 and not longer than the observation buffer"
+)]
+fn query_twap_should_fail_with_zero_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (token1, _, pool) = deploy_pool_at(&env, 1_000, &user);
+
+    pool.query_twap(&token1.address, &0);
+}
+
+#[test]
+#[should_panic(expected = "Pool: Query TWAP: Token not found in Pool")]
+fn query_twap_should_fail_for_unknown_asset() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (_, _, pool) = deploy_pool_at(&env, 1_000, &user);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_600);
+    pool.query_twap(&Address::generate(&env), &600);
+}
@@ -1,11 +1,15 @@
 extern crate std;
 use phoenix::utils::{LiquidityPoolInitInfo, StakeInitInfo, TokenInitInfo};
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, String,
+};
 
 use super::setup::{deploy_liquidity_pool_contract, deploy_token_contract};
 use crate::{
     stake_contract,
     storage::{Config, PairType},
+    token_contract,
 };
 
 use crate::contract::{LiquidityPool, LiquidityPoolClient};
@@ -59,6 +63,8 @@ fn confirm_stake_contract_deployment() {
             max_allowed_slippage_bps: 500,
             max_allowed_spread_bps: 200,
             max_referral_bps: 5_000,
+            protocol_fee_share_bps: 10_000,
+            paused: false,
         }
     );
 
@@ -73,6 +79,7 @@ fn confirm_stake_contract_deployment() {
                 manager: stake_manager,
                 owner: stake_owner,
                 max_complexity: 10,
+                paused: false,
             }
         }
     );
@@ -146,3 +153,140 @@ fn second_pool_deployment_should_fail() {
         &String::from_str(&env, "PHOBTC"),
     );
 }
+
+#[test]
+fn provide_and_stake_then_unstake_and_withdraw() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let mut token1 = deploy_token_contract(&env, &Address::generate(&env));
+    let mut token2 = deploy_token_contract(&env, &Address::generate(&env));
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+    let reward_token = deploy_token_contract(&env, &Address::generate(&env));
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let stake_manager = Address::generate(&env);
+
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        None,
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        stake_manager.clone(),
+        Address::generate(&env),
+    );
+    let share_token = token_contract::Client::new(&env, &pool.query_share_token_address());
+    let stake_client = stake_contract::Client::new(&env, &pool.query_stake_contract_address());
+
+    token1.mint(&user1, &10_000);
+    token2.mint(&user1, &10_000);
+    pool.provide_liquidity(
+        &user1,
+        &Some(10_000),
+        &None,
+        &Some(10_000),
+        &None,
+        &None,
+        &None,
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    token1.mint(&user2, &1_000);
+    token2.mint(&user2, &1_000);
+    let staked = pool.provide_and_stake(
+        &user2,
+        &Some(1_000),
+        &None,
+        &Some(1_000),
+        &None,
+        &None,
+        &None,
+    );
+
+    // the shares end up bonded in the stake contract instead of the wallet of the user
+    assert_eq!(staked, 1_000);
+    assert_eq!(share_token.balance(&user2), 0);
+    assert_eq!(token1.balance(&user2), 0);
+    assert_eq!(token2.balance(&user2), 0);
+    assert_eq!(
+        stake_client.query_staked(&user2).stakes,
+        vec![
+            &env,
+            stake_contract::Stake {
+                stake: 1_000,
+                stake_timestamp: 100,
+            }
+        ]
+    );
+
+    // the only staker earns all the distributed rewards
+    stake_client.create_distribution_flow(&stake_manager, &reward_token.address);
+    reward_token.mint(&stake_client.address, &500);
+    stake_client.distribute_rewards();
+    let rewards = stake_client
+        .query_withdrawable_rewards(&user2)
+        .rewards
+        .get(0)
+        .unwrap()
+        .reward_amount;
+    assert!(rewards > 0);
+
+    // the share of the pool is rounded down in favour of the pool
+    let withdrawn = pool.unstake_and_withdraw(&user2, &1_000, &100, &999, &999, &None);
+
+    assert_eq!(withdrawn, (999, 999));
+    assert_eq!(token1.balance(&user2), 999);
+    assert_eq!(token2.balance(&user2), 999);
+    assert_eq!(share_token.balance(&user2), 0);
+    assert_eq!(reward_token.balance(&user2), rewards as i128);
+    assert_eq!(stake_client.query_staked(&user2).stakes, vec![&env]);
+}
+
+#[test]
+#[should_panic(
+    expected = "Pool: WithdrawLiquidity: Minimum amount of token_a or token_b is not satisfied!"
+)]
+fn unstake_and_withdraw_below_min_amounts_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let mut token1 = deploy_token_contract(&env, &Address::generate(&env));
+    let mut token2 = deploy_token_contract(&env, &Address::generate(&env));
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+    let user = Address::generate(&env);
+
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        None,
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    token1.mint(&user, &10_000);
+    token2.mint(&user, &10_000);
+    let staked = pool.provide_and_stake(
+        &user,
+        &Some(10_000),
+        &None,
+        &Some(10_000),
+        &None,
+        &None,
+        &None,
+    );
+
+    pool.unstake_and_withdraw(&user, &staked, &0, &10_000, &10_000, &None);
+}
@@ -2,13 +2,15 @@ extern crate std;
 use pretty_assertions::assert_eq;
 use soroban_sdk::{
     symbol_short,
-    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Ledger},
     Address, Env, IntoVal,
 };
 use test_case::test_case;
 
 use super::setup::{deploy_liquidity_pool_contract, deploy_token_contract};
-use crate::storage::{Asset, PoolResponse, SimulateReverseSwapResponse, SimulateSwapResponse};
+use crate::storage::{
+    Asset, PoolResponse, Referral, SimulateReverseSwapResponse, SimulateSwapResponse,
+};
 use decimal::Decimal;
 
 #[test]
@@ -52,18 +54,19 @@ fn simple_swap() {
         &Some(1_000_000),
         &Some(1_000_000),
         &None,
+        &None,
     );
 
     // selling just one token with 1% max spread allowed
     let spread = 100i64; // 1% maximum spread allowed
     pool.swap(
         &user1,
-        // FIXM: Disable Referral struct
-        // &None::<Referral>,
+        &None,
         &token1.address,
         &1,
         &None,
         &Some(spread),
+        &None,
     );
     assert_eq!(
         env.auths(),
@@ -75,12 +78,12 @@ fn simple_swap() {
                     symbol_short!("swap"),
                     (
                         &user1,
-                        // FIXM: Disable Referral struct
-                        // None::<Referral>,
+                        None::<Referral>,
                         token1.address.clone(),
                         1_i128,
                         None::<i64>,
-                        spread
+                        spread,
+                        None::<u64>
                     )
                         .into_val(&env)
                 )),
@@ -124,12 +127,12 @@ fn simple_swap() {
     // this time 100 units
     let output_amount = pool.swap(
         &user1,
-        // FIXM: Disable Referral struct
-        // &None::<Referral>,
+        &None,
         &token2.address,
         &1_000,
         &None,
         &Some(spread),
+        &None,
     );
     let result = pool.query_pool_info();
     assert_eq!(
@@ -155,8 +158,6 @@ fn simple_swap() {
     assert_eq!(token2.balance(&user1), 1001 - 1000); // user1 sold 1k of token B on second swap
 }
 
-// FIXM: Disable Referral struct
-#[ignore]
 #[test]
 fn simple_swap_with_referral_fee() {
     let env = Env::default();
@@ -199,25 +200,26 @@ fn simple_swap_with_referral_fee() {
         &Some(1_000_000),
         &Some(1_000_000),
         &None,
+        &None,
     );
 
     // selling just one token with 1% max spread allowed
     let spread = 100i64; // 1% maximum spread allowed
 
     // selling with 10% fee for the big guy
-    // FIXM: Disable Referral struct
-    // let referral = Referral {
-    //     address: referral_addr.clone(),
-    //     fee: 1_000,
-    // };
+    let referral = Referral {
+        address: referral_addr.clone(),
+        fee: 1_000,
+    };
 
     pool.swap(
         &user1,
-        //     &Some(referral.clone()),
+        &Some(referral.clone()),
         &token1.address,
         &1,
         &None,
         &Some(spread),
+        &None,
     );
 
     // zero referral fee because amount is too low
@@ -248,12 +250,12 @@ fn simple_swap_with_referral_fee() {
     assert_eq!(token2.balance(&user1), 1001); // 1 from the swap
     let output_amount = pool.swap(
         &user1,
-        // FIXM: Disable Referral struct
-        // &Some(referral),
+        &Some(referral),
         &token2.address,
         &1_000,
         &None,
         &Some(spread),
+        &None,
     );
     let result = pool.query_pool_info();
     assert_eq!(
@@ -275,14 +277,11 @@ fn simple_swap_with_referral_fee() {
         }
     );
     assert_eq!(output_amount, 900);
-    assert_eq!(token1.balance(&user1), 1899); // 999 + 1_000 as a result of swap
-                                              // FIXM: Disable Referral struct
-                                              // assert_eq!(token1.balance(&referral_addr), 100);
+    assert_eq!(token1.balance(&user1), 1899); // 999 + 900 as a result of swap
+    assert_eq!(token1.balance(&referral_addr), 100);
     assert_eq!(token2.balance(&user1), 1001 - 1000); // user1 sold 1k of token B on second swap
 }
 
-// FIXM: Disable Referral struct
-#[ignore]
 #[test]
 #[should_panic(expected = "Pool: Swap: Trying to swap with more than the allowed referral fee")]
 fn test_swap_should_fail_when_referral_fee_is_larger_than_allowed() {
@@ -324,25 +323,26 @@ fn test_swap_should_fail_when_referral_fee_is_larger_than_allowed() {
         &Some(1_000_000),
         &Some(1_000_000),
         &None,
+        &None,
     );
 
     let spread = 100i64; // 1% maximum spread allowed
 
-    // FIXM: Disable Referral struct
-    // let referral = Referral {
-    //     address: Address::random(&env),
-    //     // in tests/setup.rs we hardcoded the max referral fee
-    //     // to 5_000 bps (50%), here we try to set it to 10_000 bps (100%)
-    //     fee: 10_000,
-    // };
+    let referral = Referral {
+        address: Address::generate(&env),
+        // in tests/setup.rs we hardcoded the max referral fee
+        // to 5_000 bps (50%), here we try to set it to 10_000 bps (100%)
+        fee: 10_000,
+    };
 
     pool.swap(
         &user1,
-        //     &Some(referral),
+        &Some(referral),
         &token1.address,
         &1,
         &None,
         &Some(spread),
+        &None,
     );
 }
 
@@ -380,11 +380,18 @@ fn swap_should_panic_with_bad_max_spread() {
 
     token1.mint(&user1, &1_001_000);
     token2.mint(&user1, &2_001_000);
-    pool.provide_liquidity(&user1, &Some(5000), &None, &Some(2_000_000), &None, &None);
+    pool.provide_liquidity(
+        &user1,
+        &Some(5000),
+        &None,
+        &Some(2_000_000),
+        &None,
+        &None,
+        &None,
+    );
 
     // selling just one token with 1% max spread allowed and 50 bps max spread
-    // FIXM: Disable Referral struct
-    pool.swap(&user1, &token1.address, &50, &None, &Some(50));
+    pool.swap(&user1, &None, &token1.address, &50, &None, &Some(50), &None);
 }
 
 #[test]
@@ -431,6 +438,7 @@ fn swap_with_high_fee() {
         &Some(initial_liquidity),
         &Some(initial_liquidity),
         &None,
+        &None,
     );
 
     let spread = 1_000; // 10% maximum spread allowed
@@ -438,12 +446,12 @@ fn swap_with_high_fee() {
     // let's swap 100_000 units of Token 1 in 1:1 pool with 10% protocol fee
     pool.swap(
         &user1,
-        // FIXM: Disable Referral struct
-        // &None,
+        &None,
         &token1.address,
         &100_000,
         &None,
         &Some(spread),
+        &None,
     );
 
     // This is XYK LP with constant product formula
@@ -476,6 +484,101 @@ fn swap_with_high_fee() {
     assert_eq!(token2.balance(&fee_recipient), fees);
 }
 
+#[test]
+fn swap_with_protocol_fee_share() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let mut admin1 = Address::generate(&env);
+    let mut admin2 = Address::generate(&env);
+
+    let mut token1 = deploy_token_contract(&env, &admin1);
+    let mut token2 = deploy_token_contract(&env, &admin2);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+        std::mem::swap(&mut admin1, &mut admin2);
+    }
+    let user1 = Address::generate(&env);
+    let stake_manager = Address::generate(&env);
+    let stake_owner = Address::generate(&env);
+
+    let swap_fees = 1_000i64; // 10% bps
+    let fee_recipient = Address::generate(&env);
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        None,
+        (&token1.address, &token2.address),
+        swap_fees,
+        fee_recipient.clone(),
+        None,
+        None,
+        stake_manager,
+        stake_owner,
+    );
+
+    // only half of the commission goes to the fee recipient
+    pool.update_config(&None, &None, &None, &None, &None, &Some(5_000i64));
+
+    let initial_liquidity = 1_000_000i128;
+
+    token1.mint(&user1, &(initial_liquidity + 100_000));
+    token2.mint(&user1, &initial_liquidity);
+    pool.provide_liquidity(
+        &user1,
+        &Some(initial_liquidity),
+        &Some(initial_liquidity),
+        &Some(initial_liquidity),
+        &Some(initial_liquidity),
+        &None,
+        &None,
+    );
+
+    // Y_new = (100_000 * 1_000_000) / (100_000 + 1_000_000)
+    // Y_new = 90_909.0909
+    let output_amount = 90_910i128; // rounding
+    let fees = Decimal::percent(10) * output_amount;
+    let protocol_fee = Decimal::percent(50) * fees;
+    let lp_fee = fees - protocol_fee;
+
+    let result = pool.simulate_swap(&token1.address, &100_000);
+    assert_eq!(
+        result,
+        SimulateSwapResponse {
+            ask_amount: output_amount - fees,
+            commission_amount: fees,
+            protocol_fee_amount: protocol_fee,
+            lp_fee_amount: lp_fee,
+            spread_amount: 9090,
+            total_return: 100_000,
+        }
+    );
+
+    pool.swap(
+        &user1,
+        &None,
+        &token1.address,
+        &100_000,
+        &None,
+        &Some(1_000),
+        &None,
+    );
+
+    // the liquidity providers part of the commission stays in the pool
+    let result = pool.query_pool_info();
+    assert_eq!(result.asset_a.amount, initial_liquidity + 100_000i128);
+    assert_eq!(
+        result.asset_b.amount,
+        initial_liquidity - output_amount + lp_fee
+    );
+    assert_eq!(
+        token2.balance(&pool.address),
+        initial_liquidity - output_amount + lp_fee
+    );
+    assert_eq!(token2.balance(&user1), output_amount - fees);
+    assert_eq!(token2.balance(&fee_recipient), protocol_fee);
+}
+
 #[test]
 fn swap_simulation_even_pool() {
     let env = Env::default();
@@ -514,6 +617,7 @@ fn swap_simulation_even_pool() {
         &Some(initial_liquidity),
         &Some(initial_liquidity),
         &None,
+        &None,
     );
 
     // let's simulate swap 100_000 units of Token 1 in 1:1 pool with 10% protocol fee
@@ -533,6 +637,8 @@ fn swap_simulation_even_pool() {
             // spread_amount: Decimal::from_ratio(100_000, 1_000_000) * output_amount, // since it's 10% of the pool
             spread_amount: 9090, // rounding error, one less then ^
             commission_amount: fees,
+            protocol_fee_amount: fees,
+            lp_fee_amount: 0,
             total_return: offer_amount,
         }
     );
@@ -561,6 +667,8 @@ fn swap_simulation_even_pool() {
             spread_amount: 9090, // spread amount is basically 10%, since it's basically 10% of the
             // first token
             commission_amount: fees,
+            protocol_fee_amount: fees,
+            lp_fee_amount: 0,
             total_return: offer_amount,
         }
     );
@@ -618,6 +726,7 @@ fn swap_simulation_one_third_pool() {
         &Some(3 * initial_liquidity),
         &Some(3 * initial_liquidity),
         &None,
+        &None,
     );
 
     // let's simulate swap 100_000 units of Token 1 in 1:3 pool with 5% protocol fee
@@ -636,6 +745,8 @@ fn swap_simulation_one_third_pool() {
             ask_amount: output_amount - fees,
             spread_amount: Decimal::from_ratio(offer_amount, 1_000_000) * output_amount, // since it's 10% of the pool
             commission_amount: fees,
+            protocol_fee_amount: fees,
+            lp_fee_amount: 0,
             total_return: 300_000,
         }
     );
@@ -666,6 +777,8 @@ fn swap_simulation_one_third_pool() {
             // spread_amount: Decimal::from_ratio(100_000i128, 3_000_000i128) * output_amount,
             spread_amount: 1074, // rounding error, one less then ^
             commission_amount: fees,
+            protocol_fee_amount: fees,
+            lp_fee_amount: 0,
             total_return: 33_333,
         }
     );
@@ -722,6 +835,7 @@ fn test_swap_fee_variants(swap_fees: i64, commission_fee: i128) {
         &Some(initial_liquidity),
         &Some(initial_liquidity),
         &None,
+        &None,
     );
 
     // simulating a swap with 1_000_000_000 units
@@ -743,6 +857,8 @@ fn test_swap_fee_variants(swap_fees: i64, commission_fee: i128) {
             ask_amount: output_amount - fees,
             spread_amount: 8979975,
             commission_amount: fees,
+            protocol_fee_amount: fees,
+            lp_fee_amount: 0,
             total_return: 1000000000,
         }
     );
@@ -804,16 +920,17 @@ fn test_v_phx_vul_021_should_panic_when_max_spread_invalid_range(max_spread: Opt
         &Some(1_000_000),
         &Some(1_000_000),
         &None,
+        &None,
     );
 
     pool.swap(
         &user1,
-        // FIXM: Disable Referral struct
-        // &None::<Referral>,
+        &None,
         &token1.address,
         &1,
         &None,
         &max_spread,
+        &None,
     );
 }
 
@@ -850,12 +967,12 @@ fn test_v_phx_vul_017_should_panic_when_swapping_non_existing_token_in_pool() {
     // Swap fails because we provide incorrect token as offer token.
     pool.swap(
         &user1,
-        // FIXM: Disable Referral struct
-        // &None::<Referral>,
+        &None,
         &bad_token.address,
         &1,
         &None,
         &Some(100),
+        &None,
     );
 }
 
@@ -889,12 +1006,7 @@ fn test_v_phx_vul_017_should_panic_when_simulating_swap_for_non_existing_token_i
         Address::generate(&env),
     );
     // Simulate swap fails because we provide incorrect token as offer token.
-    pool.simulate_swap(
-        // FIXM: Disable Referral struct
-        // &None::<Referral>,
-        &bad_token.address,
-        &1,
-    );
+    pool.simulate_swap(&bad_token.address, &1);
 }
 
 #[test]
@@ -927,12 +1039,7 @@ fn test_v_phx_vul_017_should_panic_when_simulating_reverse_swap_for_non_existing
         Address::generate(&env),
     );
     // Simulate swap fails because we provide incorrect token as offer token.
-    pool.simulate_reverse_swap(
-        // FIXM: Disable Referral struct
-        // &None::<Referral>,
-        &bad_token.address,
-        &1,
-    );
+    pool.simulate_reverse_swap(&bad_token.address, &1);
 }
 
 #[test]
@@ -971,18 +1078,29 @@ fn test_should_swap_with_valid_ask_asset_min_amount() {
         &Some(1_000_000),
         &Some(1_000_000),
         &None,
+        &None,
     );
 
-    pool.swap(&user, &token1.address, &10, &Some(10), &None::<i64>);
+    pool.swap(
+        &user,
+        &None,
+        &token1.address,
+        &10,
+        &Some(10),
+        &None::<i64>,
+        &None,
+    );
     assert_eq!(token1.balance(&user), 49_990);
     assert_eq!(token2.balance(&user), 50_010);
 
     pool.swap(
         &user,
+        &None,
         &token2.address,
         &5_000i128,
         &Some(4_900i128),
         &Some(500i64),
+        &None,
     );
 
     assert_eq!(token1.balance(&user), 54_966);
@@ -1027,8 +1145,306 @@ fn test_should_fail_when_invalid_ask_asset_min_amount() {
         &Some(1_000_000),
         &Some(1_000_000),
         &None,
+        &None,
     );
 
     let spread = 100i64; // 1% maximum spread allowed
-    pool.swap(&user, &token1.address, &1, &Some(10), &Some(spread));
+    pool.swap(
+        &user,
+        &None,
+        &token1.address,
+        &1,
+        &Some(10),
+        &Some(spread),
+        &None,
+    );
+}
+
+#[test]
+fn swap_exact_out_returns_exact_ask_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let mut token1 = deploy_token_contract(&env, &Address::generate(&env));
+    let mut token2 = deploy_token_contract(&env, &Address::generate(&env));
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+    let user = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+
+    let swap_fees = 1_000i64; // 10% bps
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        None,
+        (&token1.address, &token2.address),
+        swap_fees,
+        fee_recipient.clone(),
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    token1.mint(&user, &1_002_000);
+    token2.mint(&user, &1_000_000);
+    pool.provide_liquidity(
+        &user,
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &None,
+        &None,
+    );
+
+    // 1_000 / (1 - 10%) = 1_112 of token2 has to leave the pool before the commission,
+    // which costs 1_113 of token1
+    assert_eq!(pool.simulate_swap_exact_out(&token2.address, &1_000), 1_113);
+    let offer_amount = pool.swap_exact_out(&user, &token2.address, &1_000, &1_113, &None, &None);
+    assert_eq!(offer_amount, 1_113);
+
+    assert_eq!(token1.balance(&user), 2_000 - 1_113);
+    assert_eq!(token2.balance(&user), 1_000);
+    assert_eq!(token2.balance(&fee_recipient), 111);
+
+    // the rounding leftover stays in the pool
+    let result = pool.query_pool_info();
+    assert_eq!(result.asset_a.amount, 1_001_113);
+    assert_eq!(result.asset_b.amount, 1_000_000 - 1_000 - 111);
+    assert_eq!(token1.balance(&pool.address), 1_001_113);
+    assert_eq!(token2.balance(&pool.address), 998_889);
+}
+
+#[test]
+fn swap_exact_out_in_both_directions() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let mut token1 = deploy_token_contract(&env, &Address::generate(&env));
+    let mut token2 = deploy_token_contract(&env, &Address::generate(&env));
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+    let user = Address::generate(&env);
+
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        None,
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    token1.mint(&user, &1_010_000);
+    token2.mint(&user, &1_000_000);
+    pool.provide_liquidity(
+        &user,
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &None,
+        &None,
+    );
+
+    // 1_000_000 * 1_000_000 / 1_001_000 = 999_000.999, which is rounded down by the pool
+    let offer_amount = pool.swap_exact_out(&user, &token2.address, &1_000, &10_000, &None, &None);
+    assert_eq!(offer_amount, 1_000);
+    assert_eq!(token1.balance(&user), 9_000);
+    assert_eq!(token2.balance(&user), 1_000);
+
+    let offer_amount = pool.swap_exact_out(&user, &token1.address, &500, &10_000, &None, &None);
+    let simulated = pool.simulate_swap(&token2.address, &offer_amount);
+    assert!(simulated.ask_amount >= 500);
+    assert_eq!(token1.balance(&user), 9_500);
+    assert_eq!(token2.balance(&user), 1_000 - offer_amount);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #24)")]
+fn swap_exact_out_should_fail_when_offer_amount_is_bigger_than_max() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let mut token1 = deploy_token_contract(&env, &Address::generate(&env));
+    let mut token2 = deploy_token_contract(&env, &Address::generate(&env));
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+    let user = Address::generate(&env);
+
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        None,
+        (&token1.address, &token2.address),
+        1_000i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    token1.mint(&user, &1_002_000);
+    token2.mint(&user, &1_000_000);
+    pool.provide_liquidity(
+        &user,
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &None,
+        &None,
+    );
+
+    pool.swap_exact_out(&user, &token2.address, &1_000, &1_112, &None, &None);
+}
+
+#[test]
+#[should_panic(
+    expected = "Pool: compute_exact_out_offer_amount: Not enough liquidity to return the asked amount"
+)]
+fn swap_exact_out_should_fail_when_ask_amount_exceeds_reserves() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let mut token1 = deploy_token_contract(&env, &Address::generate(&env));
+    let mut token2 = deploy_token_contract(&env, &Address::generate(&env));
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+    let user = Address::generate(&env);
+
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        None,
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    token1.mint(&user, &1_000_000);
+    token2.mint(&user, &1_000_000);
+    pool.provide_liquidity(
+        &user,
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &None,
+        &None,
+    );
+
+    pool.swap_exact_out(&user, &token2.address, &1_000_000, &i128::MAX, &None, &None);
+}
+
+#[test]
+#[should_panic(expected = "Pool: Transaction deadline has passed")]
+fn swap_should_fail_after_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let mut token1 = deploy_token_contract(&env, &Address::generate(&env));
+    let mut token2 = deploy_token_contract(&env, &Address::generate(&env));
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+    let user = Address::generate(&env);
+
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        None,
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    token1.mint(&user, &1_001_000);
+    token2.mint(&user, &1_000_000);
+    pool.provide_liquidity(
+        &user,
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &None,
+        &None,
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 100);
+
+    // the deadline itself is still valid
+    pool.swap(
+        &user,
+        &None,
+        &token1.address,
+        &500,
+        &None,
+        &None,
+        &Some(100),
+    );
+    assert_eq!(token1.balance(&user), 500);
+
+    pool.swap(&user, &None, &token1.address, &500, &None, &None, &Some(99));
+}
+
+#[test]
+#[should_panic(expected = "Pool: Transaction deadline has passed")]
+fn swap_exact_out_should_fail_after_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let mut token1 = deploy_token_contract(&env, &Address::generate(&env));
+    let mut token2 = deploy_token_contract(&env, &Address::generate(&env));
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+    let user = Address::generate(&env);
+
+    let pool = deploy_liquidity_pool_contract(
+        &env,
+        None,
+        (&token1.address, &token2.address),
+        0i64,
+        None,
+        None,
+        None,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    token1.mint(&user, &1_001_000);
+    token2.mint(&user, &1_000_000);
+    pool.provide_liquidity(
+        &user,
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &Some(1_000_000),
+        &None,
+        &None,
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 100);
+
+    pool.swap_exact_out(&user, &token2.address, &500, &1_000, &None, &Some(50));
 }
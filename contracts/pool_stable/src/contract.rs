@@ -1,19 +1,25 @@
-use phoenix::utils::LiquidityPoolInitInfo;
+use phoenix::utils::{AdminChange, StableLiquidityPoolInitInfo};
 use soroban_sdk::{
     contract, contractimpl, contractmeta, log, panic_with_error, Address, BytesN, Env, IntoVal,
-    String,
+    String, Vec,
 };
 
 use crate::error::ContractError;
 use crate::storage::utils::{is_initialized, set_initialized};
 use crate::storage::StableLiquidityPoolInfo;
 use crate::{
-    math::{calc_y, compute_current_amp, compute_d, AMP_PRECISION},
+    math::{
+        calc_y, calc_y_for_d, compute_current_amp, compute_d, mul_div, mul_div_ceil, mul_rate,
+        ratio, AMP_PRECISION, DECIMAL_PRECISION, MAX_AMP, MAX_AMP_CHANGE, MAX_COINS,
+        MIN_AMP_CHANGING_TIME, MIN_COINS,
+    },
     stake_contract,
     storage::{
-        get_amp, get_config, get_greatest_precision, save_amp, save_config,
-        save_greatest_precision, utils, validate_fee_bps, AmplifierParameters, Asset, Config,
-        PairType, PoolResponse, SimulateReverseSwapResponse, SimulateSwapResponse,
+        get_amp, get_config, get_greatest_precision, get_precisions, save_amp, save_config,
+        save_greatest_precision, utils, validate_fee_bps, AmpResponse, AmplifierParameters, Asset,
+        Config, PairType, PoolResponse, SimulateProvideLiquidityResponse,
+        SimulateReverseSwapResponse, SimulateSwapResponse, SimulateWithdrawImbalancedResponse,
+        SimulateWithdrawOneCoinResponse,
     },
     token_contract,
 };
@@ -41,54 +47,99 @@ pub trait StableLiquidityPoolTrait {
         stake_wasm_hash: BytesN<32>,
         token_wasm_hash: BytesN<32>,
         amp: u64,
-        lp_init_info: LiquidityPoolInitInfo,
+        lp_init_info: StableLiquidityPoolInitInfo,
         factory_addr: Address,
         share_token_decimals: u32,
         share_token_name: String,
         share_token_symbol: String,
     );
 
-    // Deposits token_a and token_b. Also mints pool shares for the "to" Identifier. The amount minted
-    // is determined based on the difference between the reserves stored by this contract, and
-    // the actual balance of token_a and token_b for this contract.
+    // Deposits `desired_amounts` of the pool tokens, in the order of the pool tokens. Also mints pool
+    // shares for the depositor. The amount minted is determined by the increase of the invariant.
+    // The first deposit has to include every token, later deposits may leave some of them out.
+    // The part of the deposit value lost because the deposit is imbalanced must not exceed
+    // `custom_slippage_bps`, or the max allowed slippage of the pool when it is not set.
     fn provide_liquidity(
         env: Env,
         depositor: Address,
-        desired_a: i128,
-        desired_b: i128,
+        desired_amounts: Vec<i128>,
         custom_slippage_bps: Option<i64>,
+        deadline: Option<u64>,
     );
 
-    // `offer_asset` is the asset that the user would like to swap for the other token in the pool.
+    // `offer_asset` is the asset that the user would like to swap for `ask_asset`, any other token in the pool.
     // `offer_amount` is the amount being sold, with `max_spread_bps` being a safety to make sure you receive at least that amount.
     // swap will transfer the selling token "to" to this contract, and then the contract will transfer the buying token to `sender`.
+    // `deadline` is an optional ledger timestamp after which the swap is rejected.
     // Returns the amount of the token being bought.
+    #[allow(clippy::too_many_arguments)]
     fn swap(
         env: Env,
         sender: Address,
         offer_asset: Address,
+        ask_asset: Address,
         offer_amount: i128,
         ask_asset_min_amount: Option<i128>,
         max_spread_bps: Option<i64>,
+        deadline: Option<u64>,
+    ) -> i128;
+
+    // `ask_asset` is the asset that the user would like to receive from the pool in exchange for
+    // `offer_asset`. `ask_amount` is the exact amount that will be transferred to `sender`, with
+    // `max_offer_amount` being a safety to make sure you don't pay more than that amount of `offer_asset`.
+    // Returns the amount of the token being sold.
+    #[allow(clippy::too_many_arguments)]
+    fn swap_exact_out(
+        env: Env,
+        sender: Address,
+        offer_asset: Address,
+        ask_asset: Address,
+        ask_amount: i128,
+        max_offer_amount: i128,
+        max_spread_bps: Option<i64>,
+        deadline: Option<u64>,
     ) -> i128;
 
     // transfers share_amount of pool share tokens to this contract, burns all pools share tokens in this contracts, and sends the
-    // corresponding amount of token_a and token_b to "to".
-    // Returns amount of both tokens withdrawn
+    // corresponding amount of every pool token to "to". `min_amounts` are given in the order of the pool tokens.
+    // Returns the amounts of the tokens withdrawn
     fn withdraw_liquidity(
         env: Env,
         recipient: Address,
         share_amount: i128,
-        min_a: i128,
-        min_b: i128,
-    ) -> (i128, i128);
+        min_amounts: Vec<i128>,
+        deadline: Option<u64>,
+    ) -> Vec<i128>;
+
+    // Burns share_amount of pool share tokens and sends the whole withdrawal to the sender in
+    // `asset`. A fee is charged on the part of the withdrawal that is not proportional to the pool
+    // Returns the amount of `asset` withdrawn
+    fn withdraw_one_coin(
+        env: Env,
+        sender: Address,
+        share_amount: i128,
+        asset: Address,
+        min_amount: i128,
+        deadline: Option<u64>,
+    ) -> i128;
+
+    // Sends `amounts` of the pool tokens to the sender and burns the pool share tokens needed
+    // for them, which must not exceed `max_shares`. A fee is charged on the part of the withdrawal
+    // that is not proportional to the pool
+    // Returns the amount of pool share tokens burned
+    fn withdraw_imbalanced(
+        env: Env,
+        sender: Address,
+        amounts: Vec<i128>,
+        max_shares: i128,
+        deadline: Option<u64>,
+    ) -> i128;
 
     // Allows admin address set during initialization to change some parameters of the
     // configuration
     fn update_config(
         env: Env,
         sender: Address,
-        new_admin: Option<Address>,
         total_fee_bps: Option<i64>,
         fee_recipient: Option<Address>,
         max_allowed_slippage_bps: Option<i64>,
@@ -98,6 +149,37 @@ pub trait StableLiquidityPoolTrait {
     // Migration entrypoint
     fn upgrade(e: Env, new_wasm_hash: BytesN<32>);
 
+    // Sets the stored reserves to the actual token balances of the pool, so tokens sent directly
+    // to the pool are added to the liquidity
+    fn sync(env: Env);
+
+    // Allows admin to transfer the tokens held by the pool on top of the stored reserves to `to`
+    fn skim(env: Env, to: Address);
+
+    // Allows the admin or the factory to stop swaps and deposits. Withdrawals are still allowed
+    // while the pool is paused
+    fn pause(env: Env, sender: Address);
+
+    // Allows the admin or the factory to resume swaps and deposits
+    fn unpause(env: Env, sender: Address);
+
+    // Allows the admin to propose `new_admin` as the next admin. The proposal has to be accepted
+    // by `new_admin` before the ledger timestamp passes `expires_at`
+    fn propose_admin(env: Env, sender: Address, new_admin: Address, expires_at: u64);
+
+    // Allows the proposed admin to accept the admin role
+    fn accept_admin(env: Env, sender: Address);
+
+    // Allows the admin to cancel the pending admin proposal
+    fn cancel_admin_proposal(env: Env, sender: Address);
+
+    // Allows admin to gradually change the amplification coefficient to `next_amp`, reaching it
+    // at the `next_amp_time` ledger timestamp
+    fn ramp_amp(env: Env, next_amp: u64, next_amp_time: u64);
+
+    // Allows admin to stop the ongoing amplification change at its current value
+    fn stop_ramp_amp(env: Env);
+
     // QUERIES
 
     // Returns the configuration structure containing the addresses
@@ -115,16 +197,57 @@ pub trait StableLiquidityPoolTrait {
     fn query_pool_info_for_factory(env: Env) -> StableLiquidityPoolInfo;
 
     // Simulate swap transaction
-    fn simulate_swap(env: Env, offer_asset: Address, sell_amount: i128) -> SimulateSwapResponse;
+    fn simulate_swap(
+        env: Env,
+        offer_asset: Address,
+        ask_asset: Address,
+        sell_amount: i128,
+    ) -> SimulateSwapResponse;
 
     // Simulate reverse swap transaction
     fn simulate_reverse_swap(
         env: Env,
         offer_asset: Address,
+        ask_asset: Address,
         ask_amount: i128,
     ) -> SimulateReverseSwapResponse;
 
-    fn query_share(env: Env, amount: i128) -> (Asset, Asset);
+    // Returns the amount of `offer_asset` that `swap_exact_out` takes to return exactly
+    // `ask_amount` of `ask_asset`
+    fn simulate_swap_exact_out(
+        env: Env,
+        offer_asset: Address,
+        ask_asset: Address,
+        ask_amount: i128,
+    ) -> i128;
+
+    // Simulate providing `desired_amounts` of the pool tokens as liquidity. Returns the deposited
+    // amounts, the pool shares minted to the depositor and the slippage of the deposit
+    fn simulate_provide_liquidity(
+        env: Env,
+        desired_amounts: Vec<i128>,
+    ) -> SimulateProvideLiquidityResponse;
+
+    // Simulate withdrawing share_amount of pool share tokens in a single asset
+    fn simulate_withdraw_one_coin(
+        env: Env,
+        share_amount: i128,
+        asset: Address,
+    ) -> SimulateWithdrawOneCoinResponse;
+
+    // Simulate withdrawing `amounts` of the pool tokens
+    fn simulate_withdraw_imbalanced(
+        env: Env,
+        amounts: Vec<i128>,
+    ) -> SimulateWithdrawImbalancedResponse;
+
+    fn query_share(env: Env, amount: i128) -> Vec<Asset>;
+
+    // Returns the difference between the actual token balances of the pool and the stored reserves
+    fn query_reserve_drift(env: Env) -> Vec<Asset>;
+
+    // Returns the amplification parameters together with the current amplification coefficient
+    fn query_amp(env: Env) -> AmpResponse;
 }
 
 #[contractimpl]
@@ -135,7 +258,7 @@ impl StableLiquidityPoolTrait for StableLiquidityPool {
         stake_wasm_hash: BytesN<32>,
         token_wasm_hash: BytesN<32>,
         amp: u64,
-        lp_init_info: LiquidityPoolInitInfo,
+        lp_init_info: StableLiquidityPoolInitInfo,
         factory_addr: Address,
         share_token_decimals: u32,
         share_token_name: String,
@@ -154,7 +277,7 @@ impl StableLiquidityPoolTrait for StableLiquidityPool {
         let fee_recipient = lp_init_info.fee_recipient;
         let max_allowed_slippage_bps = lp_init_info.max_allowed_slippage_bps;
         let max_allowed_spread_bps = lp_init_info.max_allowed_spread_bps;
-        let token_init_info = lp_init_info.token_init_info;
+        let tokens = lp_init_info.tokens;
         let stake_init_info = lp_init_info.stake_init_info;
 
         validate_bps!(
@@ -164,24 +287,38 @@ impl StableLiquidityPoolTrait for StableLiquidityPool {
         );
         set_initialized(&env);
 
-        // Token info
-        let token_a = token_init_info.token_a;
-        let token_b = token_init_info.token_b;
         // Contract info
         let min_bond = stake_init_info.min_bond;
         let min_reward = stake_init_info.min_reward;
         let manager = stake_init_info.manager;
 
-        // Token order validation to make sure only one instance of a pool can exist
-        if token_a >= token_b {
+        if !(MIN_COINS..=MAX_COINS).contains(&(tokens.len() as usize)) {
             log!(
                 &env,
-                "Pool Stable: Initialize: First token must be alphabetically smaller than second token"
+                "Pool Stable: Initialize: A stable pool must have between MIN_COINS and MAX_COINS tokens"
             );
-            panic_with_error!(&env, ContractError::TokenABiggerThanTokenB);
+            panic_with_error!(&env, ContractError::InvalidNumberOfTokens);
+        }
+
+        // Token order validation to make sure only one instance of a pool can exist
+        for i in 1..tokens.len() {
+            if tokens.get(i - 1).unwrap() >= tokens.get(i).unwrap() {
+                log!(
+                    &env,
+                    "Pool Stable: Initialize: Tokens must be sorted alphabetically and must not repeat"
+                );
+                panic_with_error!(&env, ContractError::TokenABiggerThanTokenB);
+            }
         }
 
-        save_greatest_precision(&env, &token_a, &token_b);
+        save_greatest_precision(&env, &tokens);
+        if get_greatest_precision(&env) > DECIMAL_PRECISION as i32 {
+            log!(
+                &env,
+                "Pool Stable: Initialize: Tokens can have at most DECIMAL_PRECISION decimals"
+            );
+            panic_with_error!(&env, ContractError::InvalidTokenDecimals);
+        }
 
         if !(0..=10_000).contains(&swap_fee_bps) {
             log!(
@@ -192,8 +329,7 @@ impl StableLiquidityPoolTrait for StableLiquidityPool {
         }
 
         // deploy token contract
-        let share_token_address =
-            utils::deploy_token_contract(&env, token_wasm_hash, &token_a, &token_b);
+        let share_token_address = utils::deploy_token_contract(&env, token_wasm_hash, &tokens);
         token_contract::Client::new(&env, &share_token_address).initialize(
             // admin
             &env.current_contract_address(),
@@ -216,16 +352,21 @@ impl StableLiquidityPoolTrait for StableLiquidityPool {
             &stake_init_info.max_complexity,
         );
 
+        let mut balances = Vec::new(&env);
+        for _ in tokens.iter() {
+            balances.push_back(0i128);
+        }
+
         let config = Config {
-            token_a: token_a.clone(),
-            token_b: token_b.clone(),
+            tokens: tokens.clone(),
             share_token: share_token_address,
             stake_contract: stake_contract_address,
-            pool_type: PairType::Xyk,
+            pool_type: PairType::Stable,
             total_fee_bps: validate_fee_bps(&env, swap_fee_bps),
             fee_recipient,
             max_allowed_slippage_bps,
             max_allowed_spread_bps,
+            paused: false,
         };
         save_config(&env, config);
         let current_time = env.ledger().timestamp();
@@ -239,32 +380,28 @@ impl StableLiquidityPoolTrait for StableLiquidityPool {
             },
         );
         utils::save_admin(&env, admin);
+        utils::save_factory(&env, factory_addr);
         utils::save_total_shares(&env, 0);
-        utils::save_pool_balance_a(&env, 0);
-        utils::save_pool_balance_b(&env, 0);
+        utils::save_pool_balances(&env, &balances);
 
         env.events()
-            .publish(("initialize", "XYK LP token_a"), token_a);
-        env.events()
-            .publish(("initialize", "XYK LP token_b"), token_b);
+            .publish(("initialize", "Stable LP tokens"), tokens);
     }
 
     fn provide_liquidity(
         env: Env,
         sender: Address,
-        desired_a: i128,
-        desired_b: i128,
+        desired_amounts: Vec<i128>,
         custom_slippage_bps: Option<i64>,
+        deadline: Option<u64>,
     ) {
-        validate_int_parameters!(desired_a, desired_b);
+        assert_deadline(&env, deadline);
+        assert_not_paused(&env);
 
         // sender needs to authorize the deposit
         sender.require_auth();
 
         let config = get_config(&env);
-        let greatest_precision = get_greatest_precision(&env);
-        let old_balance_a = utils::get_pool_balance_a(&env);
-        let old_balance_b = utils::get_pool_balance_b(&env);
 
         // Check if custom_slippage_bps is more than max_allowed_slippage
         if let Some(custom_slippage) = custom_slippage_bps {
@@ -277,85 +414,59 @@ impl StableLiquidityPoolTrait for StableLiquidityPool {
             }
         }
 
-        let amp_parameters = get_amp(&env).unwrap(); // FIXME: This is minor, but add some
-                                                     // validation to AMP parameters
-        let amp = compute_current_amp(&env, &amp_parameters);
-
-        // Invariant (D) after deposit added
-        let new_balance_a = desired_a + old_balance_a;
-        let new_balance_b = desired_b + old_balance_b;
-        let new_invariant = compute_d(
-            &env,
-            amp as u128,
-            &[
-                Decimal::from_atomics(new_balance_a, 6),
-                Decimal::from_atomics(new_balance_b, 6),
-            ],
-        );
-
-        let total_shares = utils::get_total_shares(&env);
-        let shares = if total_shares == 0 {
-            let share =
-                new_invariant.to_i128_with_precision(greatest_precision) - MINIMUM_LIQUIDITY_AMOUNT;
-            if share == 0 {
-                log!(
-                    &env,
-                    "Pool Stable: ProvideLiquidity: Liquidity amount is too low"
-                );
-                panic_with_error!(&env, ContractError::LowLiquidity);
-            }
-            share
-        } else {
-            let initial_invariant = compute_d(
+        let (shares, slippage) = compute_deposit_shares(&env, &config, &desired_amounts);
+        let slippage_tolerance =
+            Decimal::bps(custom_slippage_bps.unwrap_or(config.max_allowed_slippage_bps));
+        if slippage > slippage_tolerance {
+            log!(
                 &env,
-                amp as u128,
-                &[
-                    Decimal::from_atomics(old_balance_a, 6),
-                    Decimal::from_atomics(old_balance_b, 6),
-                ],
+                "Pool Stable: ProvideLiquidity: Slippage of the deposit is more than the slippage tolerance"
             );
-            // Calculate the proportion of the change in invariant
-            (Decimal::from_ratio((new_invariant - initial_invariant) * total_shares, 1)
-                / initial_invariant)
-                .to_i128_with_precision(greatest_precision)
-        };
-
-        let token_a_client = token_contract::Client::new(&env, &config.token_a);
-        let token_b_client = token_contract::Client::new(&env, &config.token_b);
+            panic_with_error!(env, ContractError::ProvideLiquiditySlippageExceeded);
+        }
 
         // Move tokens from client's wallet to the contract
-        token_a_client.transfer(&sender, &env.current_contract_address(), &(desired_a));
-        token_b_client.transfer(&sender, &env.current_contract_address(), &(desired_b));
+        for (token, desired_amount) in config.tokens.iter().zip(desired_amounts.iter()) {
+            if desired_amount > 0 {
+                token_contract::Client::new(&env, &token).transfer(
+                    &sender,
+                    &env.current_contract_address(),
+                    &desired_amount,
+                );
+            }
+        }
 
         // Now calculate how many new pool shares to mint
-        let balance_a = utils::get_balance(&env, &config.token_a);
-        let balance_b = utils::get_balance(&env, &config.token_b);
+        let mut balances = Vec::new(&env);
+        for token in config.tokens.iter() {
+            balances.push_back(utils::get_balance(&env, &token));
+        }
 
         utils::mint_shares(&env, &config.share_token, &sender, shares);
-        utils::save_pool_balance_a(&env, balance_a);
-        utils::save_pool_balance_b(&env, balance_b);
+        utils::save_pool_balances(&env, &balances);
 
         env.events()
             .publish(("provide_liquidity", "sender"), sender);
         env.events()
-            .publish(("provide_liquidity", "token_a"), &config.token_a);
+            .publish(("provide_liquidity", "tokens"), config.tokens);
         env.events()
-            .publish(("provide_liquidity", "token_a-amount"), desired_a);
-        env.events()
-            .publish(("provide_liquidity", "token_b"), &config.token_b);
-        env.events()
-            .publish(("provide_liquidity", "token_b-amount"), desired_b);
+            .publish(("provide_liquidity", "amounts"), desired_amounts);
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn swap(
         env: Env,
         sender: Address,
         offer_asset: Address,
+        ask_asset: Address,
         offer_amount: i128,
         ask_asset_min_amount: Option<i128>,
         max_spread_bps: Option<i64>,
+        deadline: Option<u64>,
     ) -> i128 {
         validate_int_parameters!(offer_amount);
+        assert_deadline(&env, deadline);
+        assert_not_paused(&env);
 
         sender.require_auth();
 
@@ -363,30 +474,70 @@ impl StableLiquidityPoolTrait for StableLiquidityPool {
             env,
             sender,
             offer_asset,
+            ask_asset,
             offer_amount,
             ask_asset_min_amount,
             max_spread_bps,
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn swap_exact_out(
+        env: Env,
+        sender: Address,
+        offer_asset: Address,
+        ask_asset: Address,
+        ask_amount: i128,
+        max_offer_amount: i128,
+        max_spread_bps: Option<i64>,
+        deadline: Option<u64>,
+    ) -> i128 {
+        validate_int_parameters!(ask_amount, max_offer_amount);
+        assert_deadline(&env, deadline);
+        assert_not_paused(&env);
+
+        sender.require_auth();
+
+        do_swap_exact_out(
+            env,
+            sender,
+            offer_asset,
+            ask_asset,
+            ask_amount,
+            max_offer_amount,
+            max_spread_bps,
+        )
+    }
+
     fn withdraw_liquidity(
         env: Env,
         sender: Address,
         share_amount: i128,
-        min_a: i128,
-        min_b: i128,
-    ) -> (i128, i128) {
-        validate_int_parameters!(share_amount, min_a, min_b);
+        min_amounts: Vec<i128>,
+        deadline: Option<u64>,
+    ) -> Vec<i128> {
+        validate_int_parameters!(share_amount);
+        for min_amount in min_amounts.iter() {
+            validate_int_parameters!(min_amount);
+        }
+        assert_deadline(&env, deadline);
 
         sender.require_auth();
 
         let config = get_config(&env);
 
+        if min_amounts.len() != config.tokens.len() {
+            log!(
+                &env,
+                "Pool Stable: WithdrawLiquidity: A minimum amount must be given for each token"
+            );
+            panic_with_error!(env, ContractError::InvalidWithdrawAmounts);
+        }
+
         let share_token_client = token_contract::Client::new(&env, &config.share_token);
         share_token_client.transfer(&sender, &env.current_contract_address(), &share_amount);
 
-        let pool_balance_a = utils::get_pool_balance_a(&env);
-        let pool_balance_b = utils::get_pool_balance_b(&env);
+        let pool_balances = utils::get_pool_balances(&env);
 
         let total_shares = utils::get_total_shares(&env);
 
@@ -395,64 +546,176 @@ impl StableLiquidityPoolTrait for StableLiquidityPool {
             panic_with_error!(env, ContractError::TotalSharesEqualZero);
         }
 
-        let share_ratio = Decimal::from_ratio(share_amount, total_shares);
+        let mut return_amounts = Vec::new(&env);
+        for (pool_balance, min_amount) in pool_balances.iter().zip(min_amounts.iter()) {
+            let return_amount = mul_div(
+                &env,
+                pool_balance as u128,
+                share_amount as u128,
+                total_shares as u128,
+            ) as i128;
+            if return_amount < min_amount {
+                log!(
+                    &env,
+                    "Pool Stable: Minimum amount of a token is not satisfied!"
+                );
+                panic_with_error!(
+                    env,
+                    ContractError::WithdrawLiquidityMinimumAmountOfAOrBIsNotSatisfied
+                );
+            }
+            return_amounts.push_back(return_amount);
+        }
+
+        // burn shares
+        utils::burn_shares(&env, &config.share_token, share_amount);
+        // transfer tokens from contract to sender and update pool balances
+        let mut new_balances = Vec::new(&env);
+        for ((token, pool_balance), return_amount) in config
+            .tokens
+            .iter()
+            .zip(pool_balances.iter())
+            .zip(return_amounts.iter())
+        {
+            token_contract::Client::new(&env, &token).transfer(
+                &env.current_contract_address(),
+                &sender,
+                &return_amount,
+            );
+            new_balances.push_back(pool_balance - return_amount);
+        }
+        utils::save_pool_balances(&env, &new_balances);
+
+        env.events()
+            .publish(("withdraw_liquidity", "sender"), sender);
+        env.events()
+            .publish(("withdraw_liquidity", "shares_amount"), share_amount);
+        env.events().publish(
+            ("withdraw_liquidity", "return_amounts"),
+            return_amounts.clone(),
+        );
+
+        return_amounts
+    }
+
+    fn withdraw_one_coin(
+        env: Env,
+        sender: Address,
+        share_amount: i128,
+        asset: Address,
+        min_amount: i128,
+        deadline: Option<u64>,
+    ) -> i128 {
+        validate_int_parameters!(share_amount, min_amount);
+        assert_deadline(&env, deadline);
+
+        sender.require_auth();
 
-        let return_amount_a = pool_balance_a * share_ratio;
-        let return_amount_b = pool_balance_b * share_ratio;
+        let config = get_config(&env);
+        let (return_amount, fee_amount) = compute_withdraw_one_coin(&env, share_amount, &asset);
 
-        if return_amount_a < min_a || return_amount_b < min_b {
+        if return_amount < min_amount {
             log!(
                 &env,
-                "Pool Stable: Minimum amount of token_a or token_b is not satisfied! min_a: {}, min_b: {}, return_amount_a: {}, return_amount_b: {}",
-                min_a,
-                min_b,
-                return_amount_a,
-                return_amount_b
-            );
-            panic_with_error!(
-                env,
-                ContractError::WithdrawLiquidityMinimumAmountOfAOrBIsNotSatisfied
+                "Pool Stable: Withdraw one coin: Return amount is smaller than the minimum amount"
             );
+            panic_with_error!(env, ContractError::WithdrawMinAmountNotSatisfied);
         }
 
-        // burn shares
+        let share_token_client = token_contract::Client::new(&env, &config.share_token);
+        share_token_client.transfer(&sender, &env.current_contract_address(), &share_amount);
         utils::burn_shares(&env, &config.share_token, share_amount);
-        // transfer tokens from sender to contract
-        token_contract::Client::new(&env, &config.token_a).transfer(
-            &env.current_contract_address(),
-            &sender,
-            &return_amount_a,
-        );
-        token_contract::Client::new(&env, &config.token_b).transfer(
+
+        token_contract::Client::new(&env, &asset).transfer(
             &env.current_contract_address(),
             &sender,
-            &return_amount_b,
+            &return_amount,
         );
-        // update pool balances
-        utils::save_pool_balance_a(&env, pool_balance_a - return_amount_a);
-        utils::save_pool_balance_b(&env, pool_balance_b - return_amount_b);
+
+        // the fee stays in the pool for the liquidity providers
+        let index = get_token_index(&env, &config, &asset);
+        let mut balances = utils::get_pool_balances(&env);
+        balances.set(index, balances.get(index).unwrap() - return_amount);
+        utils::save_pool_balances(&env, &balances);
 
         env.events()
-            .publish(("withdraw_liquidity", "sender"), sender);
+            .publish(("withdraw_one_coin", "sender"), sender);
         env.events()
-            .publish(("withdraw_liquidity", "shares_amount"), share_amount);
+            .publish(("withdraw_one_coin", "shares_amount"), share_amount);
+        env.events().publish(("withdraw_one_coin", "asset"), asset);
+        env.events()
+            .publish(("withdraw_one_coin", "return_amount"), return_amount);
+        env.events()
+            .publish(("withdraw_one_coin", "fee_amount"), fee_amount);
+
+        return_amount
+    }
+
+    fn withdraw_imbalanced(
+        env: Env,
+        sender: Address,
+        amounts: Vec<i128>,
+        max_shares: i128,
+        deadline: Option<u64>,
+    ) -> i128 {
+        validate_int_parameters!(max_shares);
+        assert_deadline(&env, deadline);
+
+        sender.require_auth();
+
+        let config = get_config(&env);
+        let (share_amount, _) = compute_withdraw_imbalanced(&env, &amounts);
+
+        if share_amount > max_shares {
+            log!(
+                &env,
+                "Pool Stable: Withdraw imbalanced: Amount of shares to burn is bigger than the maximum"
+            );
+            panic_with_error!(env, ContractError::WithdrawMaxSharesExceeded);
+        }
+
+        let share_token_client = token_contract::Client::new(&env, &config.share_token);
+        share_token_client.transfer(&sender, &env.current_contract_address(), &share_amount);
+        utils::burn_shares(&env, &config.share_token, share_amount);
+
+        // the fees stay in the pool for the liquidity providers
+        let mut balances = Vec::new(&env);
+        for ((token, pool_balance), amount) in config
+            .tokens
+            .iter()
+            .zip(utils::get_pool_balances(&env).iter())
+            .zip(amounts.iter())
+        {
+            if amount > 0 {
+                token_contract::Client::new(&env, &token).transfer(
+                    &env.current_contract_address(),
+                    &sender,
+                    &amount,
+                );
+            }
+            balances.push_back(pool_balance - amount);
+        }
+        utils::save_pool_balances(&env, &balances);
+
+        env.events()
+            .publish(("withdraw_imbalanced", "sender"), sender);
         env.events()
-            .publish(("withdraw_liquidity", "return_amount_a"), return_amount_a);
+            .publish(("withdraw_imbalanced", "shares_amount"), share_amount);
         env.events()
-            .publish(("withdraw_liquidity", "return_amount_b"), return_amount_b);
+            .publish(("withdraw_imbalanced", "return_amounts"), amounts);
 
-        (return_amount_a, return_amount_b)
+        share_amount
     }
 
     fn update_config(
         env: Env,
         sender: Address,
-        new_admin: Option<Address>,
         total_fee_bps: Option<i64>,
         fee_recipient: Option<Address>,
         max_allowed_slippage_bps: Option<i64>,
         max_allowed_spread_bps: Option<i64>,
     ) {
+        sender.require_auth();
         if sender != utils::get_admin(&env) {
             log!(&env, "Pool Stable: UpdateConfig: Unauthorized");
             panic_with_error!(&env, ContractError::Unauthorized);
@@ -460,9 +723,6 @@ impl StableLiquidityPoolTrait for StableLiquidityPool {
 
         let mut config = get_config(&env);
 
-        if let Some(new_admin) = new_admin {
-            utils::save_admin(&env, new_admin);
-        }
         if let Some(total_fee_bps) = total_fee_bps {
             if !(0..=10_000).contains(&total_fee_bps) {
                 log!(&env, "Pool Stable: UpdateConfig: Invalid total_fee_bps");
@@ -487,123 +747,327 @@ impl StableLiquidityPoolTrait for StableLiquidityPool {
         let admin: Address = utils::get_admin(&env);
         admin.require_auth();
 
+        utils::migrate_legacy_storage(&env);
+
         env.deployer().update_current_contract_wasm(new_wasm_hash);
     }
 
-    // Queries
+    fn sync(env: Env) {
+        // syncing an empty pool would leave reserves without any shares to mint against
+        if utils::get_total_shares(&env) == 0 {
+            log!(&env, "Pool Stable: Sync: Pool has no liquidity to sync");
+            panic_with_error!(&env, ContractError::TotalSharesEqualZero);
+        }
 
-    fn query_config(env: Env) -> Config {
-        get_config(&env)
-    }
+        let config = get_config(&env);
+        let mut balances = Vec::new(&env);
+        for token in config.tokens.iter() {
+            balances.push_back(utils::get_balance(&env, &token));
+        }
+        utils::save_pool_balances(&env, &balances);
 
-    fn query_share_token_address(env: Env) -> Address {
-        get_config(&env).share_token
+        env.events().publish(("sync", "reserves"), balances);
     }
 
-    fn query_stake_contract_address(env: Env) -> Address {
-        get_config(&env).stake_contract
-    }
+    fn skim(env: Env, to: Address) {
+        let admin: Address = utils::get_admin(&env);
+        admin.require_auth();
 
-    fn query_pool_info(env: Env) -> PoolResponse {
         let config = get_config(&env);
-
-        PoolResponse {
-            asset_a: Asset {
-                address: config.token_a,
-                amount: utils::get_pool_balance_a(&env),
-            },
-            asset_b: Asset {
-                address: config.token_b,
-                amount: utils::get_pool_balance_b(&env),
-            },
-            asset_lp_share: Asset {
-                address: config.share_token,
-                amount: utils::get_total_shares(&env),
-            },
-            stake_address: config.stake_contract,
+        let mut excess_amounts = Vec::new(&env);
+        for (token, pool_balance) in config
+            .tokens
+            .iter()
+            .zip(utils::get_pool_balances(&env).iter())
+        {
+            let excess = (utils::get_balance(&env, &token) - pool_balance).max(0);
+            if excess > 0 {
+                token_contract::Client::new(&env, &token).transfer(
+                    &env.current_contract_address(),
+                    &to,
+                    &excess,
+                );
+            }
+            excess_amounts.push_back(excess);
         }
+
+        env.events().publish(("skim", "to"), to);
+        env.events().publish(("skim", "amounts"), excess_amounts);
     }
 
-    fn query_pool_info_for_factory(env: Env) -> StableLiquidityPoolInfo {
-        let config = get_config(&env);
-        let pool_response = PoolResponse {
-            asset_a: Asset {
-                address: config.token_a,
-                amount: utils::get_pool_balance_a(&env),
-            },
-            asset_b: Asset {
-                address: config.token_b,
-                amount: utils::get_pool_balance_b(&env),
-            },
-            asset_lp_share: Asset {
-                address: config.share_token,
-                amount: utils::get_total_shares(&env),
-            },
-            stake_address: config.stake_contract,
-        };
-        let total_fee_bps = config.total_fee_bps;
+    fn pause(env: Env, sender: Address) {
+        sender.require_auth();
+        assert_admin_or_factory(&env, &sender);
 
-        StableLiquidityPoolInfo {
-            pool_address: env.current_contract_address(),
-            pool_response,
-            total_fee_bps,
-        }
+        let mut config = get_config(&env);
+        config.paused = true;
+        save_config(&env, config);
+
+        env.events().publish(("pause", "sender"), sender);
     }
 
-    fn simulate_swap(env: Env, offer_asset: Address, offer_amount: i128) -> SimulateSwapResponse {
-        let config = get_config(&env);
+    fn unpause(env: Env, sender: Address) {
+        sender.require_auth();
+        assert_admin_or_factory(&env, &sender);
 
-        let pool_balance_a = utils::get_pool_balance_a(&env);
-        let pool_balance_b = utils::get_pool_balance_b(&env);
-        let (pool_balance_offer, pool_balance_ask) = if offer_asset == config.token_a {
-            (pool_balance_a, pool_balance_b)
-        } else if offer_asset == config.token_b {
-            (pool_balance_b, pool_balance_a)
-        } else {
-            log!(&env, "Pool Stable: Token offered to swap not found in Pool");
-            panic_with_error!(env, ContractError::AssetNotInPool);
-        };
+        let mut config = get_config(&env);
+        config.paused = false;
+        save_config(&env, config);
 
-        let (ask_amount, spread_amount, commission_amount) = compute_swap(
-            &env,
-            pool_balance_offer,
-            pool_balance_ask,
-            offer_amount,
-            config.protocol_fee_rate(),
-        );
+        env.events().publish(("unpause", "sender"), sender);
+    }
 
-        let total_return = ask_amount + commission_amount + spread_amount;
+    fn propose_admin(env: Env, sender: Address, new_admin: Address, expires_at: u64) {
+        sender.require_auth();
 
-        SimulateSwapResponse {
-            ask_amount,
-            spread_amount,
-            commission_amount,
-            total_return,
+        if sender != utils::get_admin(&env) {
+            log!(
+                &env,
+                "Pool Stable: Propose admin: Only the admin can propose a new admin"
+            );
+            panic_with_error!(&env, ContractError::Unauthorized);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            log!(
+                &env,
+                "Pool Stable: Propose admin: Proposal expiration must be in the future"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeExpired);
         }
+
+        utils::save_admin_change(
+            &env,
+            &AdminChange {
+                new_admin: new_admin.clone(),
+                expires_at,
+            },
+        );
+
+        env.events()
+            .publish(("propose_admin", "new_admin"), new_admin);
+        env.events()
+            .publish(("propose_admin", "expires_at"), expires_at);
     }
 
-    fn simulate_reverse_swap(
-        env: Env,
+    fn accept_admin(env: Env, sender: Address) {
+        sender.require_auth();
+
+        let admin_change = utils::get_admin_change(&env).unwrap_or_else(|| {
+            log!(&env, "Pool Stable: Accept admin: No admin change in place");
+            panic_with_error!(&env, ContractError::AdminChangeNotFound);
+        });
+        if sender != admin_change.new_admin {
+            log!(
+                &env,
+                "Pool Stable: Accept admin: Only the proposed admin can accept the admin role"
+            );
+            panic_with_error!(&env, ContractError::Unauthorized);
+        }
+        if env.ledger().timestamp() > admin_change.expires_at {
+            log!(
+                &env,
+                "Pool Stable: Accept admin: Admin change proposal has expired"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeExpired);
+        }
+
+        utils::remove_admin_change(&env);
+        utils::save_admin(&env, sender.clone());
+
+        env.events().publish(("accept_admin", "new_admin"), sender);
+    }
+
+    fn cancel_admin_proposal(env: Env, sender: Address) {
+        sender.require_auth();
+
+        if sender != utils::get_admin(&env) {
+            log!(
+                &env,
+                "Pool Stable: Cancel admin proposal: Only the admin can cancel the proposal"
+            );
+            panic_with_error!(&env, ContractError::Unauthorized);
+        }
+
+        let admin_change = utils::get_admin_change(&env).unwrap_or_else(|| {
+            log!(
+                &env,
+                "Pool Stable: Cancel admin proposal: No admin change in place"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeNotFound);
+        });
+        utils::remove_admin_change(&env);
+
+        env.events().publish(
+            ("cancel_admin_proposal", "new_admin"),
+            admin_change.new_admin,
+        );
+    }
+
+    fn ramp_amp(env: Env, next_amp: u64, next_amp_time: u64) {
+        let admin: Address = utils::get_admin(&env);
+        admin.require_auth();
+
+        if next_amp == 0 || next_amp > MAX_AMP {
+            log!(
+                &env,
+                "Pool Stable: Ramp amp: Amplification coefficient must be between 1 and MAX_AMP"
+            );
+            panic_with_error!(&env, ContractError::InvalidAmp);
+        }
+
+        let amp_params = get_amp(&env).unwrap();
+        let current_amp = compute_current_amp(&env, &amp_params);
+        let next_amp_with_precision = next_amp * AMP_PRECISION;
+
+        if next_amp_with_precision * MAX_AMP_CHANGE < current_amp
+            || next_amp_with_precision > current_amp * MAX_AMP_CHANGE
+        {
+            log!(
+                &env,
+                "Pool Stable: Ramp amp: Amplification coefficient can change at most MAX_AMP_CHANGE times"
+            );
+            panic_with_error!(&env, ContractError::MaxAmpChangeExceeded);
+        }
+
+        let current_time = env.ledger().timestamp();
+        if current_time < amp_params.init_amp_time + MIN_AMP_CHANGING_TIME
+            || next_amp_time < current_time + MIN_AMP_CHANGING_TIME
+        {
+            log!(
+                &env,
+                "Pool Stable: Ramp amp: Amplification changes must last and be apart at least MIN_AMP_CHANGING_TIME"
+            );
+            panic_with_error!(&env, ContractError::MinAmpChangingTime);
+        }
+
+        save_amp(
+            &env,
+            AmplifierParameters {
+                init_amp: current_amp,
+                init_amp_time: current_time,
+                next_amp: next_amp_with_precision,
+                next_amp_time,
+            },
+        );
+
+        env.events().publish(("ramp_amp", "next_amp"), next_amp);
+        env.events()
+            .publish(("ramp_amp", "next_amp_time"), next_amp_time);
+    }
+
+    fn stop_ramp_amp(env: Env) {
+        let admin: Address = utils::get_admin(&env);
+        admin.require_auth();
+
+        let current_amp = compute_current_amp(&env, &get_amp(&env).unwrap());
+        let current_time = env.ledger().timestamp();
+
+        save_amp(
+            &env,
+            AmplifierParameters {
+                init_amp: current_amp,
+                init_amp_time: current_time,
+                next_amp: current_amp,
+                next_amp_time: current_time,
+            },
+        );
+
+        env.events()
+            .publish(("stop_ramp_amp", "current_amp"), current_amp);
+    }
+
+    // Queries
+
+    fn query_config(env: Env) -> Config {
+        get_config(&env)
+    }
+
+    fn query_share_token_address(env: Env) -> Address {
+        get_config(&env).share_token
+    }
+
+    fn query_stake_contract_address(env: Env) -> Address {
+        get_config(&env).stake_contract
+    }
+
+    fn query_pool_info(env: Env) -> PoolResponse {
+        let config = get_config(&env);
+
+        let mut assets = Vec::new(&env);
+        for (token, pool_balance) in config
+            .tokens
+            .iter()
+            .zip(utils::get_pool_balances(&env).iter())
+        {
+            assets.push_back(Asset {
+                address: token,
+                amount: pool_balance,
+            });
+        }
+
+        PoolResponse {
+            assets,
+            asset_lp_share: Asset {
+                address: config.share_token,
+                amount: utils::get_total_shares(&env),
+            },
+            stake_address: config.stake_contract,
+        }
+    }
+
+    fn query_pool_info_for_factory(env: Env) -> StableLiquidityPoolInfo {
+        let pool_response = Self::query_pool_info(env.clone());
+        let total_fee_bps = get_config(&env).total_fee_bps;
+
+        StableLiquidityPoolInfo {
+            pool_address: env.current_contract_address(),
+            pool_response,
+            total_fee_bps,
+        }
+    }
+
+    fn simulate_swap(
+        env: Env,
+        offer_asset: Address,
+        ask_asset: Address,
+        offer_amount: i128,
+    ) -> SimulateSwapResponse {
+        let config = get_config(&env);
+        let (offer_index, ask_index) = get_swap_indexes(&env, &config, &offer_asset, &ask_asset);
+
+        let (ask_amount, spread_amount, commission_amount) = compute_swap(
+            &env,
+            &utils::get_pool_balances(&env),
+            offer_index,
+            ask_index,
+            offer_amount,
+            config.protocol_fee_rate(),
+        );
+
+        let total_return = ask_amount + commission_amount + spread_amount;
+
+        SimulateSwapResponse {
+            ask_amount,
+            spread_amount,
+            commission_amount,
+            total_return,
+        }
+    }
+
+    fn simulate_reverse_swap(
+        env: Env,
         offer_asset: Address,
+        ask_asset: Address,
         ask_amount: i128,
     ) -> SimulateReverseSwapResponse {
         let config = get_config(&env);
-
-        let pool_balance_a = utils::get_pool_balance_a(&env);
-        let pool_balance_b = utils::get_pool_balance_b(&env);
-        let (pool_balance_offer, pool_balance_ask) = if offer_asset == config.token_b {
-            (pool_balance_a, pool_balance_b)
-        } else if offer_asset == config.token_a {
-            (pool_balance_b, pool_balance_a)
-        } else {
-            log!(&env, "Pool Stable: Token offered to swap not found in Pool");
-            panic_with_error!(env, ContractError::AssetNotInPool);
-        };
+        let (offer_index, ask_index) = get_swap_indexes(&env, &config, &offer_asset, &ask_asset);
 
         let (offer_amount, spread_amount, commission_amount) = compute_offer_amount(
             &env,
-            pool_balance_offer,
-            pool_balance_ask,
+            &utils::get_pool_balances(&env),
+            offer_index,
+            ask_index,
             ask_amount,
             config.protocol_fee_rate(),
         );
@@ -615,29 +1079,122 @@ impl StableLiquidityPoolTrait for StableLiquidityPool {
         }
     }
 
-    fn query_share(env: Env, amount: i128) -> (Asset, Asset) {
-        let pool_info = Self::query_pool_info(env);
+    fn simulate_swap_exact_out(
+        env: Env,
+        offer_asset: Address,
+        ask_asset: Address,
+        ask_amount: i128,
+    ) -> i128 {
+        let config = get_config(&env);
+        let (offer_index, ask_index) = get_swap_indexes(&env, &config, &offer_asset, &ask_asset);
+
+        compute_exact_out_offer_amount(
+            &env,
+            &utils::get_pool_balances(&env),
+            offer_index,
+            ask_index,
+            ask_amount,
+            config.protocol_fee_rate(),
+        )
+    }
+
+    fn simulate_provide_liquidity(
+        env: Env,
+        desired_amounts: Vec<i128>,
+    ) -> SimulateProvideLiquidityResponse {
+        let config = get_config(&env);
+        let (share_amount, slippage) = compute_deposit_shares(&env, &config, &desired_amounts);
+
+        let mut assets = Vec::new(&env);
+        for (address, amount) in config.tokens.iter().zip(desired_amounts.iter()) {
+            assets.push_back(Asset { address, amount });
+        }
+
+        // rounded up, so the slippage is never understated
+        let one_bps = Decimal::bps(1).atomics();
+        let slippage_bps = ((slippage.atomics() + one_bps - 1) / one_bps) as i64;
+
+        SimulateProvideLiquidityResponse {
+            assets,
+            share_amount,
+            slippage_bps,
+        }
+    }
+
+    fn simulate_withdraw_one_coin(
+        env: Env,
+        share_amount: i128,
+        asset: Address,
+    ) -> SimulateWithdrawOneCoinResponse {
+        let (return_amount, fee_amount) = compute_withdraw_one_coin(&env, share_amount, &asset);
+
+        SimulateWithdrawOneCoinResponse {
+            return_amount,
+            fee_amount,
+        }
+    }
+
+    fn simulate_withdraw_imbalanced(
+        env: Env,
+        amounts: Vec<i128>,
+    ) -> SimulateWithdrawImbalancedResponse {
+        let (share_amount, fee_amounts) = compute_withdraw_imbalanced(&env, &amounts);
+
+        SimulateWithdrawImbalancedResponse {
+            share_amount,
+            fee_amounts,
+        }
+    }
+
+    fn query_share(env: Env, amount: i128) -> Vec<Asset> {
+        let pool_info = Self::query_pool_info(env.clone());
         let total_share = pool_info.asset_lp_share.amount;
-        let token_a_amount = pool_info.asset_a.amount;
-        let token_b_amount = pool_info.asset_b.amount;
 
-        let mut share_ratio = Decimal::zero();
-        if total_share != 0 {
-            share_ratio = Decimal::from_ratio(amount, total_share);
+        let mut shares = Vec::new(&env);
+        for asset in pool_info.assets.iter() {
+            let amount = if total_share != 0 {
+                mul_div(
+                    &env,
+                    asset.amount as u128,
+                    amount as u128,
+                    total_share as u128,
+                ) as i128
+            } else {
+                0
+            };
+            shares.push_back(Asset {
+                address: asset.address,
+                amount,
+            });
         }
+        shares
+    }
 
-        let amount_a = token_a_amount * share_ratio;
-        let amount_b = token_b_amount * share_ratio;
-        (
-            Asset {
-                address: pool_info.asset_a.address,
-                amount: amount_a,
-            },
-            Asset {
-                address: pool_info.asset_b.address,
-                amount: amount_b,
-            },
-        )
+    fn query_reserve_drift(env: Env) -> Vec<Asset> {
+        let config = get_config(&env);
+
+        let mut drifts = Vec::new(&env);
+        for (token, pool_balance) in config
+            .tokens
+            .iter()
+            .zip(utils::get_pool_balances(&env).iter())
+        {
+            drifts.push_back(Asset {
+                amount: utils::get_balance(&env, &token) - pool_balance,
+                address: token,
+            });
+        }
+        drifts
+    }
+
+    fn query_amp(env: Env) -> AmpResponse {
+        let amp_params = get_amp(&env).unwrap();
+        let current_amp = compute_current_amp(&env, &amp_params);
+
+        AmpResponse {
+            amp_params,
+            current_amp,
+        }
     }
 }
 
@@ -645,19 +1202,14 @@ fn do_swap(
     env: Env,
     sender: Address,
     offer_asset: Address,
+    ask_asset: Address,
     offer_amount: i128,
     ask_asset_min_amount: Option<i128>,
     max_spread: Option<i64>,
 ) -> i128 {
     let config = get_config(&env);
 
-    if offer_asset != config.token_a && offer_asset != config.token_b {
-        log!(
-            &env,
-            "Pool Stable: do swap: Trying to swap wrong asset. Aborting.."
-        );
-        panic_with_error!(&env, ContractError::IncorrectAssetSwap);
-    }
+    let (offer_index, ask_index) = get_swap_indexes(&env, &config, &offer_asset, &ask_asset);
 
     if let Some(max_spread) = max_spread {
         if !(0..=config.max_allowed_spread_bps).contains(&max_spread) {
@@ -668,22 +1220,13 @@ fn do_swap(
 
     let max_spread = Decimal::bps(max_spread.map_or_else(|| config.max_allowed_spread_bps, |x| x));
 
-    let pool_balance_a = utils::get_pool_balance_a(&env);
-    let pool_balance_b = utils::get_pool_balance_b(&env);
-
-    let (pool_balance_sell, pool_balance_buy) = if offer_asset == config.token_a {
-        (pool_balance_a, pool_balance_b)
-    } else if offer_asset == config.token_b {
-        (pool_balance_b, pool_balance_a)
-    } else {
-        log!(&env, "Pool Stable: Token offered to swap not found in Pool");
-        panic_with_error!(env, ContractError::AssetNotInPool);
-    };
+    let mut balances = utils::get_pool_balances(&env);
 
     let (return_amount, spread_amount, commission_amount) = compute_swap(
         &env,
-        pool_balance_sell,
-        pool_balance_buy,
+        &balances,
+        offer_index,
+        ask_index,
         offer_amount,
         config.protocol_fee_rate(),
     );
@@ -705,54 +1248,42 @@ fn do_swap(
         spread_amount,
     );
 
-    // Transfer the amount being sold to the contract
-    let (sell_token, buy_token) = if offer_asset == config.clone().token_a {
-        (config.clone().token_a, config.clone().token_b)
-    } else {
-        (config.clone().token_b, config.clone().token_a)
-    };
-
     // transfer tokens to swap
-    token_contract::Client::new(&env, &sell_token).transfer(
+    token_contract::Client::new(&env, &offer_asset).transfer(
         &sender,
         &env.current_contract_address(),
         &offer_amount,
     );
 
     // return swapped tokens to user
-    token_contract::Client::new(&env, &buy_token).transfer(
+    token_contract::Client::new(&env, &ask_asset).transfer(
         &env.current_contract_address(),
         &sender,
         &return_amount,
     );
 
     // send commission to fee recipient
-    token_contract::Client::new(&env, &buy_token).transfer(
+    token_contract::Client::new(&env, &ask_asset).transfer(
         &env.current_contract_address(),
         &config.fee_recipient,
         &commission_amount,
     );
 
-    // user is offering to sell A, so they will receive B
-    // A balance is bigger, B balance is smaller
-    let (balance_a, balance_b) = if offer_asset == config.token_a {
-        (
-            pool_balance_a + offer_amount,
-            pool_balance_b - commission_amount - return_amount,
-        )
-    } else {
-        (
-            pool_balance_a - commission_amount - return_amount,
-            pool_balance_b + offer_amount,
-        )
-    };
-    utils::save_pool_balance_a(&env, balance_a);
-    utils::save_pool_balance_b(&env, balance_b);
+    // the offer balance is bigger, the ask balance is smaller
+    balances.set(
+        offer_index,
+        balances.get(offer_index).unwrap() + offer_amount,
+    );
+    balances.set(
+        ask_index,
+        balances.get(ask_index).unwrap() - commission_amount - return_amount,
+    );
+    utils::save_pool_balances(&env, &balances);
 
     env.events().publish(("swap", "sender"), sender);
-    env.events().publish(("swap", "sell_token"), sell_token);
+    env.events().publish(("swap", "sell_token"), offer_asset);
     env.events().publish(("swap", "offer_amount"), offer_amount);
-    env.events().publish(("swap", "buy_token"), buy_token);
+    env.events().publish(("swap", "buy_token"), ask_asset);
     env.events()
         .publish(("swap", "return_amount"), return_amount);
     env.events()
@@ -761,6 +1292,120 @@ fn do_swap(
     return_amount
 }
 
+fn do_swap_exact_out(
+    env: Env,
+    sender: Address,
+    offer_asset: Address,
+    ask_asset: Address,
+    ask_amount: i128,
+    max_offer_amount: i128,
+    max_spread: Option<i64>,
+) -> i128 {
+    let config = get_config(&env);
+
+    let (offer_index, ask_index) = get_swap_indexes(&env, &config, &offer_asset, &ask_asset);
+
+    if let Some(max_spread) = max_spread {
+        if !(0..=config.max_allowed_spread_bps).contains(&max_spread) {
+            log!(
+                &env,
+                "Pool Stable: do swap exact out: max spread is out of bounds"
+            );
+            panic_with_error!(&env, ContractError::InvalidBps);
+        }
+    }
+
+    let max_spread = Decimal::bps(max_spread.map_or_else(|| config.max_allowed_spread_bps, |x| x));
+
+    let mut balances = utils::get_pool_balances(&env);
+
+    let offer_amount = compute_exact_out_offer_amount(
+        &env,
+        &balances,
+        offer_index,
+        ask_index,
+        ask_amount,
+        config.protocol_fee_rate(),
+    );
+
+    if offer_amount > max_offer_amount {
+        log!(
+            &env,
+            "Pool Stable: do swap exact out: Offer amount ({}) is bigger then the maximum offer amount ({})",
+            offer_amount,
+            max_offer_amount
+        );
+        panic_with_error!(&env, ContractError::SwapOfferAmountBiggerThanMax);
+    }
+
+    let (return_amount, spread_amount, commission_amount) = compute_swap(
+        &env,
+        &balances,
+        offer_index,
+        ask_index,
+        offer_amount,
+        config.protocol_fee_rate(),
+    );
+
+    // Sanity check against the precision of `calc_y`, the sender must receive the exact amount
+    if return_amount < ask_amount {
+        log!(
+            &env,
+            "Pool Stable: do swap exact out: Return amount is smaller then the asked amount"
+        );
+        panic_with_error!(&env, ContractError::SwapMinReceivedBiggerThanReturn);
+    }
+
+    assert_max_spread(
+        &env,
+        max_spread,
+        return_amount + commission_amount,
+        spread_amount,
+    );
+
+    // transfer tokens to swap
+    token_contract::Client::new(&env, &offer_asset).transfer(
+        &sender,
+        &env.current_contract_address(),
+        &offer_amount,
+    );
+
+    // return exactly the asked amount to the user, any rounding leftover stays in the pool
+    token_contract::Client::new(&env, &ask_asset).transfer(
+        &env.current_contract_address(),
+        &sender,
+        &ask_amount,
+    );
+
+    // send commission to fee recipient
+    token_contract::Client::new(&env, &ask_asset).transfer(
+        &env.current_contract_address(),
+        &config.fee_recipient,
+        &commission_amount,
+    );
+
+    // the offer balance is bigger, the ask balance is smaller
+    balances.set(
+        offer_index,
+        balances.get(offer_index).unwrap() + offer_amount,
+    );
+    balances.set(
+        ask_index,
+        balances.get(ask_index).unwrap() - commission_amount - ask_amount,
+    );
+    utils::save_pool_balances(&env, &balances);
+
+    env.events().publish(("swap", "sender"), sender);
+    env.events().publish(("swap", "sell_token"), offer_asset);
+    env.events().publish(("swap", "offer_amount"), offer_amount);
+    env.events().publish(("swap", "buy_token"), ask_asset);
+    env.events().publish(("swap", "return_amount"), ask_amount);
+    env.events()
+        .publish(("swap", "spread_amount"), spread_amount);
+
+    offer_amount
+}
+
 /// This function asserts that the spread (slippage) does not exceed a given maximum.
 /// * `max_spread` - The maximum allowed spread (slippage) as a fraction of the return amount.
 /// * `return_amount` - The amount of tokens that the user receives in return.
@@ -769,19 +1414,204 @@ fn do_swap(
 /// * An error if the spread exceeds the maximum allowed, otherwise Ok.
 pub fn assert_max_spread(env: &Env, max_spread: Decimal, return_amount: i128, spread_amount: i128) {
     // Calculate the spread ratio, the fraction of the return that is due to spread
-    let spread_ratio = Decimal::from_ratio(spread_amount, return_amount);
-
-    if spread_ratio > max_spread {
+    if spread_amount > 0 && ratio(env, spread_amount as u128, return_amount as u128) > max_spread {
         log!(env, "Pool Stable: Spread exceeds maximum allowed");
         panic_with_error!(env, ContractError::SpreadExceedsLimit);
     }
 }
 
+/// Rejects the transaction when the ledger timestamp is past the optional `deadline`.
+pub fn assert_deadline(env: &Env, deadline: Option<u64>) {
+    if let Some(deadline) = deadline {
+        if env.ledger().timestamp() > deadline {
+            log!(env, "Pool Stable: Transaction deadline has passed");
+            panic_with_error!(env, ContractError::DeadlineExceeded);
+        }
+    }
+}
+
+/// Rejects swaps and deposits while the pool is paused.
+pub fn assert_not_paused(env: &Env) {
+    if get_config(env).paused {
+        log!(env, "Pool Stable: Pool is paused");
+        panic_with_error!(env, ContractError::Paused);
+    }
+}
+
+fn assert_admin_or_factory(env: &Env, sender: &Address) {
+    if *sender != utils::get_admin(env) && *sender != utils::get_factory(env) {
+        log!(
+            env,
+            "Pool Stable: Pause: Only the admin or the factory can pause the pool"
+        );
+        panic_with_error!(env, ContractError::Unauthorized);
+    }
+}
+
+/// Returns the amount of pool shares minted for depositing `desired_amounts` of the pool tokens,
+/// together with the slippage of the deposit.
+///
+/// The first deposit mints shares equal to the invariant minus `MINIMUM_LIQUIDITY_AMOUNT`, later
+/// deposits mint shares in proportion to the increase of the invariant. The slippage is the part
+/// of the deposit value, counting every token 1:1, that does not increase the invariant.
+fn compute_deposit_shares(
+    env: &Env,
+    config: &Config,
+    desired_amounts: &Vec<i128>,
+) -> (i128, Decimal) {
+    let old_balances = utils::get_pool_balances(env);
+    let total_shares = utils::get_total_shares(env);
+
+    // The first deposit has to include every token, otherwise the invariant is not defined
+    let min_amount = if total_shares == 0 { 1 } else { 0 };
+    if desired_amounts.len() != config.tokens.len()
+        || desired_amounts.iter().any(|amount| amount < min_amount)
+        || desired_amounts.iter().all(|amount| amount == 0)
+    {
+        log!(
+            env,
+            "Pool Stable: ProvideLiquidity: An amount must be given for each token and the first deposit must include every token"
+        );
+        panic_with_error!(env, ContractError::InvalidDepositAmounts);
+    }
+
+    let amp_parameters = get_amp(env).unwrap(); // FIXME: This is minor, but add some
+                                                // validation to AMP parameters
+    let amp = compute_current_amp(env, &amp_parameters);
+
+    // Invariant (D) after deposit added
+    let mut new_balances = Vec::new(env);
+    for (old_balance, desired_amount) in old_balances.iter().zip(desired_amounts.iter()) {
+        new_balances.push_back(old_balance + desired_amount);
+    }
+    let n_coins = config.tokens.len() as usize;
+    let precisions = get_precisions(env);
+    let new_invariant = compute_d(
+        env,
+        amp as u128,
+        &to_xp(env, &new_balances, &precisions)[..n_coins],
+    );
+
+    if total_shares == 0 {
+        let share =
+            from_xp(new_invariant, get_greatest_precision(env) as u32) - MINIMUM_LIQUIDITY_AMOUNT;
+        if share == 0 {
+            log!(
+                env,
+                "Pool Stable: ProvideLiquidity: Liquidity amount is too low"
+            );
+            panic_with_error!(env, ContractError::LowLiquidity);
+        }
+        return (share, Decimal::zero());
+    }
+
+    let initial_invariant = compute_d(
+        env,
+        amp as u128,
+        &to_xp(env, &old_balances, &precisions)[..n_coins],
+    );
+    // Mint the shares in proportion to the change in invariant, the total shares already
+    // carry the precision of the pool tokens
+    let invariant_increase = new_invariant.saturating_sub(initial_invariant);
+    let shares = mul_div(
+        env,
+        total_shares as u128,
+        invariant_increase,
+        initial_invariant,
+    ) as i128;
+
+    let deposit_value: u128 = to_xp(env, desired_amounts, &precisions)[..n_coins]
+        .iter()
+        .sum();
+    let slippage = if invariant_increase < deposit_value {
+        ratio(env, deposit_value - invariant_increase, deposit_value)
+    } else {
+        Decimal::zero()
+    };
+
+    (shares, slippage)
+}
+
+/// Returns the position of `asset` in the pool tokens.
+fn get_token_index(env: &Env, config: &Config, asset: &Address) -> u32 {
+    config.tokens.first_index_of(asset).unwrap_or_else(|| {
+        log!(env, "Pool Stable: Token not found in Pool");
+        panic_with_error!(env, ContractError::AssetNotInPool);
+    })
+}
+
+/// Returns the positions of `offer_asset` and `ask_asset` in the pool tokens, which must be two
+/// different tokens of the pool.
+fn get_swap_indexes(
+    env: &Env,
+    config: &Config,
+    offer_asset: &Address,
+    ask_asset: &Address,
+) -> (u32, u32) {
+    if offer_asset == ask_asset {
+        log!(
+            env,
+            "Pool Stable: Trying to swap wrong asset. Offer and ask assets must be different"
+        );
+        panic_with_error!(env, ContractError::IncorrectAssetSwap);
+    }
+
+    (
+        get_token_index(env, config, offer_asset),
+        get_token_index(env, config, ask_asset),
+    )
+}
+
+/// Converts the pool balances to the values used by the stableswap math, normalising each of
+/// them to `DECIMAL_PRECISION` with the decimals of its token. Only the first `balances.len()`
+/// entries of the result are set.
+fn to_xp(env: &Env, balances: &Vec<i128>, precisions: &Vec<u32>) -> [u128; MAX_COINS] {
+    let mut xp = [0u128; MAX_COINS];
+    for ((x, balance), precision) in xp.iter_mut().zip(balances.iter()).zip(precisions.iter()) {
+        *x = to_xp_amount(env, balance, precision);
+    }
+    xp
+}
+
+/// Normalises `amount` of a token with `precision` decimals to `DECIMAL_PRECISION`.
+fn to_xp_amount(env: &Env, amount: i128, precision: u32) -> u128 {
+    (amount as u128)
+        .checked_mul(10u128.pow(DECIMAL_PRECISION - precision))
+        .unwrap_or_else(|| {
+            log!(
+                env,
+                "Pool Stable: Amount does not fit in 128 bits once normalised"
+            );
+            panic_with_error!(env, ContractError::ArithmeticOverflow);
+        })
+}
+
+/// Converts a normalised `amount` back to a token with `precision` decimals, rounded down.
+fn from_xp(amount: u128, precision: u32) -> i128 {
+    (amount / 10u128.pow(DECIMAL_PRECISION - precision)) as i128
+}
+
+/// Converts a normalised `amount` back to a token with `precision` decimals, rounded up.
+fn from_xp_ceil(amount: u128, precision: u32) -> i128 {
+    amount.div_ceil(10u128.pow(DECIMAL_PRECISION - precision)) as i128
+}
+
+/// Converts `amount` of a token with `from_precision` decimals to the same value in a token with
+/// `to_precision` decimals, truncating the lost digits.
+fn convert_precision(amount: i128, from_precision: u32, to_precision: u32) -> i128 {
+    if from_precision > to_precision {
+        amount / 10i128.pow(from_precision - to_precision)
+    } else {
+        amount * 10i128.pow(to_precision - from_precision)
+    }
+}
+
 /// Computes the result of a swap operation.
 ///
 /// Arguments:
-/// - `offer_pool`: Total amount of offer assets in the pool.
-/// - `ask_pool`: Total amount of ask assets in the pool.
+/// - `balances`: Total amounts of all the assets in the pool.
+/// - `offer_index`: Position of the offer asset in the pool.
+/// - `ask_index`: Position of the ask asset in the pool.
 /// - `offer_amount`: Amount of offer assets to swap.
 /// - `commission_rate`: Total amount of fees charged for the swap.
 ///
@@ -791,29 +1621,39 @@ pub fn assert_max_spread(env: &Env, max_spread: Decimal, return_amount: i128, sp
 /// - The commission amount, representing the fees charged for the swap.
 pub fn compute_swap(
     env: &Env,
-    offer_pool: i128,
-    ask_pool: i128,
+    balances: &Vec<i128>,
+    offer_index: u32,
+    ask_index: u32,
     offer_amount: i128,
     commission_rate: Decimal,
 ) -> (i128, i128, i128) {
     let amp_parameters = get_amp(env).unwrap();
     let amp = compute_current_amp(env, &amp_parameters);
 
+    let offer_pool = balances.get(offer_index).unwrap();
+    let precisions = get_precisions(env);
+    let offer_precision = precisions.get(offer_index).unwrap();
+    let ask_precision = precisions.get(ask_index).unwrap();
+
+    let xp = to_xp(env, balances, &precisions);
     let new_ask_pool = calc_y(
         env,
         amp as u128,
-        Decimal::from_atomics(offer_pool + offer_amount, 6),
-        &[
-            Decimal::from_atomics(offer_pool, 6),
-            Decimal::from_atomics(ask_pool, 6),
-        ],
-        6,
+        to_xp_amount(env, offer_pool + offer_amount, offer_precision),
+        &xp[..balances.len() as usize],
+        offer_index as usize,
+        ask_index as usize,
     );
 
-    let return_amount = ask_pool - new_ask_pool;
+    // one unit less covers the precision of Newton's method in `calc_y`, in favour of the pool
+    let return_amount = from_xp(
+        xp[ask_index as usize].saturating_sub(new_ask_pool + 1),
+        ask_precision,
+    );
     // We consider swap rate 1:1 in stable swap thus any difference is considered as spread.
-    let spread_amount = offer_amount - return_amount;
-    let commission_amount = return_amount * commission_rate;
+    let spread_amount =
+        convert_precision(offer_amount, offer_precision, ask_precision) - return_amount;
+    let commission_amount = mul_rate(env, return_amount as u128, commission_rate) as i128;
     // Because of issue #211
     let return_amount = return_amount - commission_amount;
 
@@ -822,48 +1662,315 @@ pub fn compute_swap(
 
 /// Returns an amount of offer assets for a specified amount of ask assets.
 ///
-/// * **offer_pool** total amount of offer assets in the pool.
-/// * **ask_pool** total amount of ask assets in the pool.
+/// * **balances** total amounts of all the assets in the pool.
+/// * **offer_index** position of the offer asset in the pool.
+/// * **ask_index** position of the ask asset in the pool.
 /// * **ask_amount** amount of ask assets to swap to.
 /// * **commission_rate** total amount of fees charged for the swap.
 pub fn compute_offer_amount(
     env: &Env,
-    offer_pool: i128,
-    ask_pool: i128,
+    balances: &Vec<i128>,
+    offer_index: u32,
+    ask_index: u32,
     ask_amount: i128,
     commission_rate: Decimal,
 ) -> (i128, i128, i128) {
     let amp_parameters = get_amp(env).unwrap();
     let amp = compute_current_amp(env, &amp_parameters);
 
+    let offer_pool = balances.get(offer_index).unwrap();
+    let ask_pool = balances.get(ask_index).unwrap();
+    let precisions = get_precisions(env);
+
+    let xp = to_xp(env, balances, &precisions);
     let new_offer_pool = calc_y(
         env,
         amp as u128,
-        Decimal::from_atomics(ask_pool - ask_amount, 6),
-        &[
-            Decimal::from_atomics(offer_pool, 6),
-            Decimal::from_atomics(ask_pool, 6),
-        ],
-        6,
+        to_xp_amount(
+            env,
+            ask_pool - ask_amount,
+            precisions.get(ask_index).unwrap(),
+        ),
+        &xp[..balances.len() as usize],
+        ask_index as usize,
+        offer_index as usize,
     );
 
-    let offer_amount = new_offer_pool - offer_pool;
+    let offer_amount = from_xp_ceil(
+        new_offer_pool.saturating_sub(xp[offer_index as usize]),
+        precisions.get(offer_index).unwrap(),
+    );
 
     let one_minus_commission = Decimal::one() - commission_rate;
-    let inv_one_minus_commission = Decimal::one() / one_minus_commission;
-    let ask_before_commission = ask_amount * inv_one_minus_commission;
+    let ask_before_commission = mul_div(
+        env,
+        ask_amount as u128,
+        Decimal::one().atomics() as u128,
+        one_minus_commission.atomics() as u128,
+    ) as i128;
     // Calculate the spread amount, representing the difference between the expected and actual swap amounts
-    let spread_amount: i128 = (offer_amount * ask_pool / offer_pool) - ask_before_commission;
+    let spread_amount: i128 = mul_div(
+        env,
+        offer_amount as u128,
+        ask_pool as u128,
+        offer_pool as u128,
+    ) as i128
+        - ask_before_commission;
 
     // Calculate the commission amount
-    let commission_amount: i128 = ask_before_commission * commission_rate;
+    let commission_amount = mul_rate(env, ask_before_commission as u128, commission_rate) as i128;
 
     (offer_amount, spread_amount, commission_amount)
 }
 
+/// Returns an amount of offer assets that makes `compute_swap` return at least `ask_amount` of
+/// ask assets after the commission is deducted. Unlike `compute_offer_amount`, the commission is
+/// included and the result is rounded in favour of the pool.
+///
+/// * **balances** total amounts of all the assets in the pool.
+/// * **offer_index** position of the offer asset in the pool.
+/// * **ask_index** position of the ask asset in the pool.
+/// * **ask_amount** amount of ask assets the user has to receive.
+/// * **commission_rate** total amount of fees charged for the swap.
+pub fn compute_exact_out_offer_amount(
+    env: &Env,
+    balances: &Vec<i128>,
+    offer_index: u32,
+    ask_index: u32,
+    ask_amount: i128,
+    commission_rate: Decimal,
+) -> i128 {
+    let ask_pool = balances.get(ask_index).unwrap();
+
+    let one_minus_commission = (Decimal::one() - commission_rate).atomics();
+    if one_minus_commission <= 0 {
+        log!(
+            env,
+            "Pool Stable: compute_exact_out_offer_amount: Exact output swaps are not possible with 100% fees"
+        );
+        panic_with_error!(env, ContractError::SwapAskAmountBiggerThanReserve);
+    }
+
+    // ask_amount / (1 - commission_rate), rounded up
+    let ask_before_commission = mul_div_ceil(
+        env,
+        ask_amount as u128,
+        Decimal::one().atomics() as u128,
+        one_minus_commission as u128,
+    ) as i128;
+    if ask_before_commission >= ask_pool {
+        log!(
+            env,
+            "Pool Stable: compute_exact_out_offer_amount: Not enough liquidity to return the asked amount"
+        );
+        panic_with_error!(env, ContractError::SwapAskAmountBiggerThanReserve);
+    }
+
+    let amp_parameters = get_amp(env).unwrap();
+    let amp = compute_current_amp(env, &amp_parameters);
+
+    let precisions = get_precisions(env);
+
+    // the ask pool is solved two units lower and the offer pool is raised by one more unit, which
+    // together with the unit `compute_swap` withholds covers the precision of Newton's method
+    let xp = to_xp(env, balances, &precisions);
+    let new_offer_pool = calc_y(
+        env,
+        amp as u128,
+        to_xp_amount(
+            env,
+            ask_pool - ask_before_commission,
+            precisions.get(ask_index).unwrap(),
+        )
+        .saturating_sub(2),
+        &xp[..balances.len() as usize],
+        ask_index as usize,
+        offer_index as usize,
+    );
+
+    from_xp_ceil(
+        (new_offer_pool + 1).saturating_sub(xp[offer_index as usize]),
+        precisions.get(offer_index).unwrap(),
+    )
+}
+
+/// Returns the fee rate charged on the imbalanced part of a withdrawal. As in Curve, this is
+/// `fee * n / (4 * (n - 1))`, which is half of the swap fee for a pool of two assets.
+fn imbalance_fee_rate(config: &Config) -> Decimal {
+    let n_coins = config.tokens.len() as i128;
+    config.protocol_fee_rate() * Decimal::from_ratio(n_coins, 4 * (n_coins - 1))
+}
+
+/// Returns the amount of `asset` paid out for burning `share_amount` of pool shares, together
+/// with the fee that stays in the pool.
+///
+/// The invariant is reduced in proportion to the burned shares and the balance of `asset` is
+/// solved for the reduced invariant. The fee is charged on the difference from a proportional
+/// withdrawal.
+pub fn compute_withdraw_one_coin(env: &Env, share_amount: i128, asset: &Address) -> (i128, i128) {
+    let config = get_config(env);
+
+    let index = config.tokens.first_index_of(asset).unwrap_or_else(|| {
+        log!(
+            env,
+            "Pool Stable: Withdraw one coin: Asset not found in Pool"
+        );
+        panic_with_error!(env, ContractError::AssetNotInPool);
+    });
+
+    let total_shares = utils::get_total_shares(env);
+    if total_shares == 0 {
+        log!(
+            env,
+            "Pool Stable: Withdraw one coin: Critical error - Total shares are equal to zero before withdrawal!"
+        );
+        panic_with_error!(env, ContractError::TotalSharesEqualZero);
+    }
+    if share_amount >= total_shares {
+        log!(
+            env,
+            "Pool Stable: Withdraw one coin: Share amount must be smaller than the total shares"
+        );
+        panic_with_error!(env, ContractError::WithdrawAmountExceedsTotalShares);
+    }
+
+    let amp = compute_current_amp(env, &get_amp(env).unwrap()) as u128;
+
+    let balances = utils::get_pool_balances(env);
+    let n_coins = balances.len() as usize;
+    let precisions = get_precisions(env);
+    let precision = precisions.get(index).unwrap();
+    let index = index as usize;
+    let xp = to_xp(env, &balances, &precisions);
+    let d0 = compute_d(env, amp, &xp[..n_coins]);
+    let d1 = d0 - mul_div(env, d0, share_amount as u128, total_shares as u128);
+
+    let new_balance_out = calc_y_for_d(env, amp, &xp[..n_coins], index, d1);
+
+    let fee_rate = imbalance_fee_rate(&config);
+    let mut xp_reduced = xp;
+    for (i, reduced) in xp_reduced.iter_mut().enumerate().take(n_coins) {
+        let expected_balance = mul_div(env, xp[i], d1, d0);
+        let expected_difference = if i == index {
+            expected_balance.saturating_sub(new_balance_out)
+        } else {
+            xp[i] - expected_balance
+        };
+        *reduced = xp[i] - mul_rate(env, expected_difference, fee_rate);
+    }
+    let new_balance_out_with_fee = calc_y_for_d(env, amp, &xp_reduced[..n_coins], index, d1);
+
+    // one unit less covers the precision of Newton's method in `calc_y_for_d`, in favour of the
+    // pool
+    let return_amount_without_fee =
+        from_xp(xp[index].saturating_sub(new_balance_out + 1), precision);
+    let return_amount = from_xp(
+        xp_reduced[index].saturating_sub(new_balance_out_with_fee + 1),
+        precision,
+    );
+
+    (return_amount, return_amount_without_fee - return_amount)
+}
+
+/// Returns the amount of pool shares burned for withdrawing `amounts` of the pool tokens,
+/// together with the fees that stay in the pool for each asset.
+///
+/// The fee is charged on the difference of the new balances from the balances the pool would have
+/// after a proportional withdrawal, and the shares are burned in proportion to the decrease of the
+/// invariant after fees.
+pub fn compute_withdraw_imbalanced(env: &Env, amounts: &Vec<i128>) -> (i128, Vec<i128>) {
+    let config = get_config(env);
+
+    let balances = utils::get_pool_balances(env);
+
+    if amounts.len() != balances.len() {
+        log!(
+            env,
+            "Pool Stable: Withdraw imbalanced: An amount must be given for each asset"
+        );
+        panic_with_error!(env, ContractError::InvalidWithdrawAmounts);
+    }
+    if balances
+        .iter()
+        .zip(amounts.iter())
+        .any(|(balance, amount)| !(0..balance).contains(&amount))
+        || amounts.iter().all(|amount| amount == 0)
+    {
+        log!(
+            env,
+            "Pool Stable: Withdraw imbalanced: Amounts must not be negative and must be smaller than the pool balances"
+        );
+        panic_with_error!(env, ContractError::InvalidWithdrawAmounts);
+    }
+
+    let total_shares = utils::get_total_shares(env);
+    if total_shares == 0 {
+        log!(
+            env,
+            "Pool Stable: Withdraw imbalanced: Critical error - Total shares are equal to zero before withdrawal!"
+        );
+        panic_with_error!(env, ContractError::TotalSharesEqualZero);
+    }
+
+    let amp = compute_current_amp(env, &get_amp(env).unwrap()) as u128;
+
+    let n_coins = balances.len() as usize;
+    let mut new_balances = Vec::new(env);
+    for (balance, amount) in balances.iter().zip(amounts.iter()) {
+        new_balances.push_back(balance - amount);
+    }
+    let precisions = get_precisions(env);
+    let old_xp = to_xp(env, &balances, &precisions);
+    let new_xp = to_xp(env, &new_balances, &precisions);
+
+    let d0 = compute_d(env, amp, &old_xp[..n_coins]);
+    let d1 = compute_d(env, amp, &new_xp[..n_coins]);
+
+    let fee_rate = imbalance_fee_rate(&config);
+    let mut fees = Vec::new(env);
+    let mut xp_after_fees = new_xp;
+    for (((x_after_fees, old_x), new_x), precision) in xp_after_fees
+        .iter_mut()
+        .zip(old_xp.iter())
+        .zip(new_xp.iter())
+        .zip(precisions.iter())
+    {
+        let expected_balance = mul_div(env, *old_x, d1, d0);
+        let fee = mul_rate(env, expected_balance.abs_diff(*new_x), fee_rate);
+        *x_after_fees = *new_x - fee;
+        fees.push_back(from_xp(fee, precision));
+    }
+    let d2 = compute_d(env, amp, &xp_after_fees[..n_coins]);
+
+    // rounded up in favour of the pool
+    let share_amount = mul_div(env, total_shares as u128, d0 - d2, d0) as i128 + 1;
+
+    (share_amount, fees)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use soroban_sdk::testutils::Ledger;
+
+    #[test]
+    fn test_assert_deadline() {
+        let env = Env::default();
+        env.ledger().with_mut(|li| li.timestamp = 100);
+
+        assert_deadline(&env, None);
+        assert_deadline(&env, Some(100));
+        assert_deadline(&env, Some(101));
+    }
+
+    #[test]
+    #[should_panic(expected = "Pool Stable: Transaction deadline has passed")]
+    fn test_assert_deadline_expired() {
+        let env = Env::default();
+        env.ledger().with_mut(|li| li.timestamp = 100);
+
+        assert_deadline(&env, Some(99));
+    }
 
     #[test]
     fn test_assert_max_spread_success() {
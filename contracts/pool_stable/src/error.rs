@@ -19,4 +19,22 @@ pub enum ContractError {
     NewtonMethodFailed = 13,
     CalcYErr = 14,
     SwapMinReceivedBiggerThanReturn = 15,
+    SwapOfferAmountBiggerThanMax = 16,
+    SwapAskAmountBiggerThanReserve = 17,
+    DeadlineExceeded = 18,
+    Paused = 19,
+    AdminChangeNotFound = 20,
+    AdminChangeExpired = 21,
+    InvalidAmp = 22,
+    MaxAmpChangeExceeded = 23,
+    MinAmpChangingTime = 24,
+    WithdrawMinAmountNotSatisfied = 25,
+    WithdrawMaxSharesExceeded = 26,
+    InvalidWithdrawAmounts = 27,
+    InvalidNumberOfTokens = 28,
+    InvalidDepositAmounts = 29,
+    ProvideLiquiditySlippageExceeded = 30,
+    WithdrawAmountExceedsTotalShares = 31,
+    ArithmeticOverflow = 32,
+    InvalidTokenDecimals = 33,
 }
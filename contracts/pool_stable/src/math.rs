@@ -1,24 +1,26 @@
-use soroban_sdk::{log, panic_with_error, Env};
+use soroban_sdk::{log, panic_with_error, Env, U256};
 
 use crate::{error::ContractError, storage::AmplifierParameters};
 
 use decimal::Decimal;
 
-// TODO: Those parameters will be used for updating AMP function later
-#[allow(dead_code)]
+/// The maximum amplification coefficient, without `AMP_PRECISION`
 pub const MAX_AMP: u64 = 1_000_000;
-#[allow(dead_code)]
+/// The maximum factor by which a single ramp can increase or decrease the amplification
 pub const MAX_AMP_CHANGE: u64 = 10;
-#[allow(dead_code)]
+/// The minimum duration of a ramp and the minimum time between two ramps, in seconds
 pub const MIN_AMP_CHANGING_TIME: u64 = 86400;
 pub const AMP_PRECISION: u64 = 100;
 
 /// The maximum number of calculation steps for Newton's method.
 const ITERATIONS: u8 = 64;
-/// N = 2
-pub const N_COINS: Decimal = Decimal::raw(2000000000000000000);
-/// 1e-6
-pub const TOL: Decimal = Decimal::raw(1000000000000);
+/// The minimum number of assets in a pool
+pub const MIN_COINS: usize = 2;
+/// The maximum number of assets in a pool
+pub const MAX_COINS: usize = 4;
+/// The decimals the pool balances are normalised to for the invariant math, which is also the
+/// greatest number of decimals a pool token can have
+pub const DECIMAL_PRECISION: u32 = 18;
 
 /// Compute the current pool amplification coefficient (AMP).
 pub(crate) fn compute_current_amp(env: &Env, amp_params: &AmplifierParameters) -> u64 {
@@ -45,32 +47,100 @@ pub(crate) fn compute_current_amp(env: &Env, amp_params: &AmplifierParameters) -
     }
 }
 
-/// Computes the stableswap invariant (D).
+/// Returns `a * b / c` rounded down. The product is computed with 256 bits, so it can exceed the
+/// range of `u128` as long as the result doesn't.
+pub(crate) fn mul_div(env: &Env, a: u128, b: u128, c: u128) -> u128 {
+    let result = U256::from_u128(env, a)
+        .mul(&U256::from_u128(env, b))
+        .div(&U256::from_u128(env, c));
+    to_u128(env, &result)
+}
+
+/// Returns `a * b / c` rounded up, see `mul_div`.
+pub(crate) fn mul_div_ceil(env: &Env, a: u128, b: u128, c: u128) -> u128 {
+    let c = U256::from_u128(env, c);
+    let result = U256::from_u128(env, a)
+        .mul(&U256::from_u128(env, b))
+        .add(&c.sub(&U256::from_u32(env, 1)))
+        .div(&c);
+    to_u128(env, &result)
+}
+
+/// Returns `amount * rate` rounded down, computed like `mul_div`.
+pub(crate) fn mul_rate(env: &Env, amount: u128, rate: Decimal) -> u128 {
+    mul_div(
+        env,
+        amount,
+        rate.atomics() as u128,
+        Decimal::one().atomics() as u128,
+    )
+}
+
+/// Returns `numerator / denominator` as a `Decimal`, computed like `mul_div`.
+pub(crate) fn ratio(env: &Env, numerator: u128, denominator: u128) -> Decimal {
+    Decimal::raw(mul_div(
+        env,
+        numerator,
+        Decimal::one().atomics() as u128,
+        denominator,
+    ) as i128)
+}
+
+fn to_u128(env: &Env, value: &U256) -> u128 {
+    value.to_u128().unwrap_or_else(|| {
+        log!(env, "Pool Stable: Math: Result does not fit in 128 bits");
+        panic_with_error!(env, ContractError::ArithmeticOverflow);
+    })
+}
+
+/// Returns whether `a` and `b` differ by at most one, which is the precision Newton's method is
+/// run with.
+fn is_converged(env: &Env, a: &U256, b: &U256) -> bool {
+    let difference = if a > b { a.sub(b) } else { b.sub(a) };
+    difference <= U256::from_u32(env, 1)
+}
+
+/// Computes the stableswap invariant (D) of a pool with `xp.len()` assets. The amounts in `xp`
+/// have to be normalised to `DECIMAL_PRECISION` and `amp` is multiplied by `AMP_PRECISION`.
 ///
 /// * **Equation**
 ///
 /// A * sum(x_i) * n**n + D = A * D * n**n + D**(n+1) / (n**n * prod(x_i))
-pub fn compute_d(env: &Env, amp: u128, pools: &[Decimal]) -> Decimal {
-    let leverage = Decimal::from_ratio(amp as i128, AMP_PRECISION) * N_COINS;
-    let amount_a_times_coins = pools[0] * N_COINS;
-    let amount_b_times_coins = pools[1] * N_COINS;
-
-    let sum_x = pools[0] + pools[1]; // sum(x_i), a.k.a S
-    if sum_x.is_zero() {
-        return Decimal::zero();
+pub fn compute_d(env: &Env, amp: u128, xp: &[u128]) -> u128 {
+    let n_coins = U256::from_u32(env, xp.len() as u32);
+    // A * n, still multiplied by `AMP_PRECISION`
+    let leverage = U256::from_u128(env, amp * xp.len() as u128);
+    let amp_precision = U256::from_u128(env, AMP_PRECISION as u128);
+
+    // sum(x_i), a.k.a S
+    let sum_x = xp.iter().fold(U256::from_u32(env, 0), |sum, x| {
+        sum.add(&U256::from_u128(env, *x))
+    });
+    if sum_x == U256::from_u32(env, 0) {
+        return 0;
     }
 
-    let mut d_previous: Decimal;
-    let mut d: Decimal = sum_x;
+    let mut d = sum_x.clone();
 
     // Newton's method to approximate D
     for _ in 0..ITERATIONS {
-        let d_product = d.pow(3) / (amount_a_times_coins * amount_b_times_coins);
-        d_previous = d;
-        d = calculate_step(d, leverage, sum_x, d_product);
-        // Equality with the precision of 1e-6
-        if (d - d_previous).abs() <= TOL {
-            return d;
+        // D**(n+1) / (n**n * prod(x_i)), one asset at a time to keep the values small
+        let d_product = xp.iter().fold(d.clone(), |d_product, x| {
+            d_product
+                .mul(&d)
+                .div(&U256::from_u128(env, *x).mul(&n_coins))
+        });
+        let d_previous = d;
+        d = calculate_step(
+            &d_previous,
+            &leverage,
+            &amp_precision,
+            &sum_x,
+            &d_product,
+            &n_coins,
+        );
+        if is_converged(env, &d, &d_previous) {
+            return to_u128(env, &d);
         }
     }
 
@@ -87,52 +157,88 @@ pub fn compute_d(env: &Env, amp: u128, pools: &[Decimal]) -> Decimal {
 ///
 /// d = (leverage * sum_x + d_product * n_coins) * initial_d / ((leverage - 1) * initial_d + (n_coins + 1) * d_product)
 fn calculate_step(
-    initial_d: Decimal,
-    leverage: Decimal,
-    sum_x: Decimal,
-    d_product: Decimal,
-) -> Decimal {
-    let leverage_mul = leverage * sum_x;
-    let d_p_mul = d_product * N_COINS;
+    initial_d: &U256,
+    leverage: &U256,
+    amp_precision: &U256,
+    sum_x: &U256,
+    d_product: &U256,
+    n_coins: &U256,
+) -> U256 {
+    let leverage_mul = leverage.mul(sum_x).div(amp_precision);
+    let d_p_mul = d_product.mul(n_coins);
 
-    let l_val = leverage_mul + d_p_mul * initial_d;
-    let leverage_sub = initial_d * (leverage - Decimal::one());
-    let n_coins_sum = d_product * (N_COINS + Decimal::one());
+    let l_val = leverage_mul.add(&d_p_mul).mul(initial_d);
+    let leverage_sub = leverage
+        .sub(amp_precision)
+        .mul(initial_d)
+        .div(amp_precision);
+    let n_coins_sum = d_product.mul(&n_coins.add(&U256::from_u32(n_coins.env(), 1)));
 
-    let r_val = leverage_sub + n_coins_sum;
+    let r_val = leverage_sub.add(&n_coins_sum);
 
-    l_val / r_val
+    l_val.div(&r_val)
 }
 
-/// Compute the swap amount `y` in proportion to `x`.
+/// Compute the new amount of the asset at `ask_index` after the asset at `offer_index` changes
+/// to `new_amount`, keeping the invariant of the pool `xp`. All amounts are normalised to
+/// `DECIMAL_PRECISION`.
+pub(crate) fn calc_y(
+    env: &Env,
+    amp: u128,
+    new_amount: u128,
+    xp: &[u128],
+    offer_index: usize,
+    ask_index: usize,
+) -> u128 {
+    let d = compute_d(env, amp, xp);
+
+    let mut new_xp = [0u128; MAX_COINS];
+    new_xp[..xp.len()].copy_from_slice(xp);
+    new_xp[offer_index] = new_amount;
+
+    calc_y_for_d(env, amp, &new_xp[..xp.len()], ask_index, d)
+}
+
+/// Compute the amount `y` of the asset at `index`, so that the pool invariant equals `d` given
+/// the amounts of all the other assets in `xp`. The amount at `index` in `xp` is ignored. The
+/// result is within one unit of `DECIMAL_PRECISION` of the exact solution.
 ///
 /// * **Solve for y**
 ///
 /// y**2 + y * (sum' - (A*n**n - 1) * D / (A * n**n)) = D ** (n + 1) / (n ** (2 * n) * prod' * A)
 ///
 /// y**2 + b*y = c
-pub(crate) fn calc_y(
-    env: &Env,
-    amp: u128,
-    new_amount: Decimal,
-    xp: &[Decimal],
-    target_precision: u8,
-) -> i128 {
-    let d = compute_d(env, amp, xp);
-    let leverage = Decimal::from_ratio(amp as i128, 1u8) * N_COINS;
-    let amp_prec = Decimal::from_ratio(AMP_PRECISION, 1u8);
+pub(crate) fn calc_y_for_d(env: &Env, amp: u128, xp: &[u128], index: usize, d: u128) -> u128 {
+    let n_coins = U256::from_u32(env, xp.len() as u32);
+    // A * n, still multiplied by `AMP_PRECISION`
+    let leverage = U256::from_u128(env, amp * xp.len() as u128);
+    let amp_precision = U256::from_u128(env, AMP_PRECISION as u128);
+    let d = U256::from_u128(env, d);
 
-    let c = d.pow(3) * amp_prec / (new_amount * N_COINS * N_COINS * leverage);
-    let b = new_amount + d * amp_prec / leverage;
+    // sum' and D**(n+1) / (n**n * prod') over all the other assets
+    let mut sum = U256::from_u32(env, 0);
+    let mut c = d.clone();
+    for (i, amount) in xp.iter().enumerate() {
+        if i == index {
+            continue;
+        }
+        let amount = U256::from_u128(env, *amount);
+        sum = sum.add(&amount);
+        c = c.mul(&d).div(&amount.mul(&n_coins));
+    }
+    let c = c.mul(&d).mul(&amp_precision).div(&leverage.mul(&n_coins));
+    let b = sum.add(&d.mul(&amp_precision).div(&leverage));
 
     // Solve for y by approximating: y**2 + b*y = c
-    let mut y_prev;
-    let mut y = d;
+    let mut y = d.clone();
     for _ in 0..ITERATIONS {
-        y_prev = y;
-        y = (y.pow(2) + c) / (y * N_COINS + b - d);
-        if (y - y_prev).abs() <= TOL {
-            return y.to_i128_with_precision(target_precision);
+        let y_prev = y.clone();
+        y = y
+            .mul(&y)
+            .add(&c)
+            .div(&y.mul(&U256::from_u32(env, 2)).add(&b).sub(&d));
+        if is_converged(env, &y, &y_prev) {
+            return to_u128(env, &y);
         }
     }
 
@@ -140,3 +246,154 @@ pub(crate) fn calc_y(
     log!(&env, "Pool Stable: calc_y: y is not converging");
     panic_with_error!(&env, ContractError::CalcYErr);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One whole token normalised to `DECIMAL_PRECISION`
+    const ONE: u128 = 10u128.pow(DECIMAL_PRECISION);
+
+    fn assert_close(actual: u128, expected: u128) {
+        assert!(
+            actual.abs_diff(expected) <= 1,
+            "{actual} is not within one unit of {expected}"
+        );
+    }
+
+    #[test]
+    fn compute_d_of_balanced_pool_is_the_sum_of_the_assets() {
+        let env = Env::default();
+
+        assert_eq!(compute_d(&env, 1_000, &[ONE / 10, ONE / 10]), ONE / 5);
+        assert_eq!(
+            compute_d(&env, 10_000, &[1_000_000 * ONE, 1_000_000 * ONE]),
+            2_000_000 * ONE
+        );
+    }
+
+    #[test]
+    fn compute_d_of_imbalanced_pools() {
+        let env = Env::default();
+
+        // reference values solved from the invariant with a bisection
+        assert_close(
+            compute_d(&env, 1_000, &[ONE / 100, ONE / 50]),
+            29_832_261_030_558_442,
+        );
+        assert_close(
+            compute_d(&env, 600, &[ONE / 10, 3 * ONE / 10]),
+            391_058_204_499_697_745,
+        );
+        assert_close(
+            compute_d(&env, 10_000, &[1_000_000 * ONE, 3_000_000 * ONE]),
+            3_993_431_643_088_518_257_649_020,
+        );
+        assert_close(
+            compute_d(
+                &env,
+                100,
+                &[1_000_000_000_000 * ONE, 1_000_000_000_000 * ONE / 3],
+            ),
+            1_239_259_381_247_494_541_934_721_019_093,
+        );
+    }
+
+    #[test]
+    fn compute_d_of_three_and_four_coin_pools() {
+        let env = Env::default();
+
+        assert_close(
+            compute_d(
+                &env,
+                2_000,
+                &[1_000_000 * ONE, 2_000_000 * ONE, 500_000 * ONE],
+            ),
+            3_468_469_746_287_237_843_693_124,
+        );
+        assert_close(
+            compute_d(
+                &env,
+                2_000,
+                &[
+                    1_000_000 * ONE,
+                    2_000_000 * ONE,
+                    500_000 * ONE,
+                    4_000_000 * ONE,
+                ],
+            ),
+            7_332_854_115_868_929_932_502_349,
+        );
+    }
+
+    #[test]
+    fn calc_y_keeps_the_invariant_of_two_coin_pools() {
+        let env = Env::default();
+
+        // the ask pool of 0.1 drops to 0.099000908340145948 when 0.001 is swapped into a
+        // balanced pool
+        let pools = [ONE / 10, ONE / 10];
+        assert_close(
+            calc_y(&env, 1_000, 101 * ONE / 1_000, &pools, 0, 1),
+            99_000_908_340_145_948,
+        );
+        assert_close(
+            calc_y(&env, 1_000, 11 * ONE / 100, &pools, 0, 1),
+            90_090_826_596_203_312,
+        );
+
+        // the offer asset is the second one of an imbalanced pool
+        let pools = [ONE / 10, 3 * ONE / 10];
+        assert_close(
+            calc_y(&env, 600, 301 * ONE / 1_000, &pools, 1, 0),
+            99_197_066_908_519_315,
+        );
+
+        // 1_000 tokens swapped into a pool of a million tokens on each side
+        let pools = [1_000_000 * ONE, 1_000_000 * ONE];
+        assert_close(
+            calc_y(&env, 10_000, 1_001_000 * ONE, &pools, 0, 1),
+            999_000_009_900_901_775_998_151,
+        );
+    }
+
+    #[test]
+    fn calc_y_keeps_the_invariant_of_three_and_four_coin_pools() {
+        let env = Env::default();
+
+        let pools = [1_000_000 * ONE, 2_000_000 * ONE, 500_000 * ONE];
+        assert_close(
+            calc_y(&env, 2_000, 510_000 * ONE, &pools, 2, 0),
+            989_204_160_716_893_893_989_369,
+        );
+
+        let pools = [
+            1_000_000 * ONE,
+            2_000_000 * ONE,
+            500_000 * ONE,
+            4_000_000 * ONE,
+        ];
+        assert_close(
+            calc_y(&env, 2_000, 4_100_000 * ONE, &pools, 3, 1),
+            1_906_123_037_809_469_918_066_148,
+        );
+    }
+
+    #[test]
+    fn mul_div_exceeds_128_bits_in_between() {
+        let env = Env::default();
+
+        assert_eq!(
+            mul_div(&env, 1_000_000 * ONE, 3_000_000 * ONE, 2_000_000 * ONE),
+            1_500_000 * ONE
+        );
+        assert_eq!(
+            mul_div_ceil(&env, 10 * ONE, ONE, 3 * ONE),
+            3_333_333_333_333_333_334
+        );
+        assert_eq!(
+            mul_rate(&env, 1_000_000 * ONE, Decimal::bps(30)),
+            3_000 * ONE
+        );
+    }
+}
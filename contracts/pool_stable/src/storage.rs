@@ -1,6 +1,6 @@
 use soroban_sdk::{
     contracttype, log, panic_with_error, symbol_short, xdr::ToXdr, Address, Bytes, BytesN,
-    ConversionError, Env, Symbol, TryFromVal, Val,
+    ConversionError, Env, Symbol, TryFromVal, Val, Vec,
 };
 
 use crate::{error::ContractError, token_contract};
@@ -10,12 +10,18 @@ use decimal::Decimal;
 #[repr(u32)]
 pub enum DataKey {
     TotalShares = 0,
+    /// Reserve of the first token of pools deployed before they held more than two tokens
     ReserveA = 1,
+    /// Reserve of the second token of pools deployed before they held more than two tokens
     ReserveB = 2,
     Admin = 3,
     Initialized = 4,
     Amp = 5,
     MaxPrecision = 6,
+    Factory = 7,
+    AdminChange = 8,
+    Reserves = 9,
+    Precisions = 10,
 }
 
 impl TryFromVal<Env, DataKey> for Val {
@@ -31,13 +37,14 @@ impl TryFromVal<Env, DataKey> for Val {
 #[repr(u32)]
 pub enum PairType {
     Xyk = 0,
+    Stable = 1,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Config {
-    pub token_a: Address,
-    pub token_b: Address,
+    /// The assets of the pool, sorted by address
+    pub tokens: Vec<Address>,
     pub share_token: Address,
     pub stake_contract: Address,
     pub pool_type: PairType,
@@ -49,6 +56,8 @@ pub struct Config {
     pub max_allowed_slippage_bps: i64,
     /// The maximum amount of spread (in bps) that is tolerated during swap
     pub max_allowed_spread_bps: i64,
+    /// While paused, swaps and deposits are rejected. Withdrawals are still allowed
+    pub paused: bool,
 }
 const CONFIG: Symbol = symbol_short!("CONFIG");
 
@@ -94,19 +103,37 @@ pub fn get_greatest_precision(env: &Env) -> i32 {
         .unwrap()
 }
 
-pub fn save_greatest_precision(env: &Env, token1: &Address, token2: &Address) {
-    let precision1 = token_contract::Client::new(env, token1).decimals();
-    let precision2 = token_contract::Client::new(env, token2).decimals();
-    let max_precision: u32 = if precision1 > precision2 {
-        precision1
-    } else {
-        precision2
-    };
+/// Saves the decimals of every token in the pool together with the greatest of them
+pub fn save_greatest_precision(env: &Env, tokens: &Vec<Address>) {
+    let mut precisions: Vec<u32> = Vec::new(env);
+    for token in tokens.iter() {
+        precisions.push_back(token_contract::Client::new(env, &token).decimals());
+    }
+    let max_precision: u32 = precisions.iter().max().unwrap_or(0);
+
+    env.storage()
+        .instance()
+        .set(&DataKey::Precisions, &precisions);
     env.storage()
         .instance()
         .set(&DataKey::MaxPrecision, &i32::try_from(max_precision).ok());
 }
 
+/// Returns the decimals of every token in the pool, in the order of the pool tokens.
+/// Pools deployed before the decimals were stored read them from the tokens.
+pub fn get_precisions(env: &Env) -> Vec<u32> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Precisions)
+        .unwrap_or_else(|| {
+            let mut precisions: Vec<u32> = Vec::new(env);
+            for token in get_config(env).tokens.iter() {
+                precisions.push_back(token_contract::Client::new(env, &token).decimals());
+            }
+            precisions
+        })
+}
+
 #[contracttype]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct AmplifierParameters {
@@ -116,6 +143,15 @@ pub struct AmplifierParameters {
     pub next_amp_time: u64,
 }
 
+/// The amplification parameters together with the amplification coefficient at the current
+/// ledger timestamp. All values are multiplied by `AMP_PRECISION`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AmpResponse {
+    pub amp_params: AmplifierParameters,
+    pub current_amp: u64,
+}
+
 pub fn get_amp(env: &Env) -> Option<AmplifierParameters> {
     env.storage().instance().get(&DataKey::Amp)
 }
@@ -137,10 +173,8 @@ pub struct Asset {
 #[contracttype]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PoolResponse {
-    /// The asset A in the pool together with asset amounts
-    pub asset_a: Asset,
-    /// The asset B in the pool together with asset amounts
-    pub asset_b: Asset,
+    /// The assets in the pool together with asset amounts
+    pub assets: Vec<Asset>,
     /// The total amount of LP tokens currently issued
     pub asset_lp_share: Asset,
     /// The address of the Stake contract for the liquidity pool
@@ -172,18 +206,49 @@ pub struct SimulateReverseSwapResponse {
     pub spread_amount: i128,
 }
 
+/// The result of a simulated deposit of liquidity.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimulateProvideLiquidityResponse {
+    /// The pool tokens together with the amounts that would be deposited
+    pub assets: Vec<Asset>,
+    /// The amount of LP tokens minted to the depositor
+    pub share_amount: i128,
+    /// The part of the deposit value, counting every token 1:1, lost to the imbalance of the
+    /// deposit, in bps rounded up
+    pub slippage_bps: i64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimulateWithdrawOneCoinResponse {
+    pub return_amount: i128,
+    /// The fee that stays in the pool for the imbalanced part of the withdrawal
+    pub fee_amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimulateWithdrawImbalancedResponse {
+    pub share_amount: i128,
+    /// The fees that stay in the pool for the imbalanced part of the withdrawal, in the order of
+    /// the pool tokens
+    pub fee_amounts: Vec<i128>,
+}
+
 pub mod utils {
     use super::*;
+    use phoenix::utils::AdminChange;
 
     pub fn deploy_token_contract(
         e: &Env,
         token_wasm_hash: BytesN<32>,
-        token_a: &Address,
-        token_b: &Address,
+        tokens: &Vec<Address>,
     ) -> Address {
         let mut salt = Bytes::new(e);
-        salt.append(&token_a.to_xdr(e));
-        salt.append(&token_b.to_xdr(e));
+        for token in tokens.iter() {
+            salt.append(&token.to_xdr(e));
+        }
         let salt = e.crypto().sha256(&salt);
         e.deployer()
             .with_current_contract(salt)
@@ -203,16 +268,26 @@ pub mod utils {
         e.storage().instance().set(&DataKey::Admin, &address)
     }
 
-    pub fn save_total_shares(e: &Env, amount: i128) {
-        e.storage().instance().set(&DataKey::TotalShares, &amount)
+    pub fn save_admin_change(e: &Env, admin_change: &AdminChange) {
+        e.storage()
+            .instance()
+            .set(&DataKey::AdminChange, admin_change)
+    }
+
+    pub fn remove_admin_change(e: &Env) {
+        e.storage().instance().remove(&DataKey::AdminChange)
+    }
+
+    pub fn save_factory(e: &Env, address: Address) {
+        e.storage().instance().set(&DataKey::Factory, &address)
     }
 
-    pub fn save_pool_balance_a(e: &Env, amount: i128) {
-        e.storage().instance().set(&DataKey::ReserveA, &amount)
+    pub fn save_total_shares(e: &Env, amount: i128) {
+        e.storage().instance().set(&DataKey::TotalShares, &amount)
     }
 
-    pub fn save_pool_balance_b(e: &Env, amount: i128) {
-        e.storage().instance().set(&DataKey::ReserveB, &amount)
+    pub fn save_pool_balances(e: &Env, balances: &Vec<i128>) {
+        e.storage().instance().set(&DataKey::Reserves, balances)
     }
 
     pub fn mint_shares(e: &Env, share_token: &Address, to: &Address, amount: i128) {
@@ -236,15 +311,48 @@ pub mod utils {
         e.storage().instance().get(&DataKey::Admin).unwrap()
     }
 
+    pub fn get_admin_change(e: &Env) -> Option<AdminChange> {
+        e.storage().instance().get(&DataKey::AdminChange)
+    }
+
+    pub fn get_factory(e: &Env) -> Address {
+        e.storage().instance().get(&DataKey::Factory).unwrap()
+    }
+
     pub fn get_total_shares(e: &Env) -> i128 {
         e.storage().instance().get(&DataKey::TotalShares).unwrap()
     }
-    pub fn get_pool_balance_a(e: &Env) -> i128 {
-        e.storage().instance().get(&DataKey::ReserveA).unwrap()
+    /// Returns the stored reserves, in the order of the pool tokens.
+    /// Pools deployed before they held more than two tokens keep them under the legacy keys.
+    pub fn get_pool_balances(e: &Env) -> Vec<i128> {
+        e.storage()
+            .instance()
+            .get(&DataKey::Reserves)
+            .unwrap_or_else(|| get_legacy_pool_balances(e).unwrap())
     }
 
-    pub fn get_pool_balance_b(e: &Env) -> i128 {
-        e.storage().instance().get(&DataKey::ReserveB).unwrap()
+    fn get_legacy_pool_balances(e: &Env) -> Option<Vec<i128>> {
+        let reserve_a: i128 = e.storage().instance().get(&DataKey::ReserveA)?;
+        let reserve_b: i128 = e.storage().instance().get(&DataKey::ReserveB)?;
+        Some(soroban_sdk::vec![e, reserve_a, reserve_b])
+    }
+
+    /// Moves the reserves and the decimals of a pool deployed before they held more than two
+    /// tokens to the current keys and removes the legacy keys.
+    pub fn migrate_legacy_storage(e: &Env) {
+        if let Some(balances) = get_legacy_pool_balances(e) {
+            if !e.storage().instance().has(&DataKey::Reserves) {
+                save_pool_balances(e, &balances);
+            }
+            e.storage().instance().remove(&DataKey::ReserveA);
+            e.storage().instance().remove(&DataKey::ReserveB);
+        }
+        if !e.storage().instance().has(&DataKey::Precisions) {
+            let precisions = get_precisions(e);
+            e.storage()
+                .instance()
+                .set(&DataKey::Precisions, &precisions);
+        }
     }
 
     pub fn get_balance(e: &Env, contract: &Address) -> i128 {
@@ -283,16 +391,33 @@ mod tests {
 
     #[test]
     #[should_panic]
-    fn test_get_pool_balance_a_failure() {
+    fn test_get_pool_balances_failure() {
         let env = Env::default();
-        let _ = utils::get_pool_balance_a(&env);
+        let _ = utils::get_pool_balances(&env);
     }
 
     #[test]
-    #[should_panic]
-    fn test_get_pool_balance_b_failure() {
+    fn test_legacy_pool_balances_are_read_and_migrated() {
         let env = Env::default();
-        let _ = utils::get_pool_balance_b(&env);
+        let contract_id = env.register_contract(None, crate::contract::StableLiquidityPool {});
+
+        env.as_contract(&contract_id, || {
+            env.storage().instance().set(&DataKey::ReserveA, &100i128);
+            env.storage().instance().set(&DataKey::ReserveB, &200i128);
+            let precisions: Vec<u32> = soroban_sdk::vec![&env, 7, 18];
+            env.storage()
+                .instance()
+                .set(&DataKey::Precisions, &precisions);
+
+            let expected = soroban_sdk::vec![&env, 100i128, 200i128];
+            assert_eq!(utils::get_pool_balances(&env), expected);
+
+            utils::migrate_legacy_storage(&env);
+            assert_eq!(utils::get_pool_balances(&env), expected);
+            assert!(!env.storage().instance().has(&DataKey::ReserveA));
+            assert!(!env.storage().instance().has(&DataKey::ReserveB));
+            assert_eq!(get_precisions(&env), precisions);
+        });
     }
 
     #[test]
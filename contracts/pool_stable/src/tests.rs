@@ -1,6 +1,10 @@
 mod config;
+mod decimals;
+mod exact_out;
 // mod liquidity;
+mod multi_asset;
 mod setup;
 mod stake_deployment;
+mod withdraw;
 // mod swap;
 // mod queries;
@@ -1,8 +1,11 @@
 extern crate std;
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env,
+};
 
 use super::setup::{deploy_stable_liquidity_pool_contract, deploy_token_contract};
-use crate::storage::{Config, PairType};
+use crate::storage::{AmpResponse, AmplifierParameters, Asset, Config, PairType};
 
 #[test]
 fn update_config() {
@@ -26,7 +29,7 @@ fn update_config() {
     let pool = deploy_stable_liquidity_pool_contract(
         &env,
         Some(admin1.clone()),
-        (&token1.address, &token2.address),
+        &[&token1.address, &token2.address],
         swap_fees,
         user1.clone(),
         500,
@@ -41,22 +44,21 @@ fn update_config() {
     assert_eq!(
         pool.query_config(),
         Config {
-            token_a: token1.address.clone(),
-            token_b: token2.address.clone(),
+            tokens: vec![&env, token1.address.clone(), token2.address.clone()],
             share_token: share_token_address.clone(),
             stake_contract: stake_token_address.clone(),
-            pool_type: PairType::Xyk,
+            pool_type: PairType::Stable,
             total_fee_bps: 0,
             fee_recipient: user1,
             max_allowed_slippage_bps: 500,
             max_allowed_spread_bps: 200,
+            paused: false,
         }
     );
 
     // update fees and recipient
     pool.update_config(
         &admin1,
-        &None,
         &Some(500i64), // 5% fees
         &Some(admin2.clone()),
         &None,
@@ -65,32 +67,32 @@ fn update_config() {
     assert_eq!(
         pool.query_config(),
         Config {
-            token_a: token1.address.clone(),
-            token_b: token2.address.clone(),
+            tokens: vec![&env, token1.address.clone(), token2.address.clone()],
             share_token: share_token_address.clone(),
             stake_contract: stake_token_address.clone(),
-            pool_type: PairType::Xyk,
+            pool_type: PairType::Stable,
             total_fee_bps: 500,
             fee_recipient: admin2.clone(),
             max_allowed_slippage_bps: 500,
             max_allowed_spread_bps: 200,
+            paused: false,
         }
     );
 
     // update slippage and spread
-    pool.update_config(&admin1, &None, &None, &None, &Some(5_000i64), &Some(500));
+    pool.update_config(&admin1, &None, &None, &Some(5_000i64), &Some(500));
     assert_eq!(
         pool.query_config(),
         Config {
-            token_a: token1.address.clone(),
-            token_b: token2.address,
+            tokens: vec![&env, token1.address.clone(), token2.address],
             share_token: share_token_address,
             stake_contract: stake_token_address,
-            pool_type: PairType::Xyk,
+            pool_type: PairType::Stable,
             total_fee_bps: 500,
             fee_recipient: admin2,
             max_allowed_slippage_bps: 5_000,
             max_allowed_spread_bps: 500,
+            paused: false,
         }
     );
 }
@@ -117,7 +119,7 @@ fn update_config_unauthorized() {
     let pool = deploy_stable_liquidity_pool_contract(
         &env,
         Some(admin1.clone()),
-        (&token1.address, &token2.address),
+        &[&token1.address, &token2.address],
         swap_fees,
         user1,
         500,
@@ -128,7 +130,6 @@ fn update_config_unauthorized() {
 
     pool.update_config(
         &Address::generate(&env),
-        &None,
         &Some(500i64), // 5% fees
         &Some(admin2.clone()),
         &None,
@@ -136,6 +137,37 @@ fn update_config_unauthorized() {
     );
 }
 
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn update_config_without_admin_signature_should_fail() {
+    let env = Env::default();
+
+    let mut admin1 = Address::generate(&env);
+    let mut admin2 = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    let mut token1 = deploy_token_contract(&env, &admin1);
+    let mut token2 = deploy_token_contract(&env, &admin2);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+        std::mem::swap(&mut admin1, &mut admin2);
+    }
+    let pool = deploy_stable_liquidity_pool_contract(
+        &env,
+        Some(admin1.clone()),
+        &[&token1.address, &token2.address],
+        0i64,
+        user1,
+        500,
+        200,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    // passing the admin address is not enough, the admin has to sign the call
+    pool.update_config(&admin1, &Some(500i64), &Some(admin2), &None, &None);
+}
+
 #[test]
 fn update_config_update_admin() {
     let env = Env::default();
@@ -158,7 +190,7 @@ fn update_config_update_admin() {
     let pool = deploy_stable_liquidity_pool_contract(
         &env,
         Some(admin1.clone()),
-        (&token1.address, &token2.address),
+        &[&token1.address, &token2.address],
         swap_fees,
         user1.clone(),
         500,
@@ -167,26 +199,31 @@ fn update_config_update_admin() {
         factory,
     );
 
-    // update admin to new admin
-    pool.update_config(&admin1, &Some(admin2.clone()), &None, &None, &None, &None);
+    // the admin is only changed through a proposal accepted by the new admin
+    pool.propose_admin(&admin1, &admin2, &1_000);
+    pool.accept_admin(&admin2);
 
     let share_token_address = pool.query_share_token_address();
     let stake_token_address = pool.query_stake_contract_address();
 
+    assert!(pool
+        .try_update_config(&admin1, &None, &None, &None, &Some(3_000_000))
+        .is_err());
+
     // now update succeeds
-    pool.update_config(&admin2, &None, &None, &None, &None, &Some(3_000_000));
+    pool.update_config(&admin2, &None, &None, &None, &Some(3_000_000));
     assert_eq!(
         pool.query_config(),
         Config {
-            token_a: token1.address.clone(),
-            token_b: token2.address,
+            tokens: vec![&env, token1.address.clone(), token2.address],
             share_token: share_token_address,
             stake_contract: stake_token_address,
-            pool_type: PairType::Xyk,
+            pool_type: PairType::Stable,
             total_fee_bps: 0,
             fee_recipient: user1,
             max_allowed_slippage_bps: 500,
             max_allowed_spread_bps: 3_000_000,
+            paused: false,
         }
     );
 }
@@ -213,7 +250,7 @@ fn update_config_too_high_fees() {
     let pool = deploy_stable_liquidity_pool_contract(
         &env,
         Some(admin1.clone()),
-        (&token1.address, &token2.address),
+        &[&token1.address, &token2.address],
         swap_fees,
         user1,
         500,
@@ -225,10 +262,439 @@ fn update_config_too_high_fees() {
     // update fees and recipient
     pool.update_config(
         &admin1,
-        &None,
         &Some(10_100i64), // 101% fees
         &Some(admin2.clone()),
         &None,
         &None,
     );
 }
+
+#[test]
+fn skim_sends_excess_tokens_to_recipient() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let mut admin1 = Address::generate(&env);
+    let mut admin2 = Address::generate(&env);
+
+    let mut token1 = deploy_token_contract(&env, &admin1);
+    let mut token2 = deploy_token_contract(&env, &admin2);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+        std::mem::swap(&mut admin1, &mut admin2);
+    }
+    let recipient = Address::generate(&env);
+    let pool = deploy_stable_liquidity_pool_contract(
+        &env,
+        Some(admin1.clone()),
+        &[&token1.address, &token2.address],
+        0i64,
+        Address::generate(&env),
+        500,
+        200,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    // tokens sent by mistake to the pool
+    token1.mint(&pool.address, &1_000);
+    token2.mint(&pool.address, &2_000);
+    assert_eq!(
+        pool.query_reserve_drift(),
+        vec![
+            &env,
+            Asset {
+                address: token1.address.clone(),
+                amount: 1_000
+            },
+            Asset {
+                address: token2.address.clone(),
+                amount: 2_000
+            }
+        ]
+    );
+
+    pool.skim(&recipient);
+
+    assert_eq!(token1.balance(&recipient), 1_000);
+    assert_eq!(token2.balance(&recipient), 2_000);
+    assert_eq!(pool.query_reserve_drift().get(0).unwrap().amount, 0);
+    assert_eq!(pool.query_reserve_drift().get(1).unwrap().amount, 0);
+}
+
+#[test]
+#[should_panic(expected = "Pool Stable: Sync: Pool has no liquidity to sync")]
+fn sync_empty_pool_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let mut admin1 = Address::generate(&env);
+    let mut admin2 = Address::generate(&env);
+
+    let mut token1 = deploy_token_contract(&env, &admin1);
+    let mut token2 = deploy_token_contract(&env, &admin2);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+        std::mem::swap(&mut admin1, &mut admin2);
+    }
+    let pool = deploy_stable_liquidity_pool_contract(
+        &env,
+        Some(admin1),
+        &[&token1.address, &token2.address],
+        0i64,
+        Address::generate(&env),
+        500,
+        200,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    token1.mint(&pool.address, &1_000);
+
+    pool.sync();
+}
+
+#[test]
+fn pause_and_unpause_by_admin_and_factory() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let mut admin1 = Address::generate(&env);
+    let mut admin2 = Address::generate(&env);
+
+    let mut token1 = deploy_token_contract(&env, &admin1);
+    let mut token2 = deploy_token_contract(&env, &admin2);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+        std::mem::swap(&mut admin1, &mut admin2);
+    }
+    let factory = Address::generate(&env);
+    let pool = deploy_stable_liquidity_pool_contract(
+        &env,
+        Some(admin1.clone()),
+        &[&token1.address, &token2.address],
+        0i64,
+        Address::generate(&env),
+        500,
+        200,
+        Address::generate(&env),
+        factory.clone(),
+    );
+
+    pool.pause(&admin1);
+    assert!(pool.query_config().paused);
+    pool.unpause(&admin1);
+    assert!(!pool.query_config().paused);
+
+    pool.pause(&factory);
+    assert!(pool.query_config().paused);
+    pool.unpause(&factory);
+    assert!(!pool.query_config().paused);
+}
+
+#[test]
+#[should_panic(expected = "Pool Stable: Pool is paused")]
+fn swap_when_paused_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let mut admin1 = Address::generate(&env);
+    let mut admin2 = Address::generate(&env);
+
+    let mut token1 = deploy_token_contract(&env, &admin1);
+    let mut token2 = deploy_token_contract(&env, &admin2);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+        std::mem::swap(&mut admin1, &mut admin2);
+    }
+    let pool = deploy_stable_liquidity_pool_contract(
+        &env,
+        Some(admin1.clone()),
+        &[&token1.address, &token2.address],
+        0i64,
+        Address::generate(&env),
+        500,
+        200,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    pool.pause(&admin1);
+    pool.swap(
+        &Address::generate(&env),
+        &token1.address,
+        &token2.address,
+        &1_000,
+        &None,
+        &None,
+        &None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Pool Stable: Pause: Only the admin or the factory can pause the pool")]
+fn pause_by_unauthorized_user_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let mut admin1 = Address::generate(&env);
+    let mut admin2 = Address::generate(&env);
+
+    let mut token1 = deploy_token_contract(&env, &admin1);
+    let mut token2 = deploy_token_contract(&env, &admin2);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+        std::mem::swap(&mut admin1, &mut admin2);
+    }
+    let pool = deploy_stable_liquidity_pool_contract(
+        &env,
+        Some(admin1),
+        &[&token1.address, &token2.address],
+        0i64,
+        Address::generate(&env),
+        500,
+        200,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    pool.pause(&Address::generate(&env));
+}
+
+#[test]
+fn propose_and_accept_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let mut admin1 = Address::generate(&env);
+    let mut admin2 = Address::generate(&env);
+
+    let mut token1 = deploy_token_contract(&env, &admin1);
+    let mut token2 = deploy_token_contract(&env, &admin2);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+        std::mem::swap(&mut admin1, &mut admin2);
+    }
+    let new_admin = Address::generate(&env);
+    let pool = deploy_stable_liquidity_pool_contract(
+        &env,
+        Some(admin1.clone()),
+        &[&token1.address, &token2.address],
+        0i64,
+        Address::generate(&env),
+        500,
+        200,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    pool.propose_admin(&admin1, &new_admin, &1_000);
+    pool.accept_admin(&new_admin);
+
+    assert!(pool.try_pause(&admin1).is_err());
+    pool.pause(&new_admin);
+    assert!(pool.query_config().paused);
+}
+
+#[test]
+#[should_panic(expected = "Pool Stable: Accept admin: Admin change proposal has expired")]
+fn accept_expired_admin_proposal_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let mut admin1 = Address::generate(&env);
+    let mut admin2 = Address::generate(&env);
+
+    let mut token1 = deploy_token_contract(&env, &admin1);
+    let mut token2 = deploy_token_contract(&env, &admin2);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+        std::mem::swap(&mut admin1, &mut admin2);
+    }
+    let new_admin = Address::generate(&env);
+    let pool = deploy_stable_liquidity_pool_contract(
+        &env,
+        Some(admin1.clone()),
+        &[&token1.address, &token2.address],
+        0i64,
+        Address::generate(&env),
+        500,
+        200,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    pool.propose_admin(&admin1, &new_admin, &1_000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1_001;
+    });
+
+    pool.accept_admin(&new_admin);
+}
+
+#[test]
+fn ramp_amp_and_stop_ramp_amp() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let mut admin1 = Address::generate(&env);
+    let mut admin2 = Address::generate(&env);
+
+    let mut token1 = deploy_token_contract(&env, &admin1);
+    let mut token2 = deploy_token_contract(&env, &admin2);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+        std::mem::swap(&mut admin1, &mut admin2);
+    }
+    // the pool is deployed with an amplification coefficient of 6
+    let pool = deploy_stable_liquidity_pool_contract(
+        &env,
+        Some(admin1.clone()),
+        &[&token1.address, &token2.address],
+        0i64,
+        Address::generate(&env),
+        500,
+        200,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 86_400);
+    pool.ramp_amp(&12, &259_200);
+
+    assert_eq!(
+        pool.query_amp(),
+        AmpResponse {
+            amp_params: AmplifierParameters {
+                init_amp: 600,
+                init_amp_time: 86_400,
+                next_amp: 1_200,
+                next_amp_time: 259_200,
+            },
+            current_amp: 600,
+        }
+    );
+
+    // halfway through the ramp
+    env.ledger().with_mut(|li| li.timestamp = 172_800);
+    assert_eq!(pool.query_amp().current_amp, 900);
+
+    pool.stop_ramp_amp();
+    env.ledger().with_mut(|li| li.timestamp = 259_200);
+    assert_eq!(
+        pool.query_amp(),
+        AmpResponse {
+            amp_params: AmplifierParameters {
+                init_amp: 900,
+                init_amp_time: 172_800,
+                next_amp: 900,
+                next_amp_time: 172_800,
+            },
+            current_amp: 900,
+        }
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "Pool Stable: Ramp amp: Amplification coefficient can change at most MAX_AMP_CHANGE times"
+)]
+fn ramp_amp_with_too_big_change_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let mut admin1 = Address::generate(&env);
+    let mut admin2 = Address::generate(&env);
+
+    let mut token1 = deploy_token_contract(&env, &admin1);
+    let mut token2 = deploy_token_contract(&env, &admin2);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+        std::mem::swap(&mut admin1, &mut admin2);
+    }
+    // the pool is deployed with an amplification coefficient of 6
+    let pool = deploy_stable_liquidity_pool_contract(
+        &env,
+        Some(admin1.clone()),
+        &[&token1.address, &token2.address],
+        0i64,
+        Address::generate(&env),
+        500,
+        200,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 86_400);
+    pool.ramp_amp(&61, &172_800);
+}
+
+#[test]
+#[should_panic(
+    expected = "Pool Stable: Ramp amp: Amplification changes must last and be apart at least MIN_AMP_CHANGING_TIME"
+)]
+fn ramp_amp_too_soon_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let mut admin1 = Address::generate(&env);
+    let mut admin2 = Address::generate(&env);
+
+    let mut token1 = deploy_token_contract(&env, &admin1);
+    let mut token2 = deploy_token_contract(&env, &admin2);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+        std::mem::swap(&mut admin1, &mut admin2);
+    }
+    // the pool is deployed with an amplification coefficient of 6
+    let pool = deploy_stable_liquidity_pool_contract(
+        &env,
+        Some(admin1.clone()),
+        &[&token1.address, &token2.address],
+        0i64,
+        Address::generate(&env),
+        500,
+        200,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    pool.ramp_amp(&12, &100_000);
+}
+
+#[test]
+#[should_panic(
+    expected = "Pool Stable: Ramp amp: Amplification coefficient must be between 1 and MAX_AMP"
+)]
+fn ramp_amp_to_zero_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let mut admin1 = Address::generate(&env);
+    let mut admin2 = Address::generate(&env);
+
+    let mut token1 = deploy_token_contract(&env, &admin1);
+    let mut token2 = deploy_token_contract(&env, &admin2);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+        std::mem::swap(&mut admin1, &mut admin2);
+    }
+    // the pool is deployed with an amplification coefficient of 6
+    let pool = deploy_stable_liquidity_pool_contract(
+        &env,
+        Some(admin1.clone()),
+        &[&token1.address, &token2.address],
+        0i64,
+        Address::generate(&env),
+        500,
+        200,
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 86_400);
+    pool.ramp_amp(&0, &172_800);
+}
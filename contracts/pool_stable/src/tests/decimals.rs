@@ -0,0 +1,453 @@
+extern crate std;
+use std::format;
+
+use proptest::prelude::*;
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+use test_case::test_case;
+
+use super::setup::{deploy_stable_liquidity_pool_contract, deploy_token_contract_with_decimals};
+use crate::contract::StableLiquidityPoolClient;
+use crate::math::{compute_d, mul_div, DECIMAL_PRECISION};
+use crate::token_contract;
+
+/// Deploys a pool of two tokens with the given decimals, holding one whole token of each.
+/// The tokens are returned together with their decimals in the order of the pool.
+fn deploy_pool_with_decimals<'a>(
+    env: &Env,
+    user: &Address,
+    decimals_a: u32,
+    decimals_b: u32,
+) -> (
+    StableLiquidityPoolClient<'a>,
+    (token_contract::Client<'a>, u32),
+    (token_contract::Client<'a>, u32),
+) {
+    deploy_pool_with_reserves(
+        env,
+        user,
+        (decimals_a, 10i128.pow(decimals_a)),
+        (decimals_b, 10i128.pow(decimals_b)),
+        500,
+        200,
+    )
+}
+
+/// Deploys a pool of two tokens with the given decimals and provides the given reserves of them.
+/// The tokens are returned together with their decimals in the order of the pool.
+fn deploy_pool_with_reserves<'a>(
+    env: &Env,
+    user: &Address,
+    (decimals_a, reserve_a): (u32, i128),
+    (decimals_b, reserve_b): (u32, i128),
+    max_allowed_slippage_bps: i64,
+    max_allowed_spread_bps: i64,
+) -> (
+    StableLiquidityPoolClient<'a>,
+    (token_contract::Client<'a>, u32),
+    (token_contract::Client<'a>, u32),
+) {
+    let mut token_a = (
+        deploy_token_contract_with_decimals(env, &Address::generate(env), decimals_a),
+        decimals_a,
+        reserve_a,
+    );
+    let mut token_b = (
+        deploy_token_contract_with_decimals(env, &Address::generate(env), decimals_b),
+        decimals_b,
+        reserve_b,
+    );
+    if token_b.0.address < token_a.0.address {
+        std::mem::swap(&mut token_a, &mut token_b);
+    }
+
+    let pool = deploy_stable_liquidity_pool_contract(
+        env,
+        None,
+        &[&token_a.0.address, &token_b.0.address],
+        100i64,
+        Address::generate(env),
+        max_allowed_slippage_bps,
+        max_allowed_spread_bps,
+        Address::generate(env),
+        Address::generate(env),
+    );
+
+    token_a.0.mint(user, &token_a.2);
+    token_b.0.mint(user, &token_b.2);
+    pool.provide_liquidity(user, &vec![env, token_a.2, token_b.2], &None, &None);
+
+    (pool, (token_a.0, token_a.1), (token_b.0, token_b.1))
+}
+
+#[test_case(7, 7 ; "when both tokens have 7 decimals")]
+#[test_case(6, 7 ; "when tokens have 6 and 7 decimals")]
+#[test_case(7, 18 ; "when tokens have 7 and 18 decimals")]
+#[test_case(18, 6 ; "when tokens have 18 and 6 decimals")]
+#[test_case(18, 18 ; "when both tokens have 18 decimals")]
+fn swap_returns_the_same_value_in_the_other_token(decimals_a: u32, decimals_b: u32) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (pool, (token_a, decimals_a), (token_b, decimals_b)) =
+        deploy_pool_with_decimals(&env, &user, decimals_a, decimals_b);
+
+    // a thousandth of a token swaps close to 1:1 minus the 1% fee in a balanced pool
+    let offer_amount = 10i128.pow(decimals_a - 3);
+    let expected = 10i128.pow(decimals_b - 3);
+    token_a.mint(&user, &offer_amount);
+
+    let simulated = pool.simulate_swap(&token_a.address, &token_b.address, &offer_amount);
+    let return_amount = pool.swap(
+        &user,
+        &token_a.address,
+        &token_b.address,
+        &offer_amount,
+        &None,
+        &None,
+        &None,
+    );
+
+    assert_eq!(return_amount, simulated.ask_amount);
+    assert!(return_amount > expected * 98 / 100 && return_amount < expected);
+    assert_eq!(token_b.balance(&user), return_amount);
+
+    // and back again
+    let offer_amount = return_amount;
+    let return_amount = pool.swap(
+        &user,
+        &token_b.address,
+        &token_a.address,
+        &offer_amount,
+        &None,
+        &None,
+        &None,
+    );
+    let expected = 10i128.pow(decimals_a - 3);
+    assert!(return_amount > expected * 96 / 100 && return_amount < expected);
+}
+
+#[test_case(7, 7 ; "when both tokens have 7 decimals")]
+#[test_case(6, 7 ; "when tokens have 6 and 7 decimals")]
+#[test_case(7, 18 ; "when tokens have 7 and 18 decimals")]
+#[test_case(18, 6 ; "when tokens have 18 and 6 decimals")]
+#[test_case(18, 18 ; "when both tokens have 18 decimals")]
+fn provide_liquidity_mints_shares_by_value(decimals_a: u32, decimals_b: u32) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (pool, (token_a, decimals_a), (token_b, decimals_b)) =
+        deploy_pool_with_decimals(&env, &user, decimals_a, decimals_b);
+    let share_token = token_contract::Client::new(&env, &pool.query_share_token_address());
+    let total_shares = pool.query_pool_info().asset_lp_share.amount;
+
+    // depositing a tenth of the reserves mints a tenth of the shares
+    let second_user = Address::generate(&env);
+    let amount_a = 10i128.pow(decimals_a - 1);
+    let amount_b = 10i128.pow(decimals_b - 1);
+    token_a.mint(&second_user, &amount_a);
+    token_b.mint(&second_user, &amount_b);
+    pool.provide_liquidity(&second_user, &vec![&env, amount_a, amount_b], &None, &None);
+
+    let shares = share_token.balance(&second_user);
+    assert!((shares * 10 - total_shares).abs() <= total_shares / 1_000);
+}
+
+#[test_case(7, 7 ; "when both tokens have 7 decimals")]
+#[test_case(6, 7 ; "when tokens have 6 and 7 decimals")]
+#[test_case(7, 18 ; "when tokens have 7 and 18 decimals")]
+#[test_case(18, 6 ; "when tokens have 18 and 6 decimals")]
+#[test_case(18, 18 ; "when both tokens have 18 decimals")]
+fn withdraw_one_coin_returns_the_value_of_the_shares(decimals_a: u32, decimals_b: u32) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (pool, _, (token_b, decimals_b)) =
+        deploy_pool_with_decimals(&env, &user, decimals_a, decimals_b);
+
+    // a tenth of the pool is worth a bit less than a fifth of a whole token
+    let share_amount = pool.query_pool_info().asset_lp_share.amount / 10;
+    let return_amount = pool.withdraw_one_coin(&user, &share_amount, &token_b.address, &1, &None);
+
+    let expected = 2 * 10i128.pow(decimals_b - 1);
+    assert!(return_amount > expected * 95 / 100 && return_amount < expected);
+    assert_eq!(token_b.balance(&user), return_amount);
+}
+
+/// A million whole tokens of a token with `decimals`
+fn million_tokens(decimals: u32) -> i128 {
+    1_000_000 * 10i128.pow(decimals)
+}
+
+#[test_case(7, 7 ; "when both tokens have 7 decimals")]
+#[test_case(7, 18 ; "when tokens have 7 and 18 decimals")]
+#[test_case(18, 7 ; "when tokens have 18 and 7 decimals")]
+#[test_case(18, 18 ; "when both tokens have 18 decimals")]
+fn swap_in_a_pool_of_a_million_tokens(decimals_a: u32, decimals_b: u32) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (pool, (token_a, decimals_a), (token_b, decimals_b)) = deploy_pool_with_reserves(
+        &env,
+        &user,
+        (decimals_a, million_tokens(decimals_a)),
+        (decimals_b, million_tokens(decimals_b)),
+        500,
+        200,
+    );
+
+    // a thousand tokens swap close to 1:1 minus the 1% fee
+    let offer_amount = 1_000 * 10i128.pow(decimals_a);
+    let expected = 990 * 10i128.pow(decimals_b);
+    token_a.mint(&user, &offer_amount);
+
+    let simulated = pool.simulate_swap(&token_a.address, &token_b.address, &offer_amount);
+    // asking for the simulated amount costs the same offer up to rounding
+    let exact_out_offer =
+        pool.simulate_swap_exact_out(&token_a.address, &token_b.address, &simulated.ask_amount);
+    assert!((exact_out_offer - offer_amount).abs() <= offer_amount / 1_000_000);
+
+    let return_amount = pool.swap(
+        &user,
+        &token_a.address,
+        &token_b.address,
+        &offer_amount,
+        &None,
+        &None,
+        &None,
+    );
+
+    assert_eq!(return_amount, simulated.ask_amount);
+    assert!(return_amount > expected * 999 / 1_000 && return_amount < expected);
+    assert_eq!(token_b.balance(&user), return_amount);
+}
+
+#[test_case(7, 7 ; "when both tokens have 7 decimals")]
+#[test_case(7, 18 ; "when tokens have 7 and 18 decimals")]
+#[test_case(18, 7 ; "when tokens have 18 and 7 decimals")]
+#[test_case(18, 18 ; "when both tokens have 18 decimals")]
+fn provide_and_withdraw_in_a_pool_of_a_million_tokens(decimals_a: u32, decimals_b: u32) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (pool, (token_a, decimals_a), (token_b, decimals_b)) = deploy_pool_with_reserves(
+        &env,
+        &user,
+        (decimals_a, million_tokens(decimals_a)),
+        (decimals_b, million_tokens(decimals_b)),
+        500,
+        200,
+    );
+    let share_token = token_contract::Client::new(&env, &pool.query_share_token_address());
+    let total_shares = pool.query_pool_info().asset_lp_share.amount;
+
+    // the shares carry the greatest precision and are worth the invariant of two million tokens
+    let precision = decimals_a.max(decimals_b);
+    assert_eq!(total_shares, 2 * million_tokens(precision) - 1_000);
+
+    // depositing a tenth of the reserves mints a tenth of the shares
+    let second_user = Address::generate(&env);
+    let amount_a = million_tokens(decimals_a) / 10;
+    let amount_b = million_tokens(decimals_b) / 10;
+    token_a.mint(&second_user, &amount_a);
+    token_b.mint(&second_user, &amount_b);
+    pool.provide_liquidity(&second_user, &vec![&env, amount_a, amount_b], &None, &None);
+
+    let shares = share_token.balance(&second_user);
+    assert!((shares * 10 - total_shares).abs() <= 10);
+
+    // withdrawing them in one coin returns a fifth of a million tokens minus the fee
+    let return_amount = pool.withdraw_one_coin(&second_user, &shares, &token_b.address, &1, &None);
+    let expected = million_tokens(decimals_b) / 5;
+    assert!(return_amount > expected * 98 / 100 && return_amount < expected);
+    assert_eq!(token_b.balance(&second_user), return_amount);
+    assert_eq!(share_token.balance(&second_user), 0);
+}
+
+/// Converts an amount given in thousandths of a whole token to the atomic units of a token with
+/// `decimals`, truncating what is below one unit.
+fn milli_to_atomics(decimals: u32, milli: i128) -> i128 {
+    10i128.pow(decimals) * milli / 1_000
+}
+
+/// Converts an amount of a token with `decimals` to atomic units with 18 decimals.
+fn to_value(decimals: u32, amount: i128) -> i128 {
+    amount * 10i128.pow(DECIMAL_PRECISION - decimals)
+}
+
+/// The invariant computed from rounded reserves may differ from the exact one by a few units.
+const TOLERANCE: u128 = 2;
+
+/// Returns the invariant of the pool from its reserves normalised to 18 decimals.
+fn invariant(env: &Env, pool: &StableLiquidityPoolClient, decimals: [u32; 2]) -> u128 {
+    let assets = pool.query_pool_info().assets;
+    compute_d(
+        env,
+        pool.query_amp().current_amp as u128,
+        &[
+            to_value(decimals[0], assets.get(0).unwrap().amount) as u128,
+            to_value(decimals[1], assets.get(1).unwrap().amount) as u128,
+        ],
+    )
+}
+
+// The first reserve ranges from a thousand to ten million whole tokens and the second one from a
+// quarter to four times the first, as a stable pool stays close to balance. Offers and deposits
+// take up to a fifth and almost a third of the reserves. Amounts below one unit of a token, which
+// appear with few decimals, are rejected.
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn swap_round_trip_keeps_the_invariant_and_does_not_gain_value(
+        decimals_a in 0u32..=18,
+        decimals_b in 0u32..=18,
+        reserve_a in 1_000_000i128..=10_000_000_000,
+        reserve_b_percent in 25i128..=400,
+        offer_bps in 1i128..=2_000,
+    ) {
+        let reserve_b = reserve_a * reserve_b_percent / 100;
+        let offer = reserve_a * offer_bps / 10_000;
+        let reserve_a = milli_to_atomics(decimals_a, reserve_a);
+        let reserve_b = milli_to_atomics(decimals_b, reserve_b);
+        prop_assume!(milli_to_atomics(decimals_a.min(decimals_b), offer) > 0);
+
+        let env = Env::default();
+        env.mock_all_auths();
+        env.budget().reset_unlimited();
+
+        let user = Address::generate(&env);
+        let (pool, (token_a, decimals_a), (token_b, decimals_b)) = deploy_pool_with_reserves(
+            &env,
+            &user,
+            (decimals_a, reserve_a),
+            (decimals_b, reserve_b),
+            10_000,
+            10_000,
+        );
+        let decimals = [decimals_a, decimals_b];
+
+        let offer_amount = milli_to_atomics(decimals_a, offer);
+        let simulated = pool.simulate_swap(&token_a.address, &token_b.address, &offer_amount);
+        prop_assume!(simulated.ask_amount > 0);
+
+        let invariant_before = invariant(&env, &pool, decimals);
+        token_a.mint(&user, &offer_amount);
+        let return_amount = pool.swap(
+            &user,
+            &token_a.address,
+            &token_b.address,
+            &offer_amount,
+            &None,
+            &None,
+            &None,
+        );
+        prop_assert_eq!(return_amount, simulated.ask_amount);
+        prop_assert!(invariant(&env, &pool, decimals) + TOLERANCE >= invariant_before);
+
+        let simulated = pool.simulate_swap(&token_b.address, &token_a.address, &return_amount);
+        prop_assume!(simulated.ask_amount > 0);
+        pool.swap(
+            &user,
+            &token_b.address,
+            &token_a.address,
+            &return_amount,
+            &None,
+            &None,
+            &None,
+        );
+
+        prop_assert_eq!(token_b.balance(&user), 0);
+        prop_assert!(token_a.balance(&user) <= offer_amount);
+    }
+
+    #[test]
+    fn deposit_and_withdraw_round_trip_does_not_gain_value(
+        decimals_a in 0u32..=18,
+        decimals_b in 0u32..=18,
+        reserve_a in 1_000_000i128..=10_000_000_000,
+        reserve_b_percent in 25i128..=400,
+        deposit_a_bps in 0i128..=3_000,
+        deposit_b_bps in 0i128..=3_000,
+    ) {
+        let reserve_b = reserve_a * reserve_b_percent / 100;
+        let deposit_a = reserve_a * deposit_a_bps / 10_000;
+        let deposit_b = reserve_b * deposit_b_bps / 10_000;
+        let reserve_a = milli_to_atomics(decimals_a, reserve_a);
+        let reserve_b = milli_to_atomics(decimals_b, reserve_b);
+
+        let env = Env::default();
+        env.mock_all_auths();
+        env.budget().reset_unlimited();
+
+        let user = Address::generate(&env);
+        let (pool, (token_a, decimals_a), (token_b, decimals_b)) = deploy_pool_with_reserves(
+            &env,
+            &user,
+            (decimals_a, reserve_a),
+            (decimals_b, reserve_b),
+            10_000,
+            10_000,
+        );
+        let decimals = [decimals_a, decimals_b];
+        let share_token = token_contract::Client::new(&env, &pool.query_share_token_address());
+
+        let deposit_a = milli_to_atomics(decimals_a, deposit_a);
+        let deposit_b = milli_to_atomics(decimals_b, deposit_b);
+        prop_assume!(deposit_a > 0 || deposit_b > 0);
+
+        let invariant_before = invariant(&env, &pool, decimals);
+        let pool_info = pool.query_pool_info();
+        let total_shares_before = pool_info.asset_lp_share.amount;
+        let balanced = to_value(decimals_a, pool_info.assets.get(0).unwrap().amount)
+            == to_value(decimals_b, pool_info.assets.get(1).unwrap().amount);
+
+        let second_user = Address::generate(&env);
+        token_a.mint(&second_user, &deposit_a);
+        token_b.mint(&second_user, &deposit_b);
+        pool.provide_liquidity(&second_user, &vec![&env, deposit_a, deposit_b], &None, &None);
+
+        // the invariant per share does not decrease
+        let invariant_after = invariant(&env, &pool, decimals);
+        let total_shares_after = pool.query_pool_info().asset_lp_share.amount;
+        prop_assert!(
+            invariant_after + TOLERANCE
+                >= mul_div(
+                    &env,
+                    invariant_before,
+                    total_shares_after as u128,
+                    total_shares_before as u128,
+                )
+        );
+
+        let shares = share_token.balance(&second_user);
+        // the minimum amounts have to be positive, so both tokens have to be withdrawn
+        let min_amounts = pool.query_share(&shares);
+        let min_a = min_amounts.get(0).unwrap().amount;
+        let min_b = min_amounts.get(1).unwrap().amount;
+        prop_assume!(min_a > 0 && min_b > 0);
+        pool.withdraw_liquidity(&second_user, &shares, &vec![&env, min_a, min_b], &None);
+
+        // the round trip takes out no more of the invariant than it put in, so the other
+        // liquidity providers lose nothing
+        prop_assert_eq!(pool.query_pool_info().asset_lp_share.amount, total_shares_before);
+        prop_assert!(invariant(&env, &pool, decimals) + TOLERANCE >= invariant_before);
+
+        // a balanced pool values both tokens at par
+        if balanced {
+            let deposited = to_value(decimals_a, deposit_a) + to_value(decimals_b, deposit_b);
+            let withdrawn = to_value(decimals_a, token_a.balance(&second_user))
+                + to_value(decimals_b, token_b.balance(&second_user));
+            prop_assert!(withdrawn <= deposited);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+extern crate std;
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+use super::setup::{deploy_stable_liquidity_pool_contract, deploy_token_contract};
+use crate::contract::StableLiquidityPoolClient;
+use crate::token_contract;
+
+fn deploy_pool_with_liquidity<'a>(
+    env: &Env,
+    user: &Address,
+    fee_recipient: &Address,
+) -> (
+    StableLiquidityPoolClient<'a>,
+    token_contract::Client<'a>,
+    token_contract::Client<'a>,
+) {
+    let mut token1 = deploy_token_contract(env, &Address::generate(env));
+    let mut token2 = deploy_token_contract(env, &Address::generate(env));
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+    }
+
+    let pool = deploy_stable_liquidity_pool_contract(
+        env,
+        None,
+        &[&token1.address, &token2.address],
+        100i64, // 1% fee
+        fee_recipient.clone(),
+        500,
+        200,
+        Address::generate(env),
+        Address::generate(env),
+    );
+
+    token1.mint(user, &1_000_000);
+    token2.mint(user, &1_000_000);
+    pool.provide_liquidity(user, &vec![env, 1_000_000i128, 1_000_000i128], &None, &None);
+
+    (pool, token1, token2)
+}
+
+#[test]
+fn swap_exact_out_returns_exact_ask_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let (pool, token1, token2) = deploy_pool_with_liquidity(&env, &user, &fee_recipient);
+    token1.mint(&user, &10_000);
+
+    let simulated_offer_amount =
+        pool.simulate_swap_exact_out(&token1.address, &token2.address, &1_000);
+    // the commission is paid on top of the asked amount
+    assert!(simulated_offer_amount > 1_000 && simulated_offer_amount < 1_020);
+
+    // the simulated amount is exactly enough as the maximum
+    let offer_amount = pool.swap_exact_out(
+        &user,
+        &token1.address,
+        &token2.address,
+        &1_000,
+        &simulated_offer_amount,
+        &None,
+        &None,
+    );
+
+    assert_eq!(offer_amount, simulated_offer_amount);
+    assert_eq!(token1.balance(&user), 10_000 - offer_amount);
+    assert_eq!(token2.balance(&user), 1_000);
+
+    // the stored reserves follow the token balances of the pool
+    let commission_amount = token2.balance(&fee_recipient);
+    assert!(commission_amount > 0);
+    let pool_info = pool.query_pool_info();
+    assert_eq!(
+        pool_info.assets.get(0).unwrap().amount,
+        1_000_000 + offer_amount
+    );
+    assert_eq!(
+        pool_info.assets.get(1).unwrap().amount,
+        1_000_000 - 1_000 - commission_amount
+    );
+    assert_eq!(token1.balance(&pool.address), 1_000_000 + offer_amount);
+    assert_eq!(
+        token2.balance(&pool.address),
+        1_000_000 - 1_000 - commission_amount
+    );
+}
+
+#[test]
+fn swap_exact_out_in_the_other_direction() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let (pool, token1, token2) = deploy_pool_with_liquidity(&env, &user, &fee_recipient);
+    token2.mint(&user, &10_000);
+
+    let offer_amount = pool.swap_exact_out(
+        &user,
+        &token2.address,
+        &token1.address,
+        &5_000,
+        &10_000,
+        &None,
+        &None,
+    );
+
+    assert_eq!(token1.balance(&user), 5_000);
+    assert_eq!(token2.balance(&user), 10_000 - offer_amount);
+    assert_eq!(
+        pool.query_pool_info().assets.get(1).unwrap().amount,
+        1_000_000 + offer_amount
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #16)")]
+fn swap_exact_out_should_fail_when_offer_amount_is_bigger_than_max() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (pool, token1, token2) = deploy_pool_with_liquidity(&env, &user, &Address::generate(&env));
+    token1.mint(&user, &10_000);
+
+    let simulated_offer_amount =
+        pool.simulate_swap_exact_out(&token1.address, &token2.address, &1_000);
+
+    pool.swap_exact_out(
+        &user,
+        &token1.address,
+        &token2.address,
+        &1_000,
+        &(simulated_offer_amount - 1),
+        &None,
+        &None,
+    );
+}
@@ -0,0 +1,397 @@
+extern crate std;
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+use super::setup::{deploy_stable_liquidity_pool_contract, deploy_token_contract};
+use crate::contract::StableLiquidityPoolClient;
+use crate::token_contract;
+
+fn deploy_sorted_tokens<'a>(env: &Env, count: usize) -> std::vec::Vec<token_contract::Client<'a>> {
+    let mut tokens: std::vec::Vec<_> = (0..count)
+        .map(|_| deploy_token_contract(env, &Address::generate(env)))
+        .collect();
+    tokens.sort_by(|a, b| a.address.cmp(&b.address));
+    tokens
+}
+
+fn deploy_pool<'a>(
+    env: &Env,
+    tokens: &[token_contract::Client<'a>],
+    fee_recipient: &Address,
+) -> StableLiquidityPoolClient<'a> {
+    let addresses: std::vec::Vec<&Address> = tokens.iter().map(|token| &token.address).collect();
+    deploy_stable_liquidity_pool_contract(
+        env,
+        None,
+        &addresses,
+        100i64,
+        fee_recipient.clone(),
+        500,
+        200,
+        Address::generate(env),
+        Address::generate(env),
+    )
+}
+
+#[test]
+fn three_asset_pool_provide_swap_and_withdraw() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let tokens = deploy_sorted_tokens(&env, 3);
+    let pool = deploy_pool(&env, &tokens, &fee_recipient);
+
+    for token in tokens.iter() {
+        token.mint(&user, &1_001_000);
+    }
+    pool.provide_liquidity(
+        &user,
+        &vec![&env, 1_000_000i128, 1_000_000i128, 1_000_000i128],
+        &None,
+        &None,
+    );
+
+    let pool_info = pool.query_pool_info();
+    assert_eq!(pool_info.assets.len(), 3);
+    for (asset, token) in pool_info.assets.iter().zip(tokens.iter()) {
+        assert_eq!(asset.address, token.address);
+        assert_eq!(asset.amount, 1_000_000);
+    }
+
+    // swap the first asset directly for the third one
+    let simulated = pool.simulate_swap(&tokens[0].address, &tokens[2].address, &1_000);
+    let return_amount = pool.swap(
+        &user,
+        &tokens[0].address,
+        &tokens[2].address,
+        &1_000,
+        &None,
+        &None,
+        &None,
+    );
+    assert_eq!(return_amount, simulated.ask_amount);
+    // a balanced stable pool swaps close to 1:1 minus the 1% fee
+    assert!(return_amount > 980 && return_amount < 1_000);
+    assert_eq!(tokens[0].balance(&user), 0);
+    assert_eq!(tokens[1].balance(&user), 1_000);
+    assert_eq!(tokens[2].balance(&user), 1_000 + return_amount);
+    assert_eq!(
+        tokens[2].balance(&fee_recipient),
+        simulated.commission_amount
+    );
+
+    let pool_info = pool.query_pool_info();
+    assert_eq!(pool_info.assets.get(0).unwrap().amount, 1_001_000);
+    assert_eq!(pool_info.assets.get(1).unwrap().amount, 1_000_000);
+    assert_eq!(
+        pool_info.assets.get(2).unwrap().amount,
+        1_000_000 - return_amount - simulated.commission_amount
+    );
+
+    let share_amount = pool_info.asset_lp_share.amount / 2;
+    let withdrawn = pool.withdraw_liquidity(&user, &share_amount, &vec![&env, 1, 1, 1], &None);
+    assert_eq!(withdrawn.len(), 3);
+    let pool_info_after = pool.query_pool_info();
+    for ((amount, before), after) in withdrawn
+        .iter()
+        .zip(pool_info.assets.iter())
+        .zip(pool_info_after.assets.iter())
+    {
+        assert!(amount > 0 && amount <= before.amount / 2);
+        assert_eq!(after.amount, before.amount - amount);
+    }
+}
+
+#[test]
+fn four_asset_pool_swaps_between_any_assets() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let tokens = deploy_sorted_tokens(&env, 4);
+    let pool = deploy_pool(&env, &tokens, &Address::generate(&env));
+
+    for token in tokens.iter() {
+        token.mint(&user, &1_000_000);
+    }
+    pool.provide_liquidity(
+        &user,
+        &vec![
+            &env,
+            1_000_000i128,
+            1_000_000i128,
+            1_000_000i128,
+            1_000_000i128,
+        ],
+        &None,
+        &None,
+    );
+
+    tokens[3].mint(&user, &2_000);
+    let return_amount = pool.swap(
+        &user,
+        &tokens[3].address,
+        &tokens[1].address,
+        &1_000,
+        &None,
+        &None,
+        &None,
+    );
+    assert!(return_amount > 980 && return_amount < 1_000);
+    assert_eq!(tokens[1].balance(&user), return_amount);
+
+    // the exact output swap pays out exactly the asked amount
+    let offer_amount = pool.swap_exact_out(
+        &user,
+        &tokens[3].address,
+        &tokens[0].address,
+        &500,
+        &1_000,
+        &None,
+        &None,
+    );
+    assert!(offer_amount > 500 && offer_amount < 1_000);
+    assert_eq!(tokens[0].balance(&user), 500);
+    assert_eq!(tokens[3].balance(&user), 1_000 - offer_amount);
+}
+
+/// Deploys a pool of tokens with 7 decimals holding `reserves` whole tokens of each of them.
+fn deploy_pool_with_reserves<'a>(
+    env: &Env,
+    user: &Address,
+    reserves: &[i128],
+) -> (
+    StableLiquidityPoolClient<'a>,
+    std::vec::Vec<token_contract::Client<'a>>,
+) {
+    let tokens = deploy_sorted_tokens(env, reserves.len());
+    let pool = deploy_pool(env, &tokens, &Address::generate(env));
+
+    let mut amounts = soroban_sdk::Vec::new(env);
+    for (token, reserve) in tokens.iter().zip(reserves.iter()) {
+        token.mint(user, &(reserve * 10_000_000));
+        amounts.push_back(reserve * 10_000_000);
+    }
+    pool.provide_liquidity(user, &amounts, &None, &None);
+
+    (pool, tokens)
+}
+
+#[test]
+fn three_asset_pool_with_imbalanced_reserves() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (pool, tokens) = deploy_pool_with_reserves(&env, &user, &[1_000_000, 2_000_000, 500_000]);
+
+    // the shares are worth the invariant of 3_410_966.9982099 tokens minus the minimum liquidity
+    assert_eq!(
+        pool.query_pool_info().asset_lp_share.amount,
+        34_109_669_982_099 - 1_000
+    );
+
+    // the scarce third asset buys 12_113.1124607 of the first one before the 1% fee
+    let offer_amount = 10_000 * 10_000_000;
+    tokens[2].mint(&user, &offer_amount);
+    let return_amount = pool.swap(
+        &user,
+        &tokens[2].address,
+        &tokens[0].address,
+        &offer_amount,
+        &None,
+        &None,
+        &None,
+    );
+    assert_eq!(return_amount, 121_131_124_607 - 1_211_311_246);
+    assert_eq!(tokens[0].balance(&user), return_amount);
+}
+
+#[test]
+fn four_asset_pool_with_imbalanced_reserves() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (pool, tokens) =
+        deploy_pool_with_reserves(&env, &user, &[1_000_000, 2_000_000, 500_000, 4_000_000]);
+
+    // the shares are worth the invariant of 7_073_966.7256989 tokens minus the minimum liquidity
+    assert_eq!(
+        pool.query_pool_info().asset_lp_share.amount,
+        70_739_667_256_989 - 1_000
+    );
+
+    // the scarce third asset buys 20_493.8449399 of the abundant fourth one before the 1% fee
+    let offer_amount = 10_000 * 10_000_000;
+    tokens[2].mint(&user, &offer_amount);
+    let return_amount = pool.swap(
+        &user,
+        &tokens[2].address,
+        &tokens[3].address,
+        &offer_amount,
+        &None,
+        &None,
+        &None,
+    );
+    assert_eq!(return_amount, 204_938_449_399 - 2_049_384_493);
+    assert_eq!(tokens[3].balance(&user), return_amount);
+
+    // a hundredth of the shares is worth a hundredth of the invariant, 70_739 tokens, of which the
+    // scarce first asset pays out less
+    let share_amount = pool.query_pool_info().asset_lp_share.amount / 100;
+    let return_amount = pool.withdraw_one_coin(&user, &share_amount, &tokens[0].address, &1, &None);
+    assert!(return_amount > 55_000 * 10_000_000 && return_amount < 70_739 * 10_000_000);
+    assert_eq!(tokens[0].balance(&user), return_amount);
+}
+
+#[test]
+#[should_panic(
+    expected = "Pool Stable: Initialize: A stable pool must have between MIN_COINS and MAX_COINS tokens"
+)]
+fn initialize_with_five_assets_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let tokens = deploy_sorted_tokens(&env, 5);
+    deploy_pool(&env, &tokens, &Address::generate(&env));
+}
+
+#[test]
+#[should_panic(
+    expected = "Pool Stable: ProvideLiquidity: An amount must be given for each token and the first deposit must include every token"
+)]
+fn first_deposit_without_every_asset_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let tokens = deploy_sorted_tokens(&env, 3);
+    let pool = deploy_pool(&env, &tokens, &Address::generate(&env));
+
+    for token in tokens.iter() {
+        token.mint(&user, &1_000_000);
+    }
+    pool.provide_liquidity(
+        &user,
+        &vec![&env, 1_000_000i128, 0i128, 1_000_000i128],
+        &None,
+        &None,
+    );
+}
+
+#[test]
+fn simulate_provide_liquidity_matches_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let tokens = deploy_sorted_tokens(&env, 3);
+    let pool = deploy_pool(&env, &tokens, &Address::generate(&env));
+    let share_token = token_contract::Client::new(&env, &pool.query_share_token_address());
+
+    for token in tokens.iter() {
+        token.mint(&user, &1_010_000);
+    }
+    tokens[0].mint(&user, &30_000);
+
+    let desired_amounts = vec![&env, 1_000_000i128, 1_000_000i128, 1_000_000i128];
+    let simulated = pool.simulate_provide_liquidity(&desired_amounts);
+    assert_eq!(simulated.slippage_bps, 0);
+    for ((asset, token), amount) in simulated
+        .assets
+        .iter()
+        .zip(tokens.iter())
+        .zip(desired_amounts.iter())
+    {
+        assert_eq!(asset.address, token.address);
+        assert_eq!(asset.amount, amount);
+    }
+    pool.provide_liquidity(&user, &desired_amounts, &None, &None);
+    assert_eq!(share_token.balance(&user), simulated.share_amount);
+
+    // a balanced deposit mints shares at the pool price
+    let balanced = pool.simulate_provide_liquidity(&vec![&env, 10_000i128, 10_000i128, 10_000i128]);
+    assert!(balanced.slippage_bps <= 1);
+
+    // depositing the same value in a single token costs more slippage and mints fewer shares
+    let imbalanced = vec![&env, 30_000i128, 0i128, 0i128];
+    let simulated = pool.simulate_provide_liquidity(&imbalanced);
+    assert!(simulated.slippage_bps > balanced.slippage_bps);
+    assert!(simulated.share_amount < balanced.share_amount);
+
+    let shares_before = share_token.balance(&user);
+    pool.provide_liquidity(&user, &imbalanced, &None, &None);
+    assert_eq!(
+        share_token.balance(&user) - shares_before,
+        simulated.share_amount
+    );
+}
+
+#[test]
+fn provide_liquidity_within_custom_slippage() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let tokens = deploy_sorted_tokens(&env, 3);
+    let pool = deploy_pool(&env, &tokens, &Address::generate(&env));
+
+    for token in tokens.iter() {
+        token.mint(&user, &1_000_000);
+    }
+    tokens[0].mint(&user, &30_000);
+    pool.provide_liquidity(
+        &user,
+        &vec![&env, 1_000_000i128, 1_000_000i128, 1_000_000i128],
+        &None,
+        &None,
+    );
+
+    // the rounded up slippage of the simulation is enough as the tolerance
+    let imbalanced = vec![&env, 30_000i128, 0i128, 0i128];
+    let simulated = pool.simulate_provide_liquidity(&imbalanced);
+    assert!(simulated.slippage_bps > 0);
+    pool.provide_liquidity(&user, &imbalanced, &Some(simulated.slippage_bps), &None);
+    assert_eq!(tokens[0].balance(&user), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #30)")]
+fn provide_liquidity_above_custom_slippage_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let tokens = deploy_sorted_tokens(&env, 3);
+    let pool = deploy_pool(&env, &tokens, &Address::generate(&env));
+
+    for token in tokens.iter() {
+        token.mint(&user, &1_000_000);
+    }
+    tokens[0].mint(&user, &30_000);
+    pool.provide_liquidity(
+        &user,
+        &vec![&env, 1_000_000i128, 1_000_000i128, 1_000_000i128],
+        &None,
+        &None,
+    );
+
+    // a single sided deposit is imbalanced, so it always loses some value
+    pool.provide_liquidity(
+        &user,
+        &vec![&env, 30_000i128, 0i128, 0i128],
+        &Some(0),
+        &None,
+    );
+}
@@ -1,16 +1,32 @@
-use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, String};
+use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, String, Vec};
 
 use crate::{
     contract::{StableLiquidityPool, StableLiquidityPoolClient},
     token_contract,
 };
 
-use phoenix::utils::{LiquidityPoolInitInfo, StakeInitInfo, TokenInitInfo};
+use phoenix::utils::{StableLiquidityPoolInitInfo, StakeInitInfo};
 
 pub fn deploy_token_contract<'a>(env: &Env, admin: &Address) -> token_contract::Client<'a> {
     token_contract::Client::new(env, &env.register_stellar_asset_contract(admin.clone()))
 }
 
+pub fn deploy_token_contract_with_decimals<'a>(
+    env: &Env,
+    admin: &Address,
+    decimals: u32,
+) -> token_contract::Client<'a> {
+    let token =
+        token_contract::Client::new(env, &env.register_contract_wasm(None, token_contract::WASM));
+    token.initialize(
+        admin,
+        &decimals,
+        &String::from_str(env, "Token"),
+        &String::from_str(env, "TKN"),
+    );
+    token
+}
+
 pub fn install_token_wasm(env: &Env) -> BytesN<32> {
     soroban_sdk::contractimport!(
         file = "../../target/wasm32-unknown-unknown/release/soroban_token_contract.wasm"
@@ -30,7 +46,7 @@ pub fn install_stake_wasm(env: &Env) -> BytesN<32> {
 pub fn deploy_stable_liquidity_pool_contract<'a>(
     env: &Env,
     admin: impl Into<Option<Address>>,
-    token_a_b: (&Address, &Address),
+    tokens: &[&Address],
     swap_fees: i64,
     fee_recipient: impl Into<Option<Address>>,
     max_allowed_slippage_bps: impl Into<Option<i64>>,
@@ -45,10 +61,10 @@ pub fn deploy_stable_liquidity_pool_contract<'a>(
         .into()
         .unwrap_or_else(|| Address::generate(env));
 
-    let token_init_info = TokenInitInfo {
-        token_a: token_a_b.0.clone(),
-        token_b: token_a_b.1.clone(),
-    };
+    let mut token_addresses = Vec::new(env);
+    for token in tokens {
+        token_addresses.push_back((*token).clone());
+    }
     let stake_init_info = StakeInitInfo {
         min_bond: 10i128,
         min_reward: 5i128,
@@ -59,14 +75,13 @@ pub fn deploy_stable_liquidity_pool_contract<'a>(
     let token_wasm_hash = install_token_wasm(env);
     let stake_wasm_hash = install_stake_wasm(env);
 
-    let lp_init_info = LiquidityPoolInitInfo {
+    let lp_init_info = StableLiquidityPoolInitInfo {
         admin,
         swap_fee_bps: swap_fees,
         fee_recipient,
         max_allowed_slippage_bps: max_allowed_slippage_bps.into().unwrap_or(5_000),
         max_allowed_spread_bps: max_allowed_spread_bps.into().unwrap_or(500),
-        max_referral_bps: 5_000,
-        token_init_info,
+        tokens: token_addresses,
         stake_init_info,
     };
 
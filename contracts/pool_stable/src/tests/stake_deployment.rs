@@ -1,6 +1,6 @@
 extern crate std;
-use phoenix::utils::{LiquidityPoolInitInfo, StakeInitInfo, TokenInitInfo};
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use phoenix::utils::{StableLiquidityPoolInitInfo, StakeInitInfo};
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, String};
 
 use super::setup::{deploy_stable_liquidity_pool_contract, deploy_token_contract};
 use crate::contract::{StableLiquidityPool, StableLiquidityPoolClient};
@@ -32,7 +32,7 @@ fn confirm_stake_contract_deployment() {
     let pool = deploy_stable_liquidity_pool_contract(
         &env,
         Some(admin1.clone()),
-        (&token1.address, &token2.address),
+        &[&token1.address, &token2.address],
         swap_fees,
         user1.clone(),
         500,
@@ -47,15 +47,15 @@ fn confirm_stake_contract_deployment() {
     assert_eq!(
         pool.query_config(),
         Config {
-            token_a: token1.address.clone(),
-            token_b: token2.address.clone(),
+            tokens: vec![&env, token1.address.clone(), token2.address.clone()],
             share_token: share_token_address.clone(),
             stake_contract: stake_token_address.clone(),
-            pool_type: PairType::Xyk,
+            pool_type: PairType::Stable,
             total_fee_bps: 0,
             fee_recipient: user1,
             max_allowed_slippage_bps: 500,
             max_allowed_spread_bps: 200,
+            paused: false,
         }
     );
 
@@ -70,6 +70,7 @@ fn confirm_stake_contract_deployment() {
                 owner: factory,
                 manager: stake_manager,
                 max_complexity: 10,
+                paused: false,
             }
         }
     );
@@ -105,10 +106,6 @@ fn second_pool_stable_deployment_should_fail() {
     let stake_manager = Address::generate(&env);
     let factory = Address::generate(&env);
 
-    let token_init_info = TokenInitInfo {
-        token_a: token1.address.clone(),
-        token_b: token2.address.clone(),
-    };
     let stake_init_info = StakeInitInfo {
         min_bond: 10i128,
         min_reward: 5i128,
@@ -116,14 +113,13 @@ fn second_pool_stable_deployment_should_fail() {
         max_complexity: 10,
     };
 
-    let lp_init_info = LiquidityPoolInitInfo {
+    let lp_init_info = StableLiquidityPoolInitInfo {
         admin: admin1,
         swap_fee_bps: 0i64,
         fee_recipient,
         max_allowed_slippage_bps: max_allowed_slippage,
         max_allowed_spread_bps: max_allowed_spread,
-        max_referral_bps: 500,
-        token_init_info,
+        tokens: vec![&env, token1.address.clone(), token2.address.clone()],
         stake_init_info,
     };
 
@@ -151,7 +147,7 @@ fn second_pool_stable_deployment_should_fail() {
 
 #[test]
 #[should_panic(
-    expected = "Pool Stable: Initialize: First token must be alphabetically smaller than second token"
+    expected = "Pool Stable: Initialize: Tokens must be sorted alphabetically and must not repeat"
 )]
 fn pool_stable_initialization_should_fail_with_token_a_bigger_than_token_b() {
     let env = Env::default();
@@ -181,10 +177,6 @@ fn pool_stable_initialization_should_fail_with_token_a_bigger_than_token_b() {
     let stake_manager = Address::generate(&env);
     let factory = Address::generate(&env);
 
-    let token_init_info = TokenInitInfo {
-        token_a: token1.address.clone(),
-        token_b: token2.address.clone(),
-    };
     let stake_init_info = StakeInitInfo {
         min_bond: 10i128,
         min_reward: 5i128,
@@ -192,14 +184,13 @@ fn pool_stable_initialization_should_fail_with_token_a_bigger_than_token_b() {
         max_complexity: 10,
     };
 
-    let lp_init_info = LiquidityPoolInitInfo {
+    let lp_init_info = StableLiquidityPoolInitInfo {
         admin: admin1,
         swap_fee_bps: 0i64,
         fee_recipient,
         max_allowed_slippage_bps: max_allowed_slippage,
         max_allowed_spread_bps: max_allowed_spread,
-        max_referral_bps: 500,
-        token_init_info,
+        tokens: vec![&env, token1.address.clone(), token2.address.clone()],
         stake_init_info,
     };
 
@@ -0,0 +1,180 @@
+extern crate std;
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+use super::setup::{deploy_stable_liquidity_pool_contract, deploy_token_contract};
+use crate::contract::StableLiquidityPoolClient;
+use crate::token_contract;
+
+fn deploy_pool_with_liquidity<'a>(
+    env: &Env,
+    user: &Address,
+) -> (
+    StableLiquidityPoolClient<'a>,
+    token_contract::Client<'a>,
+    token_contract::Client<'a>,
+) {
+    let mut admin1 = Address::generate(env);
+    let mut admin2 = Address::generate(env);
+
+    let mut token1 = deploy_token_contract(env, &admin1);
+    let mut token2 = deploy_token_contract(env, &admin2);
+    if token2.address < token1.address {
+        std::mem::swap(&mut token1, &mut token2);
+        std::mem::swap(&mut admin1, &mut admin2);
+    }
+    let pool = deploy_stable_liquidity_pool_contract(
+        env,
+        Some(admin1.clone()),
+        &[&token1.address, &token2.address],
+        100i64,
+        Address::generate(env),
+        500,
+        200,
+        Address::generate(env),
+        Address::generate(env),
+    );
+
+    token1.mint(user, &1_000_000);
+    token2.mint(user, &1_000_000);
+    pool.provide_liquidity(user, &vec![env, 1_000_000i128, 1_000_000i128], &None, &None);
+
+    (pool, token1, token2)
+}
+
+#[test]
+fn withdraw_one_coin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (pool, token1, token2) = deploy_pool_with_liquidity(&env, &user);
+    let share_token = token_contract::Client::new(&env, &pool.query_share_token_address());
+
+    let total_shares = pool.query_pool_info().asset_lp_share.amount;
+    let share_amount = total_shares / 10;
+    let simulated = pool.simulate_withdraw_one_coin(&share_amount, &token1.address);
+
+    let return_amount = pool.withdraw_one_coin(&user, &share_amount, &token1.address, &1, &None);
+
+    assert_eq!(return_amount, simulated.return_amount);
+    assert!(simulated.fee_amount > 0);
+    // a tenth of the pool is worth a bit less than 200_000 of a single token
+    assert!(return_amount > 100_000 && return_amount < 200_000);
+
+    assert_eq!(token1.balance(&user), return_amount);
+    assert_eq!(token2.balance(&user), 0);
+    assert_eq!(
+        share_token.balance(&user),
+        pool.query_pool_info().asset_lp_share.amount
+    );
+
+    let pool_info = pool.query_pool_info();
+    assert_eq!(
+        pool_info.assets.get(0).unwrap().amount,
+        1_000_000 - return_amount
+    );
+    assert_eq!(pool_info.assets.get(1).unwrap().amount, 1_000_000);
+    assert_eq!(pool_info.asset_lp_share.amount, total_shares - share_amount);
+}
+
+#[test]
+#[should_panic(
+    expected = "Pool Stable: Withdraw one coin: Return amount is smaller than the minimum amount"
+)]
+fn withdraw_one_coin_below_min_amount_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (pool, token1, _) = deploy_pool_with_liquidity(&env, &user);
+
+    let share_amount = pool.query_pool_info().asset_lp_share.amount / 10;
+    let simulated = pool.simulate_withdraw_one_coin(&share_amount, &token1.address);
+
+    pool.withdraw_one_coin(
+        &user,
+        &share_amount,
+        &token1.address,
+        &(simulated.return_amount + 1),
+        &None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #31)")]
+fn withdraw_one_coin_of_all_shares_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (pool, token1, _) = deploy_pool_with_liquidity(&env, &user);
+
+    let total_shares = pool.query_pool_info().asset_lp_share.amount;
+    pool.simulate_withdraw_one_coin(&total_shares, &token1.address);
+}
+
+#[test]
+fn withdraw_imbalanced() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (pool, token1, token2) = deploy_pool_with_liquidity(&env, &user);
+    let share_token = token_contract::Client::new(&env, &pool.query_share_token_address());
+
+    let total_shares = pool.query_pool_info().asset_lp_share.amount;
+    let amounts = vec![&env, 100_000i128, 0i128];
+    let simulated = pool.simulate_withdraw_imbalanced(&amounts);
+
+    let share_amount = pool.withdraw_imbalanced(&user, &amounts, &total_shares, &None);
+
+    assert_eq!(share_amount, simulated.share_amount);
+    assert!(simulated.fee_amounts.get(0).unwrap() > 0);
+    // taking a single token costs more shares than the 5% of the pool it is worth
+    assert!(share_amount > total_shares / 20);
+
+    assert_eq!(token1.balance(&user), 100_000);
+    assert_eq!(token2.balance(&user), 0);
+    assert_eq!(share_token.balance(&user), total_shares - share_amount);
+
+    let pool_info = pool.query_pool_info();
+    assert_eq!(pool_info.assets.get(0).unwrap().amount, 900_000);
+    assert_eq!(pool_info.assets.get(1).unwrap().amount, 1_000_000);
+}
+
+#[test]
+#[should_panic(
+    expected = "Pool Stable: Withdraw imbalanced: Amount of shares to burn is bigger than the maximum"
+)]
+fn withdraw_imbalanced_above_max_shares_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (pool, _, _) = deploy_pool_with_liquidity(&env, &user);
+
+    let amounts = vec![&env, 100_000i128, 50_000i128];
+    let simulated = pool.simulate_withdraw_imbalanced(&amounts);
+
+    pool.withdraw_imbalanced(&user, &amounts, &(simulated.share_amount - 1), &None);
+}
+
+#[test]
+#[should_panic(
+    expected = "Pool Stable: Withdraw imbalanced: Amounts must not be negative and must be smaller than the pool balances"
+)]
+fn withdraw_imbalanced_more_than_reserves_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let user = Address::generate(&env);
+    let (pool, _, _) = deploy_pool_with_liquidity(&env, &user);
+
+    pool.simulate_withdraw_imbalanced(&vec![&env, 1_000_000i128, 0i128]);
+}
@@ -27,6 +27,7 @@ use crate::{
     token_contract,
 };
 use curve::Curve;
+use phoenix::utils::AdminChange;
 
 // Metadata that is added on to the WASM custom section
 contractmeta!(
@@ -70,6 +71,23 @@ pub trait StakingTrait {
         token_amount: i128,
     );
 
+    // Allows the admin or the owner to stop bonding. Unbonding and withdrawing rewards are
+    // still allowed while the contract is paused
+    fn pause(env: Env, sender: Address);
+
+    // Allows the admin or the owner to resume bonding
+    fn unpause(env: Env, sender: Address);
+
+    // Allows the admin to propose `new_admin` as the next admin. The proposal has to be accepted
+    // by `new_admin` before the ledger timestamp passes `expires_at`
+    fn propose_admin(env: Env, sender: Address, new_admin: Address, expires_at: u64);
+
+    // Allows the proposed admin to accept the admin role
+    fn accept_admin(env: Env, sender: Address);
+
+    // Allows the admin to cancel the pending admin proposal
+    fn cancel_admin_proposal(env: Env, sender: Address);
+
     // QUERIES
 
     fn query_config(env: Env) -> ConfigResponse;
@@ -142,6 +160,7 @@ impl StakingTrait for Staking {
             manager,
             owner,
             max_complexity,
+            paused: false,
         };
         save_config(&env, config);
 
@@ -155,6 +174,11 @@ impl StakingTrait for Staking {
         let ledger = env.ledger();
         let config = get_config(&env);
 
+        if config.paused {
+            log!(&env, "Stake: Bond: Contract is paused");
+            panic_with_error!(&env, ContractError::Paused);
+        }
+
         if tokens < config.min_bond {
             log!(
                 &env,
@@ -461,6 +485,114 @@ impl StakingTrait for Staking {
             .publish(("fund_reward_distribution", "end_time"), end_time);
     }
 
+    fn pause(env: Env, sender: Address) {
+        sender.require_auth();
+        assert_admin_or_owner(&env, &sender);
+
+        let mut config = get_config(&env);
+        config.paused = true;
+        save_config(&env, config);
+
+        env.events().publish(("pause", "sender"), sender);
+    }
+
+    fn unpause(env: Env, sender: Address) {
+        sender.require_auth();
+        assert_admin_or_owner(&env, &sender);
+
+        let mut config = get_config(&env);
+        config.paused = false;
+        save_config(&env, config);
+
+        env.events().publish(("unpause", "sender"), sender);
+    }
+
+    fn propose_admin(env: Env, sender: Address, new_admin: Address, expires_at: u64) {
+        sender.require_auth();
+
+        if sender != get_admin(&env) {
+            log!(
+                &env,
+                "Stake: Propose admin: Only the admin can propose a new admin"
+            );
+            panic_with_error!(&env, ContractError::Unauthorized);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            log!(
+                &env,
+                "Stake: Propose admin: Proposal expiration must be in the future"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeExpired);
+        }
+
+        utils::save_admin_change(
+            &env,
+            &AdminChange {
+                new_admin: new_admin.clone(),
+                expires_at,
+            },
+        );
+
+        env.events()
+            .publish(("propose_admin", "new_admin"), new_admin);
+        env.events()
+            .publish(("propose_admin", "expires_at"), expires_at);
+    }
+
+    fn accept_admin(env: Env, sender: Address) {
+        sender.require_auth();
+
+        let admin_change = utils::get_admin_change(&env).unwrap_or_else(|| {
+            log!(&env, "Stake: Accept admin: No admin change in place");
+            panic_with_error!(&env, ContractError::AdminChangeNotFound);
+        });
+        if sender != admin_change.new_admin {
+            log!(
+                &env,
+                "Stake: Accept admin: Only the proposed admin can accept the admin role"
+            );
+            panic_with_error!(&env, ContractError::Unauthorized);
+        }
+        if env.ledger().timestamp() > admin_change.expires_at {
+            log!(
+                &env,
+                "Stake: Accept admin: Admin change proposal has expired"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeExpired);
+        }
+
+        utils::remove_admin_change(&env);
+        utils::save_admin(&env, &sender);
+
+        env.events().publish(("accept_admin", "new_admin"), sender);
+    }
+
+    fn cancel_admin_proposal(env: Env, sender: Address) {
+        sender.require_auth();
+
+        if sender != get_admin(&env) {
+            log!(
+                &env,
+                "Stake: Cancel admin proposal: Only the admin can cancel the proposal"
+            );
+            panic_with_error!(&env, ContractError::Unauthorized);
+        }
+
+        let admin_change = utils::get_admin_change(&env).unwrap_or_else(|| {
+            log!(
+                &env,
+                "Stake: Cancel admin proposal: No admin change in place"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeNotFound);
+        });
+        utils::remove_admin_change(&env);
+
+        env.events().publish(
+            ("cancel_admin_proposal", "new_admin"),
+            admin_change.new_admin,
+        );
+    }
+
     // QUERIES
 
     fn query_config(env: Env) -> ConfigResponse {
@@ -567,6 +699,17 @@ fn remove_stake(env: &Env, stakes: &mut Vec<Stake>, stake: i128, stake_timestamp
     }
 }
 
+// Rejects senders other than the admin and the owner from pausing or unpausing the contract
+fn assert_admin_or_owner(env: &Env, sender: &Address) {
+    if *sender != get_admin(env) && *sender != get_config(env).owner {
+        log!(
+            env,
+            "Stake: Pause/Unpause: Only the admin or the owner can pause or unpause the contract"
+        );
+        panic_with_error!(env, ContractError::Unauthorized);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -16,4 +16,7 @@ pub enum ContractError {
     DistributionExists = 10,
     InvalidRewardAmount = 11,
     InvalidMaxComplexity = 12,
+    Paused = 13,
+    AdminChangeNotFound = 14,
+    AdminChangeExpired = 15,
 }
@@ -12,6 +12,8 @@ pub struct Config {
     pub owner: Address,
     // Maximum complexity for the reward distribution curve
     pub max_complexity: u32,
+    // While paused, bonding is rejected. Unbonding and withdrawing rewards are still allowed
+    pub paused: bool,
 }
 const CONFIG: Symbol = symbol_short!("CONFIG");
 
@@ -72,6 +74,7 @@ pub mod utils {
 
     use super::*;
 
+    use phoenix::utils::AdminChange;
     use soroban_sdk::{log, panic_with_error, ConversionError, TryFromVal, Val};
 
     #[derive(Clone, Copy)]
@@ -81,6 +84,7 @@ pub mod utils {
         TotalStaked = 1,
         Distributions = 2,
         Initialized = 3,
+        AdminChange = 4,
     }
 
     impl TryFromVal<Env, DataKey> for Val {
@@ -110,6 +114,20 @@ pub mod utils {
         e.storage().persistent().get(&DataKey::Admin).unwrap()
     }
 
+    pub fn save_admin_change(e: &Env, admin_change: &AdminChange) {
+        e.storage()
+            .persistent()
+            .set(&DataKey::AdminChange, admin_change)
+    }
+
+    pub fn get_admin_change(e: &Env) -> Option<AdminChange> {
+        e.storage().persistent().get(&DataKey::AdminChange)
+    }
+
+    pub fn remove_admin_change(e: &Env) {
+        e.storage().persistent().remove(&DataKey::AdminChange)
+    }
+
     pub fn init_total_staked(e: &Env) {
         e.storage().persistent().set(&DataKey::TotalStaked, &0i128);
     }
@@ -44,6 +44,7 @@ fn initialize_staking_contract() {
                 manager,
                 owner,
                 max_complexity: 7,
+                paused: false,
             }
         }
     );
@@ -396,3 +397,173 @@ fn initialize_staking_contract_should_panic_when_max_complexity_invalid() {
         &0u32,
     );
 }
+
+#[test]
+fn pause_blocks_bonding_but_allows_unbonding() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let lp_token = deploy_token_contract(&env, &admin);
+    let manager = Address::generate(&env);
+    let owner = Address::generate(&env);
+
+    let staking = deploy_staking_contract(
+        &env,
+        admin.clone(),
+        &lp_token.address,
+        &manager,
+        &owner,
+        &DEFAULT_COMPLEXITY,
+    );
+
+    lp_token.mint(&user, &20_000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2_000;
+    });
+    staking.bond(&user, &10_000);
+
+    staking.pause(&admin);
+    assert!(staking.query_config().config.paused);
+
+    assert!(staking.try_bond(&user, &10_000).is_err());
+
+    // unbonding is still possible while paused
+    staking.unbond(&user, &10_000, &2_000);
+    assert_eq!(lp_token.balance(&user), 20_000);
+    assert_eq!(staking.query_total_staked(), 0);
+
+    // the owner is allowed to resume bonding as well
+    staking.unpause(&owner);
+    assert!(!staking.query_config().config.paused);
+
+    staking.bond(&user, &10_000);
+    assert_eq!(staking.query_total_staked(), 10_000);
+}
+
+#[test]
+#[should_panic = "Stake: Bond: Contract is paused"]
+fn bond_when_paused_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let lp_token = deploy_token_contract(&env, &admin);
+    let manager = Address::generate(&env);
+    let owner = Address::generate(&env);
+
+    let staking = deploy_staking_contract(
+        &env,
+        admin.clone(),
+        &lp_token.address,
+        &manager,
+        &owner,
+        &DEFAULT_COMPLEXITY,
+    );
+
+    lp_token.mint(&user, &10_000);
+
+    staking.pause(&owner);
+    staking.bond(&user, &10_000);
+}
+
+#[test]
+#[should_panic = "Stake: Pause/Unpause: Only the admin or the owner can pause or unpause the contract"]
+fn pause_by_unauthorized_user_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let lp_token = deploy_token_contract(&env, &admin);
+    let manager = Address::generate(&env);
+    let owner = Address::generate(&env);
+
+    let staking = deploy_staking_contract(
+        &env,
+        admin.clone(),
+        &lp_token.address,
+        &manager,
+        &owner,
+        &DEFAULT_COMPLEXITY,
+    );
+
+    staking.pause(&manager);
+}
+
+#[test]
+fn propose_and_accept_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let lp_token = deploy_token_contract(&env, &admin);
+
+    let staking = deploy_staking_contract(
+        &env,
+        admin.clone(),
+        &lp_token.address,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &DEFAULT_COMPLEXITY,
+    );
+
+    staking.propose_admin(&admin, &new_admin, &1_000);
+    assert_eq!(staking.query_admin(), admin);
+
+    staking.accept_admin(&new_admin);
+    assert_eq!(staking.query_admin(), new_admin);
+}
+
+#[test]
+#[should_panic = "Stake: Accept admin: Admin change proposal has expired"]
+fn accept_expired_admin_proposal_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let lp_token = deploy_token_contract(&env, &admin);
+
+    let staking = deploy_staking_contract(
+        &env,
+        admin.clone(),
+        &lp_token.address,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &DEFAULT_COMPLEXITY,
+    );
+
+    staking.propose_admin(&admin, &new_admin, &1_000);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1_001;
+    });
+
+    staking.accept_admin(&new_admin);
+}
+
+#[test]
+#[should_panic = "Stake: Propose admin: Only the admin can propose a new admin"]
+fn propose_admin_by_non_admin_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let lp_token = deploy_token_contract(&env, &admin);
+
+    let staking = deploy_staking_contract(
+        &env,
+        admin.clone(),
+        &lp_token.address,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &DEFAULT_COMPLEXITY,
+    );
+
+    staking.propose_admin(&user, &user, &1_000);
+}
@@ -1,9 +1,11 @@
 use soroban_sdk::{contract, contractimpl, contractmeta, log, panic_with_error, Address, Env, Vec};
 
 use curve::Curve;
+use phoenix::utils::AdminChange;
 
 use crate::storage::{
-    get_admin, get_token_info, save_max_vesting_complexity, save_token_info, DistributionInfo,
+    get_admin, get_admin_change, get_token_info, remove_admin_change, save_admin_change,
+    save_max_vesting_complexity, save_token_info, DistributionInfo,
 };
 use crate::utils::{create_vesting_accounts, verify_vesting_and_update_balances};
 use crate::{
@@ -73,6 +75,16 @@ pub trait VestingTrait {
 
     fn update_minter_capacity(env: Env, sender: Address, new_capacity: u128);
 
+    // Allows the admin to propose `new_admin` as the next admin. The proposal has to be accepted
+    // by `new_admin` before the ledger timestamp passes `expires_at`
+    fn propose_admin(env: Env, sender: Address, new_admin: Address, expires_at: u64);
+
+    // Allows the proposed admin to accept the admin role
+    fn accept_admin(env: Env, sender: Address);
+
+    // Allows the admin to cancel the pending admin proposal
+    fn cancel_admin_proposal(env: Env, sender: Address);
+
     fn query_balance(env: Env, address: Address) -> i128;
 
     fn query_distribution_info(env: Env, address: Address) -> DistributionInfo;
@@ -510,6 +522,92 @@ impl VestingTrait for Vesting {
         );
     }
 
+    fn propose_admin(env: Env, sender: Address, new_admin: Address, expires_at: u64) {
+        sender.require_auth();
+
+        if sender != get_admin(&env) {
+            log!(
+                &env,
+                "Vesting: Propose admin: Only the admin can propose a new admin"
+            );
+            panic_with_error!(&env, ContractError::NotAuthorized);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            log!(
+                &env,
+                "Vesting: Propose admin: Proposal expiration must be in the future"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeExpired);
+        }
+
+        save_admin_change(
+            &env,
+            &AdminChange {
+                new_admin: new_admin.clone(),
+                expires_at,
+            },
+        );
+
+        env.events()
+            .publish(("propose_admin", "new_admin"), new_admin);
+        env.events()
+            .publish(("propose_admin", "expires_at"), expires_at);
+    }
+
+    fn accept_admin(env: Env, sender: Address) {
+        sender.require_auth();
+
+        let admin_change = get_admin_change(&env).unwrap_or_else(|| {
+            log!(&env, "Vesting: Accept admin: No admin change in place");
+            panic_with_error!(&env, ContractError::AdminChangeNotFound);
+        });
+        if sender != admin_change.new_admin {
+            log!(
+                &env,
+                "Vesting: Accept admin: Only the proposed admin can accept the admin role"
+            );
+            panic_with_error!(&env, ContractError::NotAuthorized);
+        }
+        if env.ledger().timestamp() > admin_change.expires_at {
+            log!(
+                &env,
+                "Vesting: Accept admin: Admin change proposal has expired"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeExpired);
+        }
+
+        remove_admin_change(&env);
+        save_admin(&env, &sender);
+
+        env.events().publish(("accept_admin", "new_admin"), sender);
+    }
+
+    fn cancel_admin_proposal(env: Env, sender: Address) {
+        sender.require_auth();
+
+        if sender != get_admin(&env) {
+            log!(
+                &env,
+                "Vesting: Cancel admin proposal: Only the admin can cancel the proposal"
+            );
+            panic_with_error!(&env, ContractError::NotAuthorized);
+        }
+
+        let admin_change = get_admin_change(&env).unwrap_or_else(|| {
+            log!(
+                &env,
+                "Vesting: Cancel admin proposal: No admin change in place"
+            );
+            panic_with_error!(&env, ContractError::AdminChangeNotFound);
+        });
+        remove_admin_change(&env);
+
+        env.events().publish(
+            ("cancel_admin_proposal", "new_admin"),
+            admin_change.new_admin,
+        );
+    }
+
     fn query_balance(env: Env, address: Address) -> i128 {
         token_contract::Client::new(&env, &get_token_info(&env).address).balance(&address)
     }
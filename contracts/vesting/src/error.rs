@@ -32,6 +32,8 @@ pub enum ContractError {
     NoAddressesToAdd = 24,
     NoEnoughtTokensToStart = 25,
     NotEnoughBalance = 26,
+    AdminChangeNotFound = 27,
+    AdminChangeExpired = 28,
 }
 
 impl From<CurveError> for ContractError {
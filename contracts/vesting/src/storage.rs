@@ -1,4 +1,5 @@
 use curve::{Curve, SaturatingLinear};
+use phoenix::utils::AdminChange;
 use soroban_sdk::{
     contracttype, log, panic_with_error, Address, ConversionError, Env, String, TryFromVal, Val,
 };
@@ -22,6 +23,7 @@ pub enum DataKey {
     Whitelist = 4,
     VestingTokenInfo = 5,
     MaxVestingComplexity = 6,
+    AdminChange = 7,
 }
 
 #[contracttype]
@@ -93,6 +95,20 @@ pub fn get_admin(env: &Env) -> Address {
         })
 }
 
+pub fn save_admin_change(env: &Env, admin_change: &AdminChange) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::AdminChange, admin_change);
+}
+
+pub fn get_admin_change(env: &Env) -> Option<AdminChange> {
+    env.storage().persistent().get(&DataKey::AdminChange)
+}
+
+pub fn remove_admin_change(env: &Env) {
+    env.storage().persistent().remove(&DataKey::AdminChange);
+}
+
 pub fn save_vesting(env: &Env, address: &Address, vesting_info: &VestingInfo) {
     env.storage().persistent().set(address, vesting_info);
 }
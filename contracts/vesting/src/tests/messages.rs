@@ -722,3 +722,102 @@ fn test_should_fail_when_burning_more_than_the_user_has() {
     // vester1 tries to burn 121 tokens
     vesting_client.burn(&vester1, &121);
 }
+
+#[test]
+fn propose_and_accept_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let vester1 = Address::generate(&env);
+
+    let token = deploy_token_contract(&env, &admin);
+    token.mint(&admin, &120);
+
+    let vesting_token = VestingTokenInfo {
+        name: String::from_str(&env, "Phoenix"),
+        symbol: String::from_str(&env, "PHO"),
+        decimals: 6,
+        address: token.address.clone(),
+    };
+
+    let vesting_balances = vec![
+        &env,
+        VestingBalance {
+            rcpt_address: vester1.clone(),
+            distribution_info: DistributionInfo {
+                start_timestamp: 15,
+                end_timestamp: 60,
+                amount: 120,
+            },
+        },
+    ];
+
+    let minter_info = MinterInfo {
+        address: Address::generate(&env),
+        mint_capacity: 500,
+    };
+
+    let vesting_client = instantiate_vesting_client(&env);
+    vesting_client.initialize(
+        &admin,
+        &vesting_token,
+        &vesting_balances,
+        &Some(minter_info),
+        &10u32,
+    );
+
+    vesting_client.propose_admin(&admin, &new_admin, &1_000);
+    vesting_client.accept_admin(&new_admin);
+
+    assert!(vesting_client
+        .try_update_minter_capacity(&admin, &1_000)
+        .is_err());
+    vesting_client.update_minter_capacity(&new_admin, &1_000);
+    assert_eq!(vesting_client.query_minter().mint_capacity, 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Vesting: Accept admin: Admin change proposal has expired")]
+fn accept_expired_admin_proposal_should_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let vester1 = Address::generate(&env);
+
+    let token = deploy_token_contract(&env, &admin);
+    token.mint(&admin, &120);
+
+    let vesting_token = VestingTokenInfo {
+        name: String::from_str(&env, "Phoenix"),
+        symbol: String::from_str(&env, "PHO"),
+        decimals: 6,
+        address: token.address.clone(),
+    };
+
+    let vesting_balances = vec![
+        &env,
+        VestingBalance {
+            rcpt_address: vester1.clone(),
+            distribution_info: DistributionInfo {
+                start_timestamp: 15,
+                end_timestamp: 60,
+                amount: 120,
+            },
+        },
+    ];
+
+    let vesting_client = instantiate_vesting_client(&env);
+    vesting_client.initialize(&admin, &vesting_token, &vesting_balances, &None, &10u32);
+
+    vesting_client.propose_admin(&admin, &new_admin, &1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_001);
+
+    vesting_client.accept_admin(&new_admin);
+}
@@ -1,5 +1,5 @@
 use decimal::Decimal;
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracttype, Address, Vec};
 
 // Validate if int value is bigger then 0
 #[macro_export]
@@ -67,6 +67,28 @@ pub struct LiquidityPoolInitInfo {
     pub stake_init_info: StakeInitInfo,
 }
 
+/// Initialization parameters of a stable pool, which can hold between two and four `tokens`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StableLiquidityPoolInitInfo {
+    pub admin: Address,
+    pub swap_fee_bps: i64,
+    pub fee_recipient: Address,
+    pub max_allowed_slippage_bps: i64,
+    pub max_allowed_spread_bps: i64,
+    pub tokens: Vec<Address>,
+    pub stake_init_info: StakeInitInfo,
+}
+
+/// Pending change of a contract admin, which has to be accepted by `new_admin` before the ledger
+/// timestamp passes `expires_at`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminChange {
+    pub new_admin: Address,
+    pub expires_at: u64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;